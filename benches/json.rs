@@ -0,0 +1,87 @@
+//! Compares the throughput of the JSON backends.
+//!
+//! The `serde_json` and `sonic-rs` benchmarks call each library directly,
+//! which is the difference the `json-sonic-rs` feature makes. The `codec`
+//! benchmarks go through [`Codec`] to show its overhead on top of the backend.
+//! The dev-dependencies enable `arbitrary-precision`, so `Codec` uses
+//! `serde_json` here.
+//!
+//! Run with `cargo bench --bench json`.
+
+use axum_codec::Codec;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+#[axum_codec::apply(encode, decode)]
+#[derive(Clone)]
+struct Order {
+	id: u64,
+	customer: String,
+	note: Option<String>,
+	paid: bool,
+	lines: Vec<Line>,
+}
+
+#[axum_codec::apply(encode, decode)]
+#[derive(Clone)]
+struct Line {
+	sku: String,
+	quantity: u32,
+	price: f64,
+}
+
+fn orders() -> Vec<Order> {
+	(0..1_000)
+		.map(|id| Order {
+			id,
+			customer: format!("customer-{id}"),
+			note: (id % 3 == 0).then(|| "leave at the \"front\" door".into()),
+			paid: id % 2 == 0,
+			lines: (0..5)
+				.map(|n| Line {
+					sku: format!("SKU-{id:05}-{n}"),
+					quantity: n + 1,
+					#[allow(clippy::cast_precision_loss)]
+					price: (id * 100 + u64::from(n)) as f64 / 7.0,
+				})
+				.collect(),
+		})
+		.collect()
+}
+
+fn decode(c: &mut Criterion) {
+	let bytes = serde_json::to_vec(&orders()).unwrap();
+	let mut group = c.benchmark_group("decode");
+
+	group.throughput(Throughput::Bytes(bytes.len() as u64));
+	group.bench_function("serde_json", |b| {
+		b.iter(|| serde_json::from_slice::<Vec<Order>>(black_box(&bytes)).unwrap());
+	});
+	group.bench_function("sonic-rs", |b| {
+		b.iter(|| sonic_rs::from_slice::<Vec<Order>>(black_box(&bytes)).unwrap());
+	});
+	group.bench_function("codec", |b| {
+		b.iter(|| Codec::<Vec<Order>>::from_json(black_box(&bytes)).unwrap());
+	});
+	group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+	let orders = orders();
+	let len = serde_json::to_vec(&orders).unwrap().len();
+	let mut group = c.benchmark_group("encode");
+
+	group.throughput(Throughput::Bytes(len as u64));
+	group.bench_function("serde_json", |b| {
+		b.iter(|| serde_json::to_vec(black_box(&orders)).unwrap());
+	});
+	group.bench_function("sonic-rs", |b| {
+		b.iter(|| sonic_rs::to_vec(black_box(&orders)).unwrap());
+	});
+	group.bench_function("codec", |b| {
+		b.iter(|| Codec(black_box(&orders)).to_json().unwrap());
+	});
+	group.finish();
+}
+
+criterion_group!(benches, decode, encode);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+//! Round-trips `apply`-annotated types through whichever single codec is
+//! enabled. See `Cargo.toml` for the feature combinations this is checked with.
+
+use axum_codec::{Codec, CodecDecode, CodecEncode, ContentType};
+
+#[axum_codec::apply(encode, decode)]
+#[derive(Debug, PartialEq)]
+struct User {
+	id: u32,
+	#[codec(rename = "userName")]
+	name: String,
+	#[codec(default)]
+	tags: Vec<String>,
+	role: Role,
+}
+
+#[axum_codec::apply(encode, decode)]
+#[derive(Debug, PartialEq)]
+enum Role {
+	Guest,
+	Member(u64),
+	Admin { level: u8 },
+}
+
+#[axum_codec::apply(encode, decode)]
+#[derive(Debug, PartialEq)]
+struct Pair(u16, Option<bool>);
+
+#[axum_codec::apply(encode)]
+struct Greeting<'a> {
+	message: &'a str,
+}
+
+fn roundtrip<T>(value: T, content_type: ContentType)
+where
+	T: CodecEncode + CodecDecode + std::fmt::Debug + PartialEq,
+{
+	let codec = Codec(value);
+	let bytes = codec.to_bytes(content_type).unwrap();
+	let decoded = Codec::<T>::from_bytes(&bytes, content_type)
+		.unwrap()
+		.into_inner();
+
+	assert_eq!(codec.into_inner(), decoded);
+}
+
+fn main() {
+	let content_type = ContentType::default();
+
+	roundtrip(
+		User {
+			id: 1,
+			name: "Alice".into(),
+			tags: vec!["admin".into()],
+			role: Role::Admin { level: 3 },
+		},
+		content_type,
+	);
+	roundtrip(Role::Member(42), content_type);
+	roundtrip(Role::Guest, content_type);
+	roundtrip(Pair(7, Some(true)), content_type);
+
+	let greeting = Codec(Greeting { message: "hello" })
+		.to_bytes(content_type)
+		.unwrap();
+
+	println!("round-tripped every type as {}", content_type.as_str());
+	println!("greeting: {} bytes", greeting.len());
+}
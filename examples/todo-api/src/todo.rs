@@ -7,7 +7,7 @@ use axum::{
 };
 use axum_codec::{
 	routing::{delete, get, patch, post},
-	Codec, IntoCodecResponse,
+	Codec, CodecOrDefault, IntoCodecResponse,
 };
 
 pub fn routes() -> ApiRouter {
@@ -28,13 +28,16 @@ pub struct TodoHandle {
 	inner: Todo,
 }
 
+#[axum_codec::convert(from = CreateTodo)]
 #[axum_codec::apply(encode)]
 #[derive(Clone)]
 pub struct Todo {
+	#[convert(default)]
 	id: u64,
 	/// A title describing the task to be done.
 	title: String,
 	/// Whether the task has been completed.
+	#[convert(default)]
 	completed: bool,
 }
 
@@ -45,6 +48,7 @@ pub struct CreateTodo {
 }
 
 #[axum_codec::apply(decode)]
+#[derive(Default)]
 pub struct UpdateTodo {
 	/// A title describing the task to be done.
 	title: Option<String>,
@@ -74,8 +78,7 @@ async fn create(
 
 	let todo = Todo {
 		id,
-		completed: false,
-		title: todo.title,
+		..Todo::from(todo)
 	};
 
 	tasks.push(TodoHandle {
@@ -107,7 +110,7 @@ async fn get_one(State(tasks): State<Arc<Tasks>>, Path(id): Path<u64>) -> impl I
 async fn update_one(
 	State(tasks): State<Arc<Tasks>>,
 	Path(id): Path<u64>,
-	Codec(todo): Codec<UpdateTodo>,
+	CodecOrDefault(todo): CodecOrDefault<UpdateTodo>,
 ) -> impl IntoCodecResponse {
 	let mut tasks = tasks.0.lock().unwrap();
 	let handle = match tasks.get_mut(id as usize - 1) {
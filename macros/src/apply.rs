@@ -17,6 +17,12 @@ use syn::{
 struct Args {
 	encode: bool,
 	decode: bool,
+	#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+	default: bool,
+	#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+	skip_none: bool,
+	derives: Vec<Path>,
+	size_hint: Option<syn::Expr>,
 	crate_name: Path,
 }
 
@@ -26,14 +32,21 @@ impl Parse for Args {
 
 		let mut encode = false;
 		let mut decode = false;
+		let mut default = false;
+		let mut skip_none = false;
+		let mut derives = Vec::new();
+		let mut size_hint = None;
 		let mut crate_name = syn::parse_str("axum_codec").expect("failed to parse crate name");
 
 		for meta in options {
 			match meta {
+				Meta::List(list) if list.path.is_ident("derive") => {
+					derives.extend(list.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?);
+				}
 				Meta::List(list) => {
 					return Err(syn::Error::new(
 						list.span(),
-						"expected `encode`, `decode`, or `crate`",
+						"expected `encode`, `decode`, `default`, `skip_none`, `derive(...)`, `size_hint`, or `crate`",
 					))
 				}
 				Meta::Path(path) => {
@@ -51,12 +64,28 @@ impl Parse for Args {
 								"option `decode` is already enabled",
 							))
 						}
+						Some("default") if default => {
+							return Err(syn::Error::new(
+								path.span(),
+								"option `default` is already enabled",
+							))
+						}
+						Some("skip_none") if skip_none => {
+							return Err(syn::Error::new(
+								path.span(),
+								"option `skip_none` is already enabled",
+							))
+						}
 						Some("encode") => encode = true,
 						Some("decode") => decode = true,
+						Some("default") => default = true,
+						Some("skip_none") => skip_none = true,
 						Some(other) => {
 							return Err(syn::Error::new(
 								path.span(),
-								format!("unknown option `{other}`, expected `encode` or `decode`"),
+								format!(
+									"unknown option `{other}`, expected `encode`, `decode`, `default` or `skip_none`"
+								),
 							))
 						}
 						None => {
@@ -67,9 +96,22 @@ impl Parse for Args {
 						}
 					}
 				}
+				Meta::NameValue(name_value) if name_value.path.is_ident("size_hint") => {
+					if size_hint.is_some() {
+						return Err(syn::Error::new(
+							name_value.path.span(),
+							"option `size_hint` is already set",
+						));
+					}
+
+					size_hint = Some(name_value.value);
+				}
 				Meta::NameValue(name_value) => {
 					if !name_value.path.is_ident("crate") {
-						return Err(syn::Error::new(name_value.path.span(), "expected `crate`"));
+						return Err(syn::Error::new(
+							name_value.path.span(),
+							"expected `crate` or `size_hint`",
+						));
 					}
 
 					let path = match name_value.value {
@@ -105,9 +147,34 @@ impl Parse for Args {
 			));
 		}
 
+		if default && !decode {
+			return Err(syn::Error::new(
+				input.span(),
+				"option `default` requires `decode` to be enabled",
+			));
+		}
+
+		if skip_none && !encode {
+			return Err(syn::Error::new(
+				input.span(),
+				"option `skip_none` requires `encode` to be enabled",
+			));
+		}
+
+		if size_hint.is_some() && !encode {
+			return Err(syn::Error::new(
+				input.span(),
+				"option `size_hint` requires `encode` to be enabled",
+			));
+		}
+
 		Ok(Self {
 			encode,
 			decode,
+			default,
+			skip_none,
+			derives,
+			size_hint,
 			crate_name,
 		})
 	}
@@ -122,6 +189,14 @@ pub fn apply(
 	let crate_name = &args.crate_name;
 	let mut tokens = TokenStream::default();
 
+	if !args.derives.is_empty() {
+		let derives = &args.derives;
+
+		tokens.extend(quote::quote! {
+			#[derive(#(#derives),*)]
+		});
+	}
+
 	#[cfg(feature = "serde")]
 	{
 		if args.encode {
@@ -141,6 +216,12 @@ pub fn apply(
 		tokens.extend(quote! {
 			#[serde(crate = #crate_)]
 		});
+
+		if args.default {
+			tokens.extend(quote! {
+				#[serde(default)]
+			});
+		}
 	}
 
 	#[cfg(feature = "bincode")]
@@ -195,15 +276,657 @@ pub fn apply(
 		});
 	}
 
-	// TODO: Implement #[validate(crate = "...")]
-	// For now, use the real crate name so the error is nicer.
+	let mut input = TokenStream::from(input);
+	let mut item = syn::parse2::<syn::DeriveInput>(input.clone()).ok();
+
+	if let Some(item) = &mut item {
+		if let Err(err) = check_field_types(item).and_then(|()| expand_field_attrs(item, &args)) {
+			let err = err.into_compile_error();
+
+			return quote::quote! { #input #err }.into();
+		}
+
+		input = quote::quote! { #item };
+	}
+
+	// `validator` can only derive `Validate` for structs with named fields, so
+	// other shapes (tuple structs, unit structs and enums) get an impl that
+	// validates each field whose type implements `Validate`.
 	#[cfg(feature = "validator")]
-	if args.decode {
-		tokens.extend(quote! {
-			#[derive(validator::Validate)]
-		});
+	let mut validate_impl = TokenStream::default();
+
+	#[cfg(feature = "validator")]
+	if args.decode || (cfg!(feature = "validate-responses") && args.encode) {
+		match &item {
+			Some(item) if !has_named_fields(item) => {
+				validate_impl.extend(validate_fields(item, crate_name));
+			}
+			// TODO: Implement #[validate(crate = "...")]
+			// For now, use the real crate name so the error is nicer.
+			_ => tokens.extend(quote! {
+				#[derive(validator::Validate)]
+			}),
+		}
+	}
+
+	tokens.extend(input);
+
+	#[cfg(feature = "validator")]
+	tokens.extend(validate_impl);
+
+	if let Some((item, size_hint)) = item.as_ref().zip(args.size_hint.as_ref()) {
+		tokens.extend(impl_size_hint(item, size_hint, crate_name));
+	}
+
+	#[cfg(any(feature = "bincode", feature = "bitcode"))]
+	if let Some(item) = &item {
+		tokens.extend(assert_field_formats(item, &args));
+	}
+
+	#[cfg(all(feature = "serde", any(feature = "bincode", feature = "bitcode")))]
+	if let Some(item) = &item {
+		tokens.extend(warn_serde_with(item));
 	}
 
-	tokens.extend(TokenStream::from(input));
 	tokens.into()
 }
+
+/// Implements `SizeHint` for the item, returning the given expression (which
+/// can use `self`) for every format.
+fn impl_size_hint(item: &syn::DeriveInput, size_hint: &syn::Expr, crate_name: &Path) -> TokenStream {
+	let ident = &item.ident;
+	let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+	quote::quote! {
+		impl #impl_generics #crate_name::SizeHint for #ident #ty_generics #where_clause {
+			#[inline]
+			fn size_hint(&self, _: #crate_name::ContentType) -> ::core::option::Option<usize> {
+				::core::option::Option::Some(#size_hint)
+			}
+		}
+	}
+}
+
+/// Returns `true` if the item is a struct with named fields, which is the only
+/// shape supported by `#[derive(validator::Validate)]`.
+#[cfg(feature = "validator")]
+fn has_named_fields(item: &syn::DeriveInput) -> bool {
+	matches!(
+		&item.data,
+		syn::Data::Struct(syn::DataStruct {
+			fields: syn::Fields::Named(..),
+			..
+		})
+	)
+}
+
+/// Implements `Validate` for shapes that `#[derive(validator::Validate)]` does
+/// not support by validating every field whose type implements `Validate`,
+/// keyed by its name or index.
+///
+/// Whether a field implements `Validate` is decided with autoref
+/// specialization, so fields whose type is a generic parameter are skipped.
+#[cfg(feature = "validator")]
+fn validate_fields(item: &syn::DeriveInput, crate_name: &Path) -> TokenStream {
+	fn bindings(fields: &syn::Fields) -> (TokenStream, Vec<(syn::Ident, String)>) {
+		let names = fields
+			.iter()
+			.enumerate()
+			.map(|(index, field)| match &field.ident {
+				Some(ident) => (ident.clone(), ident.to_string()),
+				None => (quote::format_ident!("__field{index}"), index.to_string()),
+			})
+			.collect::<Vec<_>>();
+		let idents = names.iter().map(|(ident, _)| ident);
+
+		let pattern = match fields {
+			syn::Fields::Named(..) => quote! { { #(#idents),* } },
+			syn::Fields::Unnamed(..) => quote! { ( #(#idents),* ) },
+			syn::Fields::Unit => TokenStream::default(),
+		};
+
+		(pattern, names)
+	}
+
+	let arms = match &item.data {
+		syn::Data::Struct(data) => vec![(quote! { Self }, &data.fields)],
+		syn::Data::Enum(data) => data
+			.variants
+			.iter()
+			.map(|variant| {
+				let ident = &variant.ident;
+
+				(quote! { Self::#ident }, &variant.fields)
+			})
+			.collect(),
+		syn::Data::Union(..) => Vec::new(),
+	};
+
+	let arms = arms.into_iter().map(|(path, fields)| {
+		let (pattern, names) = bindings(fields);
+		let checks = names.iter().map(|(ident, name)| {
+			quote! {
+				errors.merge_self(#name, (&#crate_name::__private::validate::Field(#ident)).validate_field());
+			}
+		});
+
+		quote! {
+			#path #pattern => {
+				#(#checks)*
+			}
+		}
+	});
+
+	let name = &item.ident;
+	let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+	quote! {
+		impl #impl_generics #crate_name::__private::validator::Validate for #name #ty_generics #where_clause {
+			#[allow(unused_mut, unused_imports, unreachable_code)]
+			fn validate(&self) -> ::core::result::Result<(), #crate_name::__private::validator::ValidationErrors> {
+				use #crate_name::__private::validate::{SkipField as _, ValidateField as _};
+
+				let mut errors = #crate_name::__private::validator::ValidationErrors::new();
+
+				match self {
+					#(#arms)*
+				}
+
+				if errors.is_empty() {
+					::core::result::Result::Ok(())
+				} else {
+					::core::result::Result::Err(errors)
+				}
+			}
+		}
+	}
+}
+
+/// Replaces `#[codec(...)]` field attributes with the equivalent attributes
+/// for each enabled format.
+///
+/// Formats that are not self-describing (Bincode and Bitcode) always encode
+/// every field, so they ignore `#[codec(default)]`. They already encode bytes
+/// and integers compactly, so `#[codec(bytes)]` and `#[codec(json_bignum)]`
+/// only change the `serde` representation.
+///
+/// With the `skip_none` option, named `Option` fields are also given
+/// `#[serde(skip_serializing_if = "...")]` unless they already control when
+/// they are skipped. Fields of tuple structs and tuple variants are left
+/// alone, since they are decoded by position.
+fn expand_field_attrs(item: &mut syn::DeriveInput, args: &Args) -> syn::Result<()> {
+	#[cfg(not(feature = "serde"))]
+	let _ = args;
+
+	let fields: Vec<&mut syn::Field> = match &mut item.data {
+		syn::Data::Struct(data) => data.fields.iter_mut().collect(),
+		syn::Data::Enum(data) => data
+			.variants
+			.iter_mut()
+			.flat_map(|variant| variant.fields.iter_mut())
+			.collect(),
+		syn::Data::Union(data) => data.fields.named.iter_mut().collect(),
+	};
+
+	for field in fields {
+		let mut attrs = Vec::with_capacity(field.attrs.len());
+
+		for attr in field.attrs.drain(..) {
+			if !attr.path().is_ident("codec") {
+				attrs.push(attr);
+				continue;
+			}
+
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("default") {
+					#[cfg(feature = "serde")]
+					attrs.push(syn::parse_quote! { #[serde(default)] });
+
+					Ok(())
+				} else if meta.path.is_ident("rename") {
+					let name = meta.value()?.parse::<syn::LitStr>()?;
+
+					#[cfg(feature = "serde")]
+					attrs.push(syn::parse_quote! { #[serde(rename = #name)] });
+					#[cfg(feature = "aide")]
+					attrs.push(syn::parse_quote! { #[schemars(rename = #name)] });
+					#[cfg(not(any(feature = "serde", feature = "aide")))]
+					let _ = name;
+
+					Ok(())
+				} else if meta.path.is_ident("bytes") {
+					#[cfg(feature = "serde")]
+					{
+						let with = format!("{}::__private::bytes", args.crate_name.to_token_stream());
+
+						attrs.push(syn::parse_quote! { #[serde(with = #with)] });
+					}
+					#[cfg(feature = "aide")]
+					attrs.push(syn::parse_quote! { #[schemars(with = "String")] });
+
+					Ok(())
+				} else if meta.path.is_ident("json_bignum") {
+					#[cfg(feature = "serde")]
+					{
+						let with = format!("{}::__private::bignum", args.crate_name.to_token_stream());
+
+						attrs.push(syn::parse_quote! { #[serde(with = #with)] });
+					}
+					#[cfg(feature = "aide")]
+					attrs.push(syn::parse_quote! { #[schemars(with = "String")] });
+
+					Ok(())
+				} else if meta.path.is_ident("allow_datetime") {
+					// Only read by `check_field_types`.
+					Ok(())
+				} else if meta.path.is_ident("schema_skip") {
+					#[cfg(feature = "aide")]
+					attrs.push(syn::parse_quote! { #[schemars(skip)] });
+
+					Ok(())
+				} else {
+					Err(meta.error(
+						"unknown codec attribute, expected `default`, `rename`, `bytes`, `json_bignum`, \
+						 `schema_skip` or `allow_datetime`",
+					))
+				}
+			})?;
+		}
+
+		#[cfg(feature = "serde")]
+		if args.skip_none && field.ident.is_some() && is_option(&field.ty) && !has_serde_skip(&attrs) {
+			attrs.push(syn::parse_quote! {
+				#[serde(skip_serializing_if = "::core::option::Option::is_none")]
+			});
+		}
+
+		field.attrs = attrs;
+	}
+
+	Ok(())
+}
+
+/// Returns `true` if the type is written as `Option<T>`, which is the same
+/// check `serde` uses to decide that a missing field decodes to `None`.
+#[cfg(feature = "serde")]
+fn is_option(ty: &syn::Type) -> bool {
+	let syn::Type::Path(ty) = ty else {
+		return false;
+	};
+
+	ty.qself.is_none()
+		&& ty.path.segments.last().is_some_and(|segment| {
+			segment.ident == "Option"
+				&& matches!(segment.arguments, syn::PathArguments::AngleBracketed(..))
+		})
+}
+
+/// Returns `true` if a `#[serde(...)]` attribute already decides when the
+/// field is skipped during serialization.
+#[cfg(feature = "serde")]
+fn has_serde_skip(attrs: &[syn::Attribute]) -> bool {
+	attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("serde"))
+		.filter_map(|attr| {
+			attr
+				.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+				.ok()
+		})
+		.flatten()
+		.any(|meta| {
+			let path = meta.path();
+
+			path.is_ident("skip")
+				|| path.is_ident("skip_serializing")
+				|| path.is_ident("skip_serializing_if")
+		})
+}
+
+/// Asserts that every field type can be encoded and decoded by each enabled
+/// binary format.
+///
+/// The derives for Bincode and Bitcode already require this, but their errors
+/// point into the generated code. These assertions are spanned to the field
+/// type and use the `on_unimplemented` messages from `__private`, so the
+/// offending field is obvious. Generic items are skipped, since their field
+/// types are only known once the item is used, as are fields with a
+/// `#[bincode(...)]` or `#[bitcode(...)]` attribute for that format, since
+/// options such as `#[bincode(with_serde)]` change which traits are required.
+#[cfg(any(feature = "bincode", feature = "bitcode"))]
+fn assert_field_formats(item: &syn::DeriveInput, args: &Args) -> TokenStream {
+	if !item.generics.params.is_empty() {
+		return TokenStream::default();
+	}
+
+	let fields: Vec<&syn::Field> = match &item.data {
+		syn::Data::Struct(data) => data.fields.iter().collect(),
+		syn::Data::Enum(data) => data
+			.variants
+			.iter()
+			.flat_map(|variant| variant.fields.iter())
+			.collect(),
+		syn::Data::Union(..) => return TokenStream::default(),
+	};
+
+	let crate_name = &args.crate_name;
+	let mut markers = Vec::new();
+
+	#[cfg(feature = "bincode")]
+	{
+		if args.encode {
+			markers.push(("bincode", quote! { BincodeEncode }));
+		}
+
+		if args.decode {
+			markers.push(("bincode", quote! { BincodeDecode }));
+		}
+	}
+
+	#[cfg(feature = "bitcode")]
+	{
+		if args.encode {
+			markers.push(("bitcode", quote! { BitcodeEncode }));
+		}
+
+		if args.decode {
+			markers.push(("bitcode", quote! { BitcodeDecode }));
+		}
+	}
+
+	let assertions = markers
+		.iter()
+		.flat_map(|(format, marker)| {
+			fields
+				.iter()
+				.filter(move |field| !field.attrs.iter().any(|attr| attr.path().is_ident(format)))
+				.map(move |field| {
+					let ty = &field.ty;
+
+					quote::quote_spanned! {ty.span()=>
+						{
+							fn assert<T: ?Sized + #crate_name::__private::#marker>() {}
+							assert::<#ty>();
+						}
+					}
+				})
+		})
+		.collect::<Vec<_>>();
+
+	if assertions.is_empty() {
+		return TokenStream::default();
+	}
+
+	quote! {
+		const _: () = {
+			#[allow(dead_code)]
+			fn check() {
+				#(#assertions)*
+			}
+		};
+	}
+}
+
+/// Warns about `#[serde(with = "...")]` (and `serialize_with` or
+/// `deserialize_with`) on fields and variants, since Bincode and Bitcode
+/// ignore it and would encode the field differently from the `serde` formats.
+///
+/// Procedural macros cannot emit warnings on stable, so each occurrence uses a
+/// deprecated item spanned to the attribute instead.
+#[cfg(all(feature = "serde", any(feature = "bincode", feature = "bitcode")))]
+fn warn_serde_with(item: &syn::DeriveInput) -> TokenStream {
+	let attrs: Vec<&syn::Attribute> = match &item.data {
+		syn::Data::Struct(data) => data.fields.iter().flat_map(|field| &field.attrs).collect(),
+		syn::Data::Enum(data) => data
+			.variants
+			.iter()
+			.flat_map(|variant| {
+				variant
+					.attrs
+					.iter()
+					.chain(variant.fields.iter().flat_map(|field| &field.attrs))
+			})
+			.collect(),
+		syn::Data::Union(data) => data
+			.fields
+			.named
+			.iter()
+			.flat_map(|field| &field.attrs)
+			.collect(),
+	};
+
+	let formats = match (cfg!(feature = "bincode"), cfg!(feature = "bitcode")) {
+		(true, true) => "Bincode and Bitcode ignore it",
+		(true, false) => "Bincode ignores it",
+		_ => "Bitcode ignores it",
+	};
+
+	let mut warnings = TokenStream::default();
+
+	for attr in attrs
+		.into_iter()
+		.filter(|attr| attr.path().is_ident("serde"))
+	{
+		let Ok(metas) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+			continue;
+		};
+
+		for meta in metas {
+			let path = meta.path();
+
+			let Some(name) = ["with", "serialize_with", "deserialize_with"]
+				.into_iter()
+				.find(|name| path.is_ident(name))
+			else {
+				continue;
+			};
+
+			if is_codec_with(&meta) {
+				continue;
+			}
+
+			let note = format!(
+				"`#[serde({name} = \"...\")]` only applies to serde formats. {formats}, so the value is \
+				 encoded differently depending on the negotiated format. Prefer a newtype that implements \
+				 the traits for every format."
+			);
+
+			warnings.extend(quote::quote_spanned! {path.span()=>
+				{
+					#[deprecated(note = #note)]
+					struct SerdeWith;
+
+					let _ = SerdeWith;
+				}
+			});
+		}
+	}
+
+	if warnings.is_empty() {
+		return warnings;
+	}
+
+	quote! {
+		const _: () = {
+			#warnings
+		};
+	}
+}
+
+/// Returns `true` if the meta is the `#[serde(with = "...")]` added for
+/// `#[codec(bytes)]` or `#[codec(json_bignum)]`, which match how the binary
+/// formats encode the field.
+#[cfg(all(feature = "serde", any(feature = "bincode", feature = "bitcode")))]
+fn is_codec_with(meta: &Meta) -> bool {
+	let Meta::NameValue(syn::MetaNameValue {
+		value: syn::Expr::Lit(syn::ExprLit {
+			lit: syn::Lit::Str(path),
+			..
+		}),
+		..
+	}) = meta
+	else {
+		return false;
+	};
+
+	let path = path.value();
+
+	path.ends_with("::__private::bytes") || path.ends_with("::__private::bignum")
+}
+
+/// Rejects field types that cannot be represented consistently across all
+/// formats, unless the field is marked with `#[codec(allow_datetime)]`.
+fn check_field_types(item: &syn::DeriveInput) -> syn::Result<()> {
+	use syn::visit::Visit;
+
+	#[derive(Default)]
+	struct Visitor {
+		error: Option<syn::Error>,
+	}
+
+	impl Visitor {
+		fn push(&mut self, error: syn::Error) {
+			match &mut self.error {
+				Some(existing) => existing.combine(error),
+				None => self.error = Some(error),
+			}
+		}
+	}
+
+	impl<'ast> Visit<'ast> for Visitor {
+		fn visit_type_path(&mut self, ty: &'ast syn::TypePath) {
+			// Only paths that name the `toml` crates are checked, since a bare
+			// `Datetime` could be any crate's type (such as `surrealdb::sql::Datetime`).
+			let segments = &ty.path.segments;
+			let is_toml_datetime = ty.qself.is_none()
+				&& segments.len() > 1
+				&& segments
+					.first()
+					.is_some_and(|segment| segment.ident == "toml" || segment.ident == "toml_datetime")
+				&& segments
+					.last()
+					.is_some_and(|segment| segment.ident == "Datetime");
+
+			if is_toml_datetime {
+				self.push(syn::Error::new(
+					ty.span(),
+					"`toml::value::Datetime` only round-trips through TOML and cannot be used with \
+					 `#[axum_codec::apply]`. Store the value as an RFC 3339 `String` or use a \
+					 format-agnostic type such as `chrono::DateTime` instead, or mark the field with \
+					 `#[codec(allow_datetime)]` if it is only exchanged as TOML.",
+				));
+			}
+
+			syn::visit::visit_type_path(self, ty);
+		}
+	}
+
+	let fields: Vec<&syn::Field> = match &item.data {
+		syn::Data::Struct(data) => data.fields.iter().collect(),
+		syn::Data::Enum(data) => data
+			.variants
+			.iter()
+			.flat_map(|variant| variant.fields.iter())
+			.collect(),
+		syn::Data::Union(data) => data.fields.named.iter().collect(),
+	};
+
+	let mut visitor = Visitor::default();
+
+	for field in fields {
+		if !allows_datetime(field) {
+			visitor.visit_type(&field.ty);
+		}
+	}
+
+	visitor.error.map_or(Ok(()), Err)
+}
+
+/// Returns `true` if the field is marked with `#[codec(allow_datetime)]`.
+///
+/// Other `#[codec(...)]` options are skipped here and validated by
+/// [`expand_field_attrs`].
+fn allows_datetime(field: &syn::Field) -> bool {
+	let mut allowed = false;
+
+	for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("codec")) {
+		let _ = attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("allow_datetime") {
+				allowed = true;
+			} else if meta.input.peek(syn::Token![=]) {
+				meta.value()?.parse::<syn::Expr>()?;
+			}
+
+			Ok(())
+		});
+	}
+
+	allowed
+}
+
+#[cfg(test)]
+mod test {
+	use super::check_field_types;
+
+	#[test]
+	fn test_rejects_toml_datetime() {
+		let items: [syn::DeriveInput; 3] = [
+			syn::parse_quote! {
+				struct Event {
+					at: toml::value::Datetime,
+				}
+			},
+			syn::parse_quote! {
+				struct Event {
+					at: ::toml::Datetime,
+				}
+			},
+			syn::parse_quote! {
+				enum Event {
+					Scheduled(Option<toml_datetime::Datetime>),
+				}
+			},
+		];
+
+		for item in &items {
+			assert!(check_field_types(item).is_err());
+		}
+
+		let item: syn::DeriveInput = syn::parse_quote! {
+			struct Event {
+				at: chrono::DateTime<chrono::Utc>,
+				name: String,
+			}
+		};
+
+		assert!(check_field_types(&item).is_ok());
+	}
+
+	#[test]
+	fn test_allows_other_datetimes() {
+		let items: [syn::DeriveInput; 3] = [
+			syn::parse_quote! {
+				struct Event {
+					at: Datetime,
+				}
+			},
+			syn::parse_quote! {
+				struct Event {
+					at: surrealdb::sql::Datetime,
+				}
+			},
+			syn::parse_quote! {
+				enum Event {
+					Scheduled {
+						#[codec(rename = "at", allow_datetime)]
+						at: Option<toml::value::Datetime>,
+					},
+				}
+			},
+		];
+
+		for item in &items {
+			assert!(check_field_types(item).is_ok());
+		}
+	}
+}
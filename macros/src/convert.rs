@@ -0,0 +1,128 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+	parse::{Parse, ParseStream},
+	punctuated::Punctuated,
+	spanned::Spanned,
+	Token,
+};
+
+struct Args {
+	from: Vec<syn::Type>,
+}
+
+impl Parse for Args {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut from = Vec::new();
+
+		while !input.is_empty() {
+			let key = input.parse::<syn::Ident>()?;
+
+			if key != "from" {
+				return Err(syn::Error::new(key.span(), "expected `from`"));
+			}
+
+			input.parse::<Token![=]>()?;
+			from.push(input.parse()?);
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		if from.is_empty() {
+			return Err(syn::Error::new(
+				input.span(),
+				"expected at least one `from = Type`",
+			));
+		}
+
+		Ok(Self { from })
+	}
+}
+
+pub fn convert(
+	attr: proc_macro::TokenStream,
+	input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+	let args = syn::parse_macro_input!(attr as Args);
+	let mut item = syn::parse_macro_input!(input as syn::DeriveInput);
+
+	match expand(&args, &mut item) {
+		Ok(impls) => quote! { #item #impls }.into(),
+		Err(err) => {
+			let err = err.into_compile_error();
+
+			quote! { #item #err }.into()
+		}
+	}
+}
+
+/// Strips the `#[convert(...)]` field attributes from the item and returns a
+/// `From` impl for each source type.
+fn expand(args: &Args, item: &mut syn::DeriveInput) -> syn::Result<TokenStream> {
+	let syn::Data::Struct(syn::DataStruct {
+		fields: syn::Fields::Named(fields),
+		..
+	}) = &mut item.data
+	else {
+		return Err(syn::Error::new(
+			item.ident.span(),
+			"`convert` only supports structs with named fields",
+		));
+	};
+
+	let mut values = Vec::with_capacity(fields.named.len());
+
+	for field in &mut fields.named {
+		let ident = field.ident.clone().expect("named fields have an ident");
+		let mut default = false;
+		let mut attrs = Vec::with_capacity(field.attrs.len());
+
+		for attr in field.attrs.drain(..) {
+			if !attr.path().is_ident("convert") {
+				attrs.push(attr);
+				continue;
+			}
+
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("default") {
+					default = true;
+
+					Ok(())
+				} else {
+					Err(meta.error("unknown convert attribute, expected `default`"))
+				}
+			})?;
+		}
+
+		field.attrs = attrs;
+
+		values.push(if default {
+			quote_spanned! {field.ty.span()=>
+				#ident: ::core::default::Default::default()
+			}
+		} else {
+			quote_spanned! {ident.span()=>
+				#ident: ::core::convert::Into::into(value.#ident)
+			}
+		});
+	}
+
+	let name = &item.ident;
+	let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+	let values = values.into_iter().collect::<Punctuated<_, Token![,]>>();
+
+	let impls = args.from.iter().map(|source| {
+		quote! {
+			impl #impl_generics ::core::convert::From<#source> for #name #ty_generics #where_clause {
+				#[allow(unused_variables)]
+				fn from(value: #source) -> Self {
+					Self { #values }
+				}
+			}
+		}
+	});
+
+	Ok(quote! { #(#impls)* })
+}
@@ -254,7 +254,7 @@ fn check_inputs_impls_from_request(
 	state_ty: Type,
 	kind: FunctionKind,
 ) -> TokenStream {
-	let takes_self = item_fn.sig.inputs.first().map_or(false, |arg| match arg {
+	let takes_self = item_fn.sig.inputs.first().is_some_and(|arg| match arg {
 		FnArg::Receiver(_) => true,
 		FnArg::Typed(typed) => is_self_pat_type(typed),
 	});
@@ -626,10 +626,7 @@ fn request_consuming_type_name(ty: &Type) -> Option<&'static str> {
 }
 
 fn well_known_last_response_type(ty: &Type) -> Option<&'static str> {
-	let typename = match extract_clean_typename(ty) {
-		Some(tn) => tn,
-		None => return None,
-	};
+	let typename = extract_clean_typename(ty)?;
 
 	let type_name = match &*typename {
 		"Json" => "Json<_>",
@@ -891,7 +888,7 @@ fn skip_next_arg(arg: &FnArg, kind: FunctionKind) -> bool {
 						.path
 						.segments
 						.last()
-						.map_or(true, |path_segment| path_segment.ident != "Next")
+						.is_none_or(|path_segment| path_segment.ident != "Next")
 				} else {
 					true
 				}
@@ -14,11 +14,206 @@ use syn::parse::Parse;
 
 mod apply;
 mod attr_parsing;
+mod convert;
 mod debug_handler;
 mod with_position;
 
 /// A utility macro for automatically deriving the correct traits
 /// depending on the enabled features.
+///
+/// Structs with named fields, tuple structs (including newtypes), unit structs
+/// and enums are all supported. Note that `validator` can only derive
+/// `Validate` for structs with named fields, so other shapes are validated by
+/// running `Validate` on each field whose type implements it (fields whose type
+/// is a generic parameter are not validated), and that TOML
+/// can only represent types that serialize to a table (such as a newtype around
+/// a struct with named fields).
+///
+/// # Additional derives
+///
+/// Other derives can be passed along with `derive(...)`, which is useful for
+/// types that are also used as map or cache keys:
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode, derive(Clone, Hash, PartialEq, Eq))]
+/// struct CacheKey {
+///   user_id: u64,
+///   page: u32,
+/// }
+/// ```
+///
+/// # Size hints
+///
+/// `size_hint = <expr>` implements `axum_codec::SizeHint` with the given
+/// estimate of the encoded size in bytes, which can use `self`. It is used by
+/// `Codec::to_bytes_hinted` and `Codec::encode_into_hinted` to allocate the
+/// output buffer once:
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, size_hint = 32 + self.body.len())]
+/// struct Message {
+///   id: u64,
+///   body: String,
+/// }
+/// ```
+///
+/// The same estimate is used for every format. For a hint per format,
+/// implement `SizeHint` by hand instead.
+///
+/// # Defaults
+///
+/// Passing `default` (along with `decode`) fills in missing fields from the
+/// type's [`Default`] implementation, and a field can be given its own default
+/// with `#[codec(default)]`:
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode, default)]
+/// #[derive(Default)]
+/// struct Settings {
+///   theme: String,
+///   #[codec(default)]
+///   notifications: bool,
+/// }
+/// ```
+///
+/// Defaults are only applied by self-describing formats (JSON, MessagePack,
+/// CBOR, BSON, YAML, TOML and RON). Bincode and Bitcode always encode every
+/// field, so they never have anything to fill in.
+///
+/// # Omitting `None`
+///
+/// By default, `Option` fields that are `None` are encoded as `null` (or the
+/// format's equivalent). Passing `skip_none` (along with `encode`) leaves them
+/// out instead, by adding `#[serde(skip_serializing_if = "Option::is_none")]`
+/// to every named field whose type is written as `Option<T>`. Fields that
+/// already have a `skip`, `skip_serializing` or `skip_serializing_if`
+/// attribute are left alone, as are the fields of tuple structs and tuple
+/// variants, which are decoded by position.
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode, skip_none)]
+/// struct Profile {
+///   name: String,
+///   // Encoded as `{"name":"..."}` instead of `{"name":"...","bio":null}`.
+///   bio: Option<String>,
+/// }
+/// ```
+///
+/// Missing `Option` fields always decode to `None`, so the same type can read
+/// both shapes. Only the self-describing formats (JSON, MessagePack, CBOR,
+/// BSON, YAML, TOML and RON) are affected. Bincode and Bitcode always encode
+/// every field with a tag for `None`, so their output does not change. TOML has
+/// no `null`, so it omits `None` fields with or without this option.
+///
+/// # Renaming fields
+///
+/// `#[codec(rename = "...")]` renames a field in both the self-describing
+/// formats and the generated `OpenAPI` schema (with the `aide` feature), so
+/// the two cannot drift apart. Bincode and Bitcode do not encode field names,
+/// so they are unaffected.
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode)]
+/// struct User {
+///   #[codec(rename = "userName")]
+///   user_name: String,
+/// }
+/// ```
+///
+/// # Hiding fields from the schema
+///
+/// `#[codec(schema_skip)]` leaves a field out of the generated `OpenAPI`
+/// schema (with the `aide` feature) while still encoding and decoding it in
+/// every format, which is useful for fields that only matter to other
+/// services, such as an internal discriminator.
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode)]
+/// struct Event {
+///   name: String,
+///   #[codec(schema_skip)]
+///   shard: u16,
+/// }
+/// ```
+///
+/// # TOML datetimes
+///
+/// `toml::value::Datetime` only round-trips through TOML, so `apply` rejects
+/// fields that name it (or `toml_datetime::Datetime`) by path. A field that is
+/// only ever exchanged as TOML can opt out with `#[codec(allow_datetime)]`.
+/// Types from other crates that are also called `Datetime` are not affected.
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode)]
+/// struct Config {
+///   #[codec(allow_datetime)]
+///   updated_at: toml::value::Datetime,
+/// }
+/// ```
+///
+/// # Byte fields
+///
+/// By default, `serde` encodes a `Vec<u8>` as a sequence of integers, which is
+/// several times larger than the data in JSON and wastes space in the binary
+/// formats. `#[codec(bytes)]` encodes the field as a standard base64 string in
+/// the text formats (JSON, YAML, TOML and RON) and as a native byte string in
+/// MessagePack, CBOR and BSON. Bincode and Bitcode already encode bytes
+/// compactly, so they are unaffected.
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode)]
+/// struct Upload {
+///   name: String,
+///   // Encoded as `"aGVsbG8="` in JSON instead of `[104,101,108,108,111]`.
+///   #[codec(bytes)]
+///   data: Vec<u8>,
+/// }
+/// ```
+///
+/// Any type that implements `AsRef<[u8]>` and `From<Vec<u8>>` can be used,
+/// such as `Box<[u8]>`, `bytes::Bytes` or `serde_bytes::ByteBuf`, as long as
+/// it also implements the traits of the enabled binary formats. Decoding still
+/// accepts a sequence of integers, so clients that send the default
+/// representation keep working. With the `aide` feature, the field is
+/// documented as a string.
+///
+/// # Large integers
+///
+/// JavaScript parses JSON numbers as `f64`, so integers above 2^53 lose
+/// precision. `#[codec(json_bignum)]` encodes the field as a decimal string in
+/// the text formats (JSON, YAML, TOML and RON) while keeping it a number in
+/// every other format. Decoding accepts both a string and a plain number.
+///
+/// ```ignore
+/// #[axum_codec::apply(encode, decode)]
+/// struct Account {
+///   // Encoded as `"18446744073709551615"` in JSON.
+///   #[codec(json_bignum)]
+///   id: u64,
+/// }
+/// ```
+///
+/// Any integer type that implements `Display` and `FromStr` can be used, such
+/// as `u64` and `i64`. With the `aide` feature, the field is documented as a
+/// string.
+///
+/// # Binary formats
+///
+/// With the `bincode` or `bitcode` features enabled, every field type must
+/// also implement that format's traits. Both derives report missing impls
+/// from inside their generated code, so `apply` additionally checks each
+/// field of non-generic types and reports errors such as
+/// ``"`Cow<'static, str>` cannot be encoded with Bitcode"`` on the field
+/// itself.
+///
+/// Neither format can skip a field, so the fix is to use a supported type
+/// (for example, `String` instead of `Cow<'static, str>`) or to disable the
+/// feature.
+///
+/// Field attributes such as `#[serde(with = "...")]` only change how the
+/// `serde` formats encode a field, so `apply` emits a warning for them when
+/// either binary format is enabled. Wrapping the field in a newtype that
+/// implements the traits for every format keeps the formats consistent.
 #[proc_macro_attribute]
 pub fn apply(
 	attr: proc_macro::TokenStream,
@@ -27,6 +222,48 @@ pub fn apply(
 	apply::apply(attr, input)
 }
 
+/// Generates `From` impls that build a struct from other structs with
+/// matching field names, such as a request DTO.
+///
+/// Each field is taken from the field with the same name in the source and
+/// converted with [`Into`], so a `String` can become a `Box<str>` and so on.
+/// Fields that the source does not have can be marked with
+/// `#[convert(default)]` to use their [`Default`] value instead. Fields of the
+/// source that the struct does not have are dropped.
+///
+/// ```ignore
+/// #[axum_codec::apply(decode)]
+/// struct CreateTodo {
+///   title: String,
+/// }
+///
+/// #[axum_codec::convert(from = CreateTodo)]
+/// #[axum_codec::apply(encode)]
+/// struct Todo {
+///   #[convert(default)]
+///   id: u64,
+///   title: String,
+///   #[convert(default)]
+///   completed: bool,
+/// }
+///
+/// let todo = Todo { id: 1, ..Todo::from(create) };
+/// ```
+///
+/// Multiple sources can be given with `from = A, from = B`. Only structs with
+/// named fields are supported.
+///
+/// Place `convert` above `apply`, so it expands before the derives that
+/// `apply` adds. Some of those derives reject attributes they do not
+/// recognize.
+#[proc_macro_attribute]
+pub fn convert(
+	attr: proc_macro::TokenStream,
+	input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+	convert::convert(attr, input)
+}
+
 /// Generates better error messages when applied to handler functions.
 ///
 /// For more information, see [`axum::debug_handler`](https://docs.rs/axum/latest/axum/attr.debug_handler.html).
@@ -124,6 +361,22 @@ where
 		.cloned()
 }
 
+#[cfg(test)]
+fn run_ui_tests(directory: &str) {
+	#[rustversion::nightly]
+	fn go(directory: &str) {
+		let t = trybuild::TestCases::new();
+
+		t.compile_fail(format!("tests/{directory}/fail/*.rs"));
+		t.pass(format!("tests/{directory}/pass/*.rs"));
+	}
+
+	#[rustversion::not(nightly)]
+	fn go(_directory: &str) {}
+
+	go(directory);
+}
+
 #[doc(hidden)]
 #[proc_macro]
 pub fn __private_decode_trait(input: TokenStream) -> TokenStream {
@@ -292,6 +545,17 @@ mod __private {
 			});
 		}
 
+		#[cfg(feature = "validate-responses")]
+		{
+			if !constraints.is_empty() {
+				constraints.extend(quote! { + });
+			}
+
+			constraints.extend(quote! {
+				validator::Validate
+			});
+		}
+
 		codec_trait.extend(constraints.clone());
 		codec_impl.extend(constraints);
 
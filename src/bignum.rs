@@ -0,0 +1,93 @@
+//! The `serde` representation used by `#[codec(json_bignum)]` fields.
+//!
+//! Human-readable formats (JSON, YAML, TOML and RON) encode the number as a
+//! decimal string, so that clients that parse JSON numbers as `f64`, such as
+//! JavaScript, do not lose precision. The other formats keep the native
+//! integer representation. Decoding from a human-readable format also accepts
+//! a plain number.
+
+use core::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::{
+	de::{self, Visitor},
+	Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Encodes the number as a string or as a number, depending on the format.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: fmt::Display + Serialize,
+	S: Serializer,
+{
+	if serializer.is_human_readable() {
+		serializer.collect_str(value)
+	} else {
+		value.serialize(serializer)
+	}
+}
+
+/// Decodes the number from a string or from a number.
+///
+/// # Errors
+///
+/// Returns an error if the value is neither, or if it does not fit in `T`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+	T: FromStr + Deserialize<'de>,
+	T::Err: fmt::Display,
+	D: Deserializer<'de>,
+{
+	if deserializer.is_human_readable() {
+		deserializer.deserialize_any(NumberVisitor(PhantomData))
+	} else {
+		T::deserialize(deserializer)
+	}
+}
+
+struct NumberVisitor<T>(PhantomData<T>);
+
+impl<T> NumberVisitor<T>
+where
+	T: FromStr,
+	T::Err: fmt::Display,
+{
+	fn parse<E: de::Error>(value: impl fmt::Display) -> Result<T, E> {
+		value.to_string().parse().map_err(E::custom)
+	}
+}
+
+impl<T> Visitor<'_> for NumberVisitor<T>
+where
+	T: FromStr,
+	T::Err: fmt::Display,
+{
+	type Value = T;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("an integer or a string containing one")
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		v.parse().map_err(E::custom)
+	}
+
+	fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+		Self::parse(v)
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+		Self::parse(v)
+	}
+
+	fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+		Self::parse(v)
+	}
+
+	fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+		Self::parse(v)
+	}
+}
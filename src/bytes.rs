@@ -0,0 +1,96 @@
+//! The `serde` representation used by `#[codec(bytes)]` fields.
+//!
+//! Human-readable formats (JSON, YAML, TOML and RON) encode the bytes as a
+//! standard base64 string, while the other formats use their native byte
+//! string type. Decoding also accepts a sequence of integers, which is how
+//! `serde` encodes a `Vec<u8>` by default, so existing clients keep working.
+
+use core::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{
+	de::{self, SeqAccess, Visitor},
+	Deserializer, Serializer,
+};
+
+/// Encodes bytes as a base64 string or a byte string, depending on the format.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: AsRef<[u8]> + ?Sized,
+	S: Serializer,
+{
+	if serializer.is_human_readable() {
+		serializer.serialize_str(&STANDARD.encode(bytes))
+	} else {
+		serializer.serialize_bytes(bytes.as_ref())
+	}
+}
+
+/// Decodes bytes from a base64 string, a byte string or a sequence of integers.
+///
+/// # Errors
+///
+/// Returns an error if the value is none of these, or if the base64 is
+/// invalid.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+	T: From<Vec<u8>>,
+	D: Deserializer<'de>,
+{
+	let human_readable = deserializer.is_human_readable();
+	let visitor = BytesVisitor { human_readable };
+
+	if human_readable {
+		deserializer.deserialize_any(visitor).map(T::from)
+	} else {
+		deserializer.deserialize_byte_buf(visitor).map(T::from)
+	}
+}
+
+struct BytesVisitor {
+	human_readable: bool,
+}
+
+impl<'de> Visitor<'de> for BytesVisitor {
+	type Value = Vec<u8>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.human_readable {
+			f.write_str("a base64 string")
+		} else {
+			f.write_str("a byte string")
+		}
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		if !self.human_readable {
+			return Ok(v.as_bytes().to_vec());
+		}
+
+		STANDARD
+			.decode(v)
+			.map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		Ok(v.to_vec())
+	}
+
+	fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+		Ok(v)
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+
+		while let Some(byte) = seq.next_element()? {
+			bytes.push(byte);
+		}
+
+		Ok(bytes)
+	}
+}
@@ -3,9 +3,11 @@ use std::{convert::Infallible, str::FromStr};
 
 use axum::{
 	extract::FromRequestParts,
-	http::{header, request::Parts, HeaderValue},
+	http::{header, request::Parts, Extensions, HeaderMap, HeaderValue, Uri},
 };
 
+use crate::{CodecRejection, Shape};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ContentType {
@@ -19,10 +21,18 @@ pub enum ContentType {
 	Bitcode,
 	#[cfg(feature = "cbor")]
 	Cbor,
+	#[cfg(feature = "bson")]
+	Bson,
 	#[cfg(feature = "yaml")]
 	Yaml,
 	#[cfg(feature = "toml")]
 	Toml,
+	#[cfg(feature = "ron")]
+	Ron,
+	/// A format registered at runtime with
+	/// [`custom::register`](crate::custom::register).
+	#[cfg(feature = "custom")]
+	Custom(crate::custom::CustomId),
 }
 
 #[cfg(not(any(
@@ -31,13 +41,15 @@ pub enum ContentType {
 	feature = "bincode",
 	feature = "bitcode",
 	feature = "cbor",
+	feature = "bson",
 	feature = "yaml",
-	feature = "toml"
+	feature = "toml",
+	feature = "ron"
 )))]
 const _: () = {
 	compile_error!(
 		"At least one of the following features must be enabled: `json`, `msgpack`, `bincode`, \
-		 `bitcode`, `cbor`, `yaml`, `toml`."
+		 `bitcode`, `cbor`, `bson`, `yaml`, `toml`, `ron`."
 	);
 
 	impl Default for ContentType {
@@ -53,8 +65,10 @@ const _: () = {
 	feature = "bincode",
 	feature = "bitcode",
 	feature = "cbor",
+	feature = "bson",
 	feature = "yaml",
-	feature = "toml"
+	feature = "toml",
+	feature = "ron"
 ))]
 impl Default for ContentType {
 	#[allow(unreachable_code)]
@@ -69,10 +83,14 @@ impl Default for ContentType {
 		return Self::Bitcode;
 		#[cfg(feature = "cbor")]
 		return Self::Cbor;
+		#[cfg(feature = "bson")]
+		return Self::Bson;
 		#[cfg(feature = "yaml")]
 		return Self::Yaml;
 		#[cfg(feature = "toml")]
 		return Self::Toml;
+		#[cfg(feature = "ron")]
+		return Self::Ron;
 	}
 }
 
@@ -82,6 +100,247 @@ impl fmt::Display for ContentType {
 	}
 }
 
+/// Expands to the given [`ContentType`] variant, with a descriptive compile
+/// error if the feature flag for that format is disabled.
+///
+/// Referring to `ContentType::Yaml` directly without the `yaml` feature only
+/// reports that the variant does not exist, which is easy to misread.
+///
+/// ```edition2021
+/// # use axum_codec::{codec_content_type, ContentType};
+/// #
+/// # fn main() {
+/// assert_eq!(codec_content_type!(Json), ContentType::Json);
+/// # }
+/// ```
+///
+/// Unknown formats are rejected as well:
+///
+/// ```compile_fail
+/// let content_type = axum_codec::codec_content_type!(Xml);
+/// ```
+#[macro_export]
+macro_rules! codec_content_type {
+	(Json) => {
+		$crate::__private_content_type_json!()
+	};
+	(MsgPack) => {
+		$crate::__private_content_type_msgpack!()
+	};
+	(Bincode) => {
+		$crate::__private_content_type_bincode!()
+	};
+	(Bitcode) => {
+		$crate::__private_content_type_bitcode!()
+	};
+	(Cbor) => {
+		$crate::__private_content_type_cbor!()
+	};
+	(Bson) => {
+		$crate::__private_content_type_bson!()
+	};
+	(Yaml) => {
+		$crate::__private_content_type_yaml!()
+	};
+	(Toml) => {
+		$crate::__private_content_type_toml!()
+	};
+	(Ron) => {
+		$crate::__private_content_type_ron!()
+	};
+	($other:ident) => {
+		::core::compile_error!(::core::concat!(
+			"unknown content type `",
+			::core::stringify!($other),
+			"`, expected one of `Json`, `MsgPack`, `Bincode`, `Bitcode`, `Cbor`, `Bson`, `Yaml`, \
+			 `Toml`, `Ron`"
+		))
+	};
+}
+
+#[cfg(feature = "json")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_json {
+	() => {
+		$crate::ContentType::Json
+	};
+}
+
+#[cfg(not(feature = "json"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_json {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Json` is not available because the `json` feature of `axum-codec` is disabled"
+		)
+	};
+}
+
+#[cfg(feature = "msgpack")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_msgpack {
+	() => {
+		$crate::ContentType::MsgPack
+	};
+}
+
+#[cfg(not(feature = "msgpack"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_msgpack {
+	() => {
+		::core::compile_error!(
+			"`ContentType::MsgPack` is not available because the `msgpack` feature of `axum-codec` is \
+			 disabled"
+		)
+	};
+}
+
+#[cfg(feature = "bincode")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_bincode {
+	() => {
+		$crate::ContentType::Bincode
+	};
+}
+
+#[cfg(not(feature = "bincode"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_bincode {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Bincode` is not available because the `bincode` feature of `axum-codec` is \
+			 disabled"
+		)
+	};
+}
+
+#[cfg(feature = "bitcode")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_bitcode {
+	() => {
+		$crate::ContentType::Bitcode
+	};
+}
+
+#[cfg(not(feature = "bitcode"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_bitcode {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Bitcode` is not available because the `bitcode` feature of `axum-codec` is \
+			 disabled"
+		)
+	};
+}
+
+#[cfg(feature = "cbor")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_cbor {
+	() => {
+		$crate::ContentType::Cbor
+	};
+}
+
+#[cfg(not(feature = "cbor"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_cbor {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Cbor` is not available because the `cbor` feature of `axum-codec` is disabled"
+		)
+	};
+}
+
+#[cfg(feature = "bson")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_bson {
+	() => {
+		$crate::ContentType::Bson
+	};
+}
+
+#[cfg(not(feature = "bson"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_bson {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Bson` is not available because the `bson` feature of `axum-codec` is disabled"
+		)
+	};
+}
+
+#[cfg(feature = "yaml")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_yaml {
+	() => {
+		$crate::ContentType::Yaml
+	};
+}
+
+#[cfg(not(feature = "yaml"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_yaml {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Yaml` is not available because the `yaml` feature of `axum-codec` is disabled"
+		)
+	};
+}
+
+#[cfg(feature = "toml")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_toml {
+	() => {
+		$crate::ContentType::Toml
+	};
+}
+
+#[cfg(not(feature = "toml"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_toml {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Toml` is not available because the `toml` feature of `axum-codec` is disabled"
+		)
+	};
+}
+
+#[cfg(feature = "ron")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_ron {
+	() => {
+		$crate::ContentType::Ron
+	};
+}
+
+#[cfg(not(feature = "ron"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __private_content_type_ron {
+	() => {
+		::core::compile_error!(
+			"`ContentType::Ron` is not available because the `ron` feature of `axum-codec` is disabled"
+		)
+	};
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum FromStrError {
 	#[error("invalid content type")]
@@ -95,9 +354,123 @@ impl FromStr for ContentType {
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		let mime = s.parse::<mime::Mime>()?;
+
+		Self::from_mime(&mime).ok_or(FromStrError::InvalidContentType)
+	}
+}
+
+impl ContentType {
+	/// The payload size in bytes from which [`ContentType::preferred_for_size`]
+	/// prefers binary formats over text formats.
+	pub const BINARY_SIZE_THRESHOLD: usize = 16 * 1024;
+
+	/// Returns [`ContentType::Json`] if the `json` feature is enabled.
+	///
+	/// Unlike the variants, these constructors exist regardless of the enabled
+	/// features, so code that supports optional formats does not need to be
+	/// gated itself.
+	///
+	/// ```edition2021
+	/// # use axum_codec::ContentType;
+	/// #
+	/// # fn main() {
+	/// let preferred = ContentType::cbor()
+	///   .or(ContentType::json())
+	///   .unwrap_or_default();
+	///
+	/// assert_eq!(preferred, ContentType::Cbor);
+	/// # }
+	/// ```
+	#[must_use]
+	pub const fn json() -> Option<Self> {
+		#[cfg(feature = "json")]
+		return Some(Self::Json);
+		#[cfg(not(feature = "json"))]
+		None
+	}
+
+	/// Returns [`ContentType::MsgPack`] if the `msgpack` feature is enabled.
+	#[must_use]
+	pub const fn msgpack() -> Option<Self> {
+		#[cfg(feature = "msgpack")]
+		return Some(Self::MsgPack);
+		#[cfg(not(feature = "msgpack"))]
+		None
+	}
+
+	/// Returns [`ContentType::Bincode`] if the `bincode` feature is enabled.
+	#[must_use]
+	pub const fn bincode() -> Option<Self> {
+		#[cfg(feature = "bincode")]
+		return Some(Self::Bincode);
+		#[cfg(not(feature = "bincode"))]
+		None
+	}
+
+	/// Returns [`ContentType::Bitcode`] if the `bitcode` feature is enabled.
+	#[must_use]
+	pub const fn bitcode() -> Option<Self> {
+		#[cfg(feature = "bitcode")]
+		return Some(Self::Bitcode);
+		#[cfg(not(feature = "bitcode"))]
+		None
+	}
+
+	/// Returns [`ContentType::Cbor`] if the `cbor` feature is enabled.
+	#[must_use]
+	pub const fn cbor() -> Option<Self> {
+		#[cfg(feature = "cbor")]
+		return Some(Self::Cbor);
+		#[cfg(not(feature = "cbor"))]
+		None
+	}
+
+	/// Returns [`ContentType::Bson`] if the `bson` feature is enabled.
+	#[must_use]
+	pub const fn bson() -> Option<Self> {
+		#[cfg(feature = "bson")]
+		return Some(Self::Bson);
+		#[cfg(not(feature = "bson"))]
+		None
+	}
+
+	/// Returns [`ContentType::Yaml`] if the `yaml` feature is enabled.
+	#[must_use]
+	pub const fn yaml() -> Option<Self> {
+		#[cfg(feature = "yaml")]
+		return Some(Self::Yaml);
+		#[cfg(not(feature = "yaml"))]
+		None
+	}
+
+	/// Returns [`ContentType::Toml`] if the `toml` feature is enabled.
+	#[must_use]
+	pub const fn toml() -> Option<Self> {
+		#[cfg(feature = "toml")]
+		return Some(Self::Toml);
+		#[cfg(not(feature = "toml"))]
+		None
+	}
+
+	/// Returns [`ContentType::Ron`] if the `ron` feature is enabled.
+	#[must_use]
+	pub const fn ron() -> Option<Self> {
+		#[cfg(feature = "ron")]
+		return Some(Self::Ron);
+		#[cfg(not(feature = "ron"))]
+		None
+	}
+
+	/// Attempts to map a parsed MIME type to a [`ContentType`].
+	fn from_mime(mime: &mime::Mime) -> Option<Self> {
+		#[cfg(feature = "custom")]
+		if let Some(custom) = crate::custom::find(mime.essence_str()) {
+			return Some(custom);
+		}
+
 		let subtype = mime.suffix().unwrap_or_else(|| mime.subtype());
 
-		Ok(match (mime.type_().as_str(), subtype.as_str()) {
+		Some(match (mime.type_().as_str(), subtype.as_str()) {
 			#[cfg(feature = "json")]
 			("application", "json") => Self::Json,
 			#[cfg(feature = "msgpack")]
@@ -108,16 +481,58 @@ impl FromStr for ContentType {
 			("application", "bitcode" | "vnd.bitcode" | "x-bitcode" | "x.bitcode") => Self::Bitcode,
 			#[cfg(feature = "cbor")]
 			("application", "cbor") => Self::Cbor,
+			#[cfg(feature = "bson")]
+			("application", "bson") => Self::Bson,
 			#[cfg(feature = "yaml")]
 			("application" | "text", "yaml" | "yml" | "x-yaml") => Self::Yaml,
 			#[cfg(feature = "toml")]
 			("application" | "text", "toml" | "x-toml" | "vnd.toml") => Self::Toml,
-			_ => return Err(FromStrError::InvalidContentType),
+			#[cfg(feature = "ron")]
+			("application" | "text", "ron" | "x-ron") => Self::Ron,
+			_ => return None,
+		})
+	}
+
+	/// Maps a short format name, such as a file extension, to a
+	/// [`ContentType`]. The name is matched case-insensitively.
+	///
+	/// Formats registered at runtime with the `custom` feature are not
+	/// recognized.
+	///
+	/// ```edition2021
+	/// # use axum_codec::ContentType;
+	/// #
+	/// # fn main() {
+	/// assert_eq!(ContentType::from_extension("json"), Some(ContentType::Json));
+	/// assert_eq!(ContentType::from_extension("YML"), Some(ContentType::Yaml));
+	/// assert_eq!(ContentType::from_extension("xml"), None);
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn from_extension(extension: &str) -> Option<Self> {
+		Some(match extension.to_ascii_lowercase().as_str() {
+			#[cfg(feature = "json")]
+			"json" => Self::Json,
+			#[cfg(feature = "msgpack")]
+			"msgpack" | "mpk" => Self::MsgPack,
+			#[cfg(feature = "bincode")]
+			"bincode" => Self::Bincode,
+			#[cfg(feature = "bitcode")]
+			"bitcode" => Self::Bitcode,
+			#[cfg(feature = "cbor")]
+			"cbor" => Self::Cbor,
+			#[cfg(feature = "bson")]
+			"bson" => Self::Bson,
+			#[cfg(feature = "yaml")]
+			"yaml" | "yml" => Self::Yaml,
+			#[cfg(feature = "toml")]
+			"toml" => Self::Toml,
+			#[cfg(feature = "ron")]
+			"ron" => Self::Ron,
+			_ => return None,
 		})
 	}
-}
 
-impl ContentType {
 	/// Attempts to parse the given [`HeaderValue`] into a [`ContentType`]
 	/// by treating it as a MIME type.
 	///
@@ -148,6 +563,34 @@ impl ContentType {
 		header.to_str().ok()?.parse().ok()
 	}
 
+	/// Returns all built-in formats enabled through feature flags, in the same
+	/// order used to pick the [`Default`] content type.
+	///
+	/// Formats registered at runtime with the `custom` feature are not included.
+	#[must_use]
+	pub fn all() -> &'static [Self] {
+		&[
+			#[cfg(feature = "json")]
+			Self::Json,
+			#[cfg(feature = "msgpack")]
+			Self::MsgPack,
+			#[cfg(feature = "bincode")]
+			Self::Bincode,
+			#[cfg(feature = "bitcode")]
+			Self::Bitcode,
+			#[cfg(feature = "cbor")]
+			Self::Cbor,
+			#[cfg(feature = "bson")]
+			Self::Bson,
+			#[cfg(feature = "yaml")]
+			Self::Yaml,
+			#[cfg(feature = "toml")]
+			Self::Toml,
+			#[cfg(feature = "ron")]
+			Self::Ron,
+		]
+	}
+
 	/// Returns the MIME type as a string slice.
 	///
 	/// ```edition2021
@@ -170,13 +613,107 @@ impl ContentType {
 			Self::Bitcode => "application/vnd.bitcode",
 			#[cfg(feature = "cbor")]
 			Self::Cbor => "application/cbor",
+			#[cfg(feature = "bson")]
+			Self::Bson => "application/bson",
 			#[cfg(feature = "yaml")]
 			Self::Yaml => "application/x-yaml",
 			#[cfg(feature = "toml")]
 			Self::Toml => "text/toml",
+			#[cfg(feature = "ron")]
+			Self::Ron => "application/ron",
+			#[cfg(feature = "custom")]
+			Self::Custom(id) => crate::custom::get(id).content_type(),
+		}
+	}
+
+	/// Returns `true` if the format is text-based (and always valid UTF-8).
+	#[must_use]
+	pub fn is_text(self) -> bool {
+		match self {
+			#[cfg(feature = "json")]
+			Self::Json => true,
+			#[cfg(feature = "yaml")]
+			Self::Yaml => true,
+			#[cfg(feature = "toml")]
+			Self::Toml => true,
+			#[cfg(feature = "ron")]
+			Self::Ron => true,
+			#[cfg(feature = "custom")]
+			Self::Custom(id) => crate::custom::get(id).is_text(),
+			#[allow(unreachable_patterns)]
+			_ => false,
+		}
+	}
+
+	/// Returns `true` if the format can encode a top-level value of the given
+	/// [`Shape`].
+	///
+	/// TOML and BSON documents must be tables, so they only support
+	/// [`Shape::Map`]. Every other format supports all shapes.
+	///
+	/// ```edition2021
+	/// # use axum_codec::{ContentType, Shape};
+	/// #
+	/// # fn main() {
+	/// assert!(ContentType::Json.supports_shape(Shape::Sequence));
+	/// assert!(!ContentType::Toml.supports_shape(Shape::Sequence));
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn supports_shape(self, shape: Shape) -> bool {
+		match self {
+			#[cfg(feature = "bson")]
+			Self::Bson => shape == Shape::Map,
+			#[cfg(feature = "toml")]
+			Self::Toml => shape == Shape::Map,
+			#[allow(unreachable_patterns)]
+			_ => {
+				let _ = shape;
+				true
+			}
 		}
 	}
 
+	/// Returns `true` if the format can encode a top-level value of any
+	/// [`Shape`].
+	fn supports_any_shape(self) -> bool {
+		self.supports_shape(Shape::Sequence) && self.supports_shape(Shape::Scalar)
+	}
+
+	/// Picks a format from `options` for a payload of roughly `len` bytes.
+	///
+	/// Payloads of at least [`ContentType::BINARY_SIZE_THRESHOLD`] bytes prefer
+	/// the first binary option, since compact formats pay off for large bodies,
+	/// while smaller payloads prefer the first text option for readability. If
+	/// no option of the preferred kind exists, the first option is used, and if
+	/// `options` is empty, the [`Default`] content type is returned.
+	///
+	/// This is only a heuristic, meant for handlers that are free to choose
+	/// their own response format.
+	///
+	/// ```edition2021
+	/// # use axum_codec::ContentType;
+	/// #
+	/// let options = [ContentType::Json, ContentType::MsgPack];
+	///
+	/// assert_eq!(ContentType::preferred_for_size(128, &options), ContentType::Json);
+	/// assert_eq!(
+	///   ContentType::preferred_for_size(1024 * 1024, &options),
+	///   ContentType::MsgPack
+	/// );
+	/// ```
+	#[must_use]
+	pub fn preferred_for_size(len: usize, options: &[Self]) -> Self {
+		let prefer_text = len < Self::BINARY_SIZE_THRESHOLD;
+
+		options
+			.iter()
+			.find(|content_type| content_type.is_text() == prefer_text)
+			.or_else(|| options.first())
+			.copied()
+			.unwrap_or_default()
+	}
+
 	/// Converts the [`ContentType`] into a [`HeaderValue`].
 	///
 	/// ```edition2021
@@ -208,6 +745,43 @@ impl ContentType {
 	pub fn into_header(self) -> HeaderValue {
 		HeaderValue::from_static(self.as_str())
 	}
+
+	/// Resolves the [`ContentType`] of a request body from its `Content-Type`
+	/// header, mapping `application/octet-stream` through [`OctetStream`] if it
+	/// was added to the request extensions.
+	pub(crate) fn from_request_headers(headers: &HeaderMap, extensions: &Extensions) -> Self {
+		let Some(mime) = headers
+			.get(header::CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.and_then(|value| value.parse::<mime::Mime>().ok())
+		else {
+			return Self::default();
+		};
+
+		if mime.essence_str() == mime::APPLICATION_OCTET_STREAM.essence_str() {
+			if let Some(OctetStream(content_type)) = extensions.get::<OctetStream>() {
+				return *content_type;
+			}
+		}
+
+		Self::from_mime(&mime).unwrap_or_default()
+	}
+
+	/// Rejects requests with several different `Content-Type` headers.
+	///
+	/// [`HeaderMap::get`] only returns the first one, so a body could otherwise
+	/// be decoded differently than an intermediary inspecting another header
+	/// expects. Repeating the same value is allowed.
+	pub(crate) fn ensure_unambiguous(headers: &HeaderMap) -> Result<(), CodecRejection> {
+		let mut values = headers.get_all(header::CONTENT_TYPE).iter();
+
+		match values.next() {
+			Some(first) if values.any(|value| value != first) => {
+				Err(CodecRejection::ConflictingContentType)
+			}
+			_ => Ok(()),
+		}
+	}
 }
 
 #[axum::async_trait]
@@ -215,17 +789,162 @@ impl<S> FromRequestParts<S> for ContentType {
 	type Rejection = Infallible;
 
 	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-		let header = parts
-			.headers
-			.get(header::CONTENT_TYPE)
-			.and_then(Self::from_header);
+		Ok(Self::from_request_headers(
+			&parts.headers,
+			&parts.extensions,
+		))
+	}
+}
+
+/// Decodes request bodies sent as `application/octet-stream` with the given
+/// [`ContentType`].
+///
+/// Many generic HTTP clients send binary uploads as `application/octet-stream`,
+/// which does not identify a format and would otherwise fall back to the
+/// default [`ContentType`]. Add this to a router with
+/// [`Extension`](axum::Extension) to choose the format per router.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{routing::post, Codec, ContentType, OctetStream};
+/// # use axum::{Extension, Router};
+/// #
+/// #[axum_codec::apply(decode, encode)]
+/// struct Upload {
+///   data: Vec<u8>,
+/// }
+///
+/// async fn upload(Codec(upload): Codec<Upload>) -> Codec<Upload> {
+///   Codec(upload)
+/// }
+///
+/// # fn main() {
+/// let router: Router = Router::new()
+///   .route("/upload", post(upload).into())
+///   .layer(Extension(OctetStream(ContentType::MsgPack)));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OctetStream(pub ContentType);
+
+/// Lets clients choose the response format with a query parameter, such as
+/// `?format=yaml`, which takes precedence over the `Accept` header.
+///
+/// Browsers cannot easily set the `Accept` header, so this is useful for
+/// exploring an API manually. The value is percent-decoded and mapped with
+/// [`ContentType::from_extension`] or, failing that, parsed as a MIME type
+/// (such as `?format=application%2Fjson`). Unknown values fall back to the
+/// `Accept` header. This is opt-in: add it to a router with
+/// [`Extension`](axum::Extension). The default parameter name is `format`.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{routing::get, Codec, FormatQuery};
+/// # use axum::{Extension, Router};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Status {
+///   ok: bool,
+/// }
+///
+/// async fn status() -> Codec<Status> {
+///   Codec(Status { ok: true })
+/// }
+///
+/// # fn main() {
+/// // `GET /status?format=yaml` responds with YAML
+/// let router: Router = Router::new()
+///   .route("/status", get(status).into())
+///   .layer(Extension(FormatQuery::default()));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatQuery(pub &'static str);
+
+impl Default for FormatQuery {
+	fn default() -> Self {
+		Self("format")
+	}
+}
+
+impl FormatQuery {
+	/// Finds the format requested in the given query string, if any.
+	fn content_type(self, query: &str) -> Option<ContentType> {
+		form_urlencoded::parse(query.as_bytes())
+			.find(|(name, _)| name == self.0)
+			.and_then(|(_, value)| {
+				ContentType::from_extension(&value).or_else(|| value.parse().ok())
+			})
+	}
+}
+
+/// Lets clients choose the response format with a cookie, such as
+/// `format=yaml`, when the `Accept` header is missing or only `*/*`.
+///
+/// This is useful for clients that remember a preference across requests but
+/// cannot set headers, such as links followed in a browser. The value is
+/// mapped with [`ContentType::from_extension`], and unknown values are
+/// ignored. An `Accept` header that names a format, or a [`FormatQuery`],
+/// takes precedence. This is opt-in: add it to a router with
+/// [`Extension`](axum::Extension). The default cookie name is `format`.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{routing::get, Codec, FormatCookie};
+/// # use axum::{Extension, Router};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Status {
+///   ok: bool,
+/// }
+///
+/// async fn status() -> Codec<Status> {
+///   Codec(Status { ok: true })
+/// }
+///
+/// # fn main() {
+/// // `GET /status` with `Cookie: format=yaml` responds with YAML
+/// let router: Router = Router::new()
+///   .route("/status", get(status).into())
+///   .layer(Extension(FormatCookie::default()));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatCookie(pub &'static str);
+
+impl Default for FormatCookie {
+	fn default() -> Self {
+		Self("format")
+	}
+}
 
-		Ok(header.unwrap_or_default())
+impl FormatCookie {
+	/// Finds the format requested in the `Cookie` headers, if any.
+	fn content_type(self, headers: &HeaderMap) -> Option<ContentType> {
+		headers
+			.get_all(header::COOKIE)
+			.iter()
+			.filter_map(|value| value.to_str().ok())
+			.flat_map(|value| value.split(';'))
+			.filter_map(|pair| pair.split_once('='))
+			.find(|(name, _)| name.trim() == self.0)
+			.and_then(|(_, value)| ContentType::from_extension(value.trim().trim_matches('"')))
 	}
 }
 
 /// Extractor for the request's desired response [`ContentType`].
 ///
+/// Handlers routed through [`CodecHandler`](crate::CodecHandler) resolve the
+/// [`Accept`] once, before any extractor runs, and store it in the request
+/// extensions. Extracting [`Accept`] returns that stored value, so
+/// `Codec(value).to_response(accept)` always picks the same format as
+/// returning `Codec(value)` directly. An [`Accept`] inserted into the request
+/// extensions by an earlier layer (for example with
+/// [`Extension`](axum::Extension)) takes precedence in the same way.
+///
 /// # Examples
 ///
 /// ```edition2021
@@ -250,38 +969,1176 @@ impl<S> FromRequestParts<S> for ContentType {
 /// # fn main() {}
 /// ```
 #[derive(Debug, Clone, Copy)]
-pub struct Accept(ContentType);
+pub struct Accept {
+	content_type: ContentType,
+	/// The most preferred acceptable format that supports every [`Shape`].
+	fallback: ContentType,
+	html: bool,
+}
 
 impl Accept {
 	/// Returns the request's desired response [`ContentType`].
 	#[inline]
 	#[must_use]
 	pub fn content_type(self) -> ContentType {
-		self.0
+		self.content_type
 	}
-}
 
-impl From<Accept> for ContentType {
-	#[inline]
-	fn from(accept: Accept) -> Self {
-		accept.0
+	/// Returns the request's desired response [`ContentType`] for a value of
+	/// the given [`Shape`].
+	///
+	/// If the desired format cannot represent the shape (such as TOML for a
+	/// sequence), the next acceptable format that can is returned instead,
+	/// falling back to the default [`ContentType`].
+	///
+	/// ```edition2021
+	/// # use axum_codec::{Accept, ContentType, Shape};
+	/// # use axum::http::{header, HeaderMap, HeaderValue};
+	/// #
+	/// # fn main() {
+	/// let mut headers = HeaderMap::new();
+	/// headers.insert(
+	///   header::ACCEPT,
+	///   HeaderValue::from_static("text/toml, application/cbor;q=0.5"),
+	/// );
+	///
+	/// let accept = Accept::from_headers(&headers);
+	///
+	/// assert_eq!(accept.content_type_for(Shape::Map), ContentType::Toml);
+	/// assert_eq!(accept.content_type_for(Shape::Sequence), ContentType::Cbor);
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn content_type_for(self, shape: Shape) -> ContentType {
+		if self.content_type.supports_shape(shape) {
+			self.content_type
+		} else {
+			self.fallback
+		}
 	}
-}
 
-#[axum::async_trait]
-impl<S> FromRequestParts<S> for Accept
-where
-	S: Send + Sync + 'static,
-{
-	type Rejection = Infallible;
-
-	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-		let header = None
-			.or_else(|| parts.headers.get(header::ACCEPT))
-			.or_else(|| parts.headers.get(header::CONTENT_TYPE))
+	/// Returns `true` if the most preferred media range in the request's
+	/// `Accept` header is `text/html`.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Accept;
+	/// # use axum::http::{header, HeaderMap, HeaderValue};
+	/// #
+	/// # fn main() {
+	/// let mut headers = HeaderMap::new();
+	/// headers.insert(
+	///   header::ACCEPT,
+	///   HeaderValue::from_static("text/html,application/json;q=0.9"),
+	/// );
+	///
+	/// assert!(Accept::from_headers(&headers).prefers_html());
+	/// # }
+	/// ```
+	#[inline]
+	#[must_use]
+	pub fn prefers_html(self) -> bool {
+		self.html
+	}
+
+	/// Resolves the desired response [`ContentType`] from the given request
+	/// headers.
+	///
+	/// The `Accept` header is checked first. Each enabled format takes the
+	/// quality value of the most specific media range that matches it, and the
+	/// format with the highest one is picked, so a format excluded with `q=0`
+	/// is never picked even if a wildcard such as `*/*` matches it. Formats
+	/// named explicitly win ties, in the order they are listed, before the
+	/// formats only matched by a wildcard. If no format is acceptable, the first
+	/// enabled format that is not excluded is used.
+	///
+	/// If the `Accept` header is not present, the `Content-Type` header is used
+	/// instead. If neither resolves to an enabled format, the default
+	/// [`ContentType`] is used.
+	///
+	/// See [`negotiate`](crate::negotiate) for doing the same outside of a
+	/// request.
+	#[must_use]
+	pub fn from_headers(headers: &HeaderMap) -> Self {
+		let Some(accept) = headers.get(header::ACCEPT) else {
+			return Self::new(negotiate(
+				None,
+				headers
+					.get(header::CONTENT_TYPE)
+					.and_then(|value| value.to_str().ok()),
+			));
+		};
+
+		let ranges = accept.to_str().map(all_media_ranges).unwrap_or_default();
+		let acceptable = acceptable_formats(&ranges);
+
+		Self {
+			content_type: acceptable
+				.first()
+				.copied()
+				.or_else(|| first_not_excluded(&ranges, ContentType::all().iter().copied()))
+				.unwrap_or_default(),
+			fallback: acceptable
+				.into_iter()
+				.find(|content_type| content_type.supports_any_shape())
+				.or_else(|| {
+					first_not_excluded(
+						&ranges,
+						ContentType::all()
+							.iter()
+							.copied()
+							.filter(|content_type| content_type.supports_any_shape()),
+					)
+				})
+				.unwrap_or_default(),
+			html: ranges.first().is_some_and(|(mime, q)| {
+				*q > 0.0 && mime.type_() == mime::TEXT && mime.subtype() == mime::HTML
+			}),
+		}
+	}
+
+	/// Resolves the desired response [`ContentType`] of a request, letting a
+	/// [`FormatQuery`] in the request extensions override the headers, and a
+	/// [`FormatCookie`] stand in for a missing or wildcard `Accept` header.
+	///
+	/// An [`Accept`] already stored in the request extensions, such as by a
+	/// [`CodecHandler`](crate::CodecHandler), is returned as is.
+	pub(crate) fn from_request_head(uri: &Uri, headers: &HeaderMap, extensions: &Extensions) -> Self {
+		if let Some(accept) = extensions.get::<Self>() {
+			return *accept;
+		}
+
+		let content_type = extensions
+			.get::<FormatQuery>()
+			.zip(uri.query())
+			.and_then(|(format, query)| format.content_type(query))
+			.or_else(|| {
+				extensions
+					.get::<FormatCookie>()
+					.filter(|_| accepts_anything(headers))
+					.and_then(|cookie| cookie.content_type(headers))
+			});
+
+		match content_type {
+			Some(content_type) => Self::new(content_type),
+			None => Self::from_headers(headers),
+		}
+	}
+
+	/// Returns `true` if the response format of a request can be satisfied by
+	/// an enabled format, without falling back to the default [`ContentType`].
+	///
+	/// This is the case if a [`FormatQuery`] picks a format, if there is no
+	/// `Accept` header, or if at least one enabled format is acceptable as
+	/// resolved by [`Self::from_headers`], through a media range that names it
+	/// or a wildcard, without being excluded with `q=0`.
+	pub(crate) fn is_satisfiable(uri: &Uri, headers: &HeaderMap, extensions: &Extensions) -> bool {
+		let from_query = extensions
+			.get::<FormatQuery>()
+			.zip(uri.query())
+			.and_then(|(format, query)| format.content_type(query));
+
+		let Some(accept) = headers.get(header::ACCEPT) else {
+			return true;
+		};
+
+		if from_query.is_some() {
+			return true;
+		}
+
+		let ranges = accept.to_str().map(all_media_ranges).unwrap_or_default();
+
+		// Uses the same resolution as `from_headers`, so a request that passes is
+		// never answered in a format it excluded.
+		!acceptable_formats(&ranges).is_empty()
+	}
+
+	/// Creates an [`Accept`] for a single acceptable format.
+	fn new(content_type: ContentType) -> Self {
+		Self {
+			content_type,
+			fallback: Some(content_type)
+				.filter(|content_type| content_type.supports_any_shape())
+				.unwrap_or_default(),
+			html: false,
+		}
+	}
+}
+
+/// Returns all built-in formats enabled through feature flags, such as for a
+/// capabilities endpoint. Equivalent to [`ContentType::all`].
+///
+/// Formats registered at runtime with the `custom` feature are not included.
+///
+/// ```edition2021
+/// # use axum_codec::{enabled_content_types, ContentType};
+/// #
+/// # fn main() {
+/// assert!(enabled_content_types().contains(&ContentType::Json));
+/// # }
+/// ```
+#[must_use]
+pub fn enabled_content_types() -> &'static [ContentType] {
+	ContentType::all()
+}
+
+/// Returns the MIME types of all built-in formats enabled through feature
+/// flags, in the same order as [`enabled_content_types`].
+///
+/// ```edition2021
+/// # use axum_codec::enabled_mime_types;
+/// #
+/// async fn capabilities() -> axum::Json<Vec<&'static str>> {
+///   axum::Json(enabled_mime_types().collect())
+/// }
+/// #
+/// # fn main() {
+/// assert!(enabled_mime_types().any(|mime| mime == "application/json"));
+/// # }
+/// ```
+pub fn enabled_mime_types() -> impl Iterator<Item = &'static str> {
+	enabled_content_types().iter().map(ContentType::as_str)
+}
+
+/// Resolves the response [`ContentType`] from the raw values of the `Accept`
+/// and `Content-Type` headers, using the same rules as the [`Accept`]
+/// extractor.
+///
+/// If `accept` is present, it is resolved like in [`Accept::from_headers`],
+/// honouring wildcards and formats excluded with `q=0`, and `content_type` is
+/// ignored. Otherwise, `content_type` is used. If neither resolves to an
+/// enabled format, the default [`ContentType`] is returned.
+///
+/// This is useful outside of handlers, such as in background jobs or tests.
+///
+/// ```edition2021
+/// # use axum_codec::{negotiate, ContentType};
+/// #
+/// # fn main() {
+/// assert_eq!(
+///   negotiate(Some("application/json;q=0.5, application/cbor"), None),
+///   ContentType::Cbor,
+/// );
+/// assert_eq!(negotiate(None, Some("application/yaml")), ContentType::Yaml);
+/// assert_eq!(negotiate(None, None), ContentType::default());
+/// # }
+/// ```
+#[must_use]
+pub fn negotiate(accept: Option<&str>, content_type: Option<&str>) -> ContentType {
+	match accept {
+		Some(accept) => {
+			let ranges = all_media_ranges(accept);
+
+			acceptable_formats(&ranges)
+				.first()
+				.copied()
+				.or_else(|| first_not_excluded(&ranges, ContentType::all().iter().copied()))
+				.unwrap_or_default()
+		}
+		None => content_type
+			.and_then(|content_type| content_type.parse().ok())
+			.unwrap_or_default(),
+	}
+}
+
+impl From<Accept> for ContentType {
+	#[inline]
+	fn from(accept: Accept) -> Self {
+		accept.content_type
+	}
+}
+
+/// Negotiates a response [`ContentType`] from an `Accept` header using a
+/// configurable priority, instead of the order of the enabled features.
+///
+/// Formats are ranked by the quality value of the most specific media range
+/// that matches them. Ties are broken by the order given to
+/// [`prefer`](Self::prefer), and formats that were not preferred are never
+/// chosen.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{ContentType, ContentTypeNegotiator};
+/// # use axum::http::HeaderValue;
+/// #
+/// # fn main() {
+/// let negotiator = ContentTypeNegotiator::new().prefer(&[ContentType::MsgPack, ContentType::Json]);
+///
+/// let header = HeaderValue::from_static("application/json, application/vnd.msgpack");
+/// assert_eq!(negotiator.negotiate(&header), ContentType::MsgPack);
+///
+/// let header = HeaderValue::from_static("application/vnd.msgpack;q=0.5, application/json");
+/// assert_eq!(negotiator.negotiate(&header), ContentType::Json);
+///
+/// let header = HeaderValue::from_static("*/*");
+/// assert_eq!(negotiator.negotiate(&header), ContentType::MsgPack);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContentTypeNegotiator {
+	preferred: Vec<ContentType>,
+	browser: Option<ContentType>,
+}
+
+impl Default for ContentTypeNegotiator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ContentTypeNegotiator {
+	/// Creates a negotiator that prefers all enabled formats in the order
+	/// returned by [`ContentType::all`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			preferred: ContentType::all().to_vec(),
+			browser: None,
+		}
+	}
+
+	/// Sets the formats that can be negotiated, from most to least preferred.
+	///
+	/// If `preferred` is empty, the negotiator always returns the default
+	/// [`ContentType`].
+	#[must_use]
+	pub fn prefer(mut self, preferred: &[ContentType]) -> Self {
+		self.preferred = preferred.to_vec();
+		self
+	}
+
+	/// Sets the format used for requests that look like they come from a
+	/// browser navigating to the URL directly.
+	///
+	/// Browsers send an `Accept` header such as
+	/// `text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8`,
+	/// which only matches the preferred formats through the `*/*` wildcard, so
+	/// they would otherwise receive the most preferred format. If that is a
+	/// binary format, the browser downloads the response instead of showing
+	/// it.
+	///
+	/// A header is treated as coming from a browser if it prefers `text/html`
+	/// or `application/xhtml+xml` (with any parameters) at least as much as
+	/// anything else, and names none of the enabled formats explicitly.
+	///
+	/// ```edition2021
+	/// # use axum_codec::{ContentType, ContentTypeNegotiator};
+	/// # use axum::http::HeaderValue;
+	/// #
+	/// # fn main() {
+	/// let negotiator = ContentTypeNegotiator::new()
+	///   .prefer(&[ContentType::MsgPack, ContentType::Json])
+	///   .for_browsers(ContentType::Json);
+	///
+	/// let header = HeaderValue::from_static("text/html,application/xhtml+xml,*/*;q=0.8");
+	/// assert_eq!(negotiator.negotiate(&header), ContentType::Json);
+	///
+	/// let header = HeaderValue::from_static("*/*");
+	/// assert_eq!(negotiator.negotiate(&header), ContentType::MsgPack);
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn for_browsers(mut self, content_type: ContentType) -> Self {
+		self.browser = Some(content_type);
+		self
+	}
+
+	/// Returns the most preferred format, used when the `Accept` header is
+	/// missing or does not match any of the preferred formats.
+	#[must_use]
+	pub fn fallback(&self) -> ContentType {
+		self.preferred.first().copied().unwrap_or_default()
+	}
+
+	/// Negotiates a response [`ContentType`] from the given `Accept` header.
+	///
+	/// If none of the preferred formats are acceptable, the most preferred
+	/// format that is not excluded with `q=0` is returned, or the
+	/// [`fallback`](Self::fallback) if all of them are.
+	#[must_use]
+	pub fn negotiate(&self, accept: &HeaderValue) -> ContentType {
+		let ranges = accept.to_str().map(all_media_ranges).unwrap_or_default();
+
+		if let Some(browser) = self.browser.filter(|_| is_browser(&ranges)) {
+			return browser;
+		}
+
+		rank(&ranges, self.preferred.iter().copied())
+			.first()
+			.copied()
+			.or_else(|| first_not_excluded(&ranges, self.preferred.iter().copied()))
+			.unwrap_or_else(|| self.fallback())
+	}
+
+	/// Negotiates a response [`ContentType`] from the given request headers,
+	/// falling back to the `Content-Type` header (if it is one of the preferred
+	/// formats) when no `Accept` header is present.
+	#[must_use]
+	pub fn negotiate_headers(&self, headers: &HeaderMap) -> ContentType {
+		if let Some(accept) = headers.get(header::ACCEPT) {
+			return self.negotiate(accept);
+		}
+
+		headers
+			.get(header::CONTENT_TYPE)
 			.and_then(ContentType::from_header)
-			.unwrap_or_default();
+			.filter(|content_type| self.preferred.contains(content_type))
+			.unwrap_or_else(|| self.fallback())
+	}
+}
+
+/// Returns the enabled formats accepted by the given media ranges, from most
+/// to least preferred.
+///
+/// Formats named by a media range come first, in the order of the ranges,
+/// followed by the remaining formats in the order of [`ContentType::all`]
+/// (which are only accepted through wildcards).
+fn acceptable_formats(ranges: &[(mime::Mime, f32)]) -> Vec<ContentType> {
+	rank(
+		ranges,
+		ranges
+			.iter()
+			.filter_map(|(mime, _)| ContentType::from_mime(mime))
+			.chain(ContentType::all().iter().copied()),
+	)
+}
+
+/// Orders the candidate formats by the quality value of the most specific
+/// media range that matches them, dropping the formats that no range
+/// accepts or that are excluded with `q=0`. Ties keep the order of the
+/// candidates.
+fn rank(
+	ranges: &[(mime::Mime, f32)],
+	candidates: impl IntoIterator<Item = ContentType>,
+) -> Vec<ContentType> {
+	let mut ranked = Vec::<(ContentType, f32)>::new();
+
+	for content_type in candidates {
+		if ranked.iter().any(|(ranked, _)| *ranked == content_type) {
+			continue;
+		}
+
+		if let Some(q) = quality_of(content_type, ranges).filter(|q| *q > 0.0) {
+			ranked.push((content_type, q));
+		}
+	}
+
+	// `sort_by` is stable, so formats with equal weights keep their order.
+	ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+	ranked.into_iter().map(|(content_type, _)| content_type).collect()
+}
+
+/// Returns the first candidate format that is not excluded with `q=0`, used
+/// when none of the formats are acceptable.
+fn first_not_excluded(
+	ranges: &[(mime::Mime, f32)],
+	candidates: impl IntoIterator<Item = ContentType>,
+) -> Option<ContentType> {
+	candidates
+		.into_iter()
+		.find(|content_type| quality_of(*content_type, ranges) != Some(0.0))
+}
+
+/// Returns `true` if the media ranges look like the default `Accept` header
+/// of a browser, which prefers HTML over everything else and names none of
+/// the enabled formats.
+fn is_browser(ranges: &[(mime::Mime, f32)]) -> bool {
+	let html = ranges
+		.iter()
+		.filter(|(mime, _)| is_html(mime))
+		.map(|(_, q)| *q)
+		.fold(0.0, f32::max);
+
+	html > 0.0
+		&& ranges
+			.iter()
+			.all(|(mime, q)| *q <= html && (*q == 0.0 || ContentType::from_mime(mime).is_none()))
+}
+
+/// Returns `true` if the media range is `text/html` or `application/xhtml+xml`,
+/// ignoring any parameters.
+fn is_html(mime: &mime::Mime) -> bool {
+	(mime.type_() == mime::TEXT && mime.subtype() == mime::HTML)
+		|| (mime.type_() == mime::APPLICATION
+			&& mime.subtype() == "xhtml"
+			&& mime.suffix() == Some(mime::XML))
+}
+
+/// Returns `true` if the request has no `Accept` header, or if every media
+/// range in it is `*/*` with a non-zero quality value.
+fn accepts_anything(headers: &HeaderMap) -> bool {
+	headers.get(header::ACCEPT).is_none_or(|accept| {
+		accept
+			.to_str()
+			.map(all_media_ranges)
+			.unwrap_or_default()
+			.iter()
+			.all(|(mime, q)| *q > 0.0 && mime.type_() == mime::STAR && mime.subtype() == mime::STAR)
+	})
+}
+
+/// Returns the quality value of the most specific media range that matches
+/// the given [`ContentType`], if any.
+fn quality_of(content_type: ContentType, ranges: &[(mime::Mime, f32)]) -> Option<f32> {
+	let mime = content_type.as_str().parse::<mime::Mime>().ok()?;
+
+	ranges
+		.iter()
+		.filter_map(|(range, q)| {
+			let specificity = if ContentType::from_mime(range) == Some(content_type) {
+				2
+			} else if range.type_() == mime.type_() && range.subtype() == mime::STAR {
+				1
+			} else if range.type_() == mime::STAR {
+				0
+			} else {
+				return None;
+			};
+
+			Some((specificity, *q))
+		})
+		.max_by_key(|(specificity, _)| *specificity)
+		.map(|(_, q)| q)
+}
+
+/// Parses a comma-separated header into its entries, ordered by descending
+/// quality value. Entries with a quality value of zero are skipped.
+#[cfg(feature = "encoding")]
+fn weighted(header: &str) -> Vec<(&str, f32)> {
+	let mut entries = all_weighted(header);
+	entries.retain(|(_, q)| *q > 0.0);
+	entries
+}
+
+/// Parses a comma-separated header into its entries, ordered by descending
+/// quality value. Entries with a quality value of zero are kept, since they
+/// exclude whatever they match.
+fn all_weighted(header: &str) -> Vec<(&str, f32)> {
+	let mut entries = header
+		.split(',')
+		.map(str::trim)
+		.filter(|entry| !entry.is_empty())
+		.map(|entry| {
+			let q = entry
+				.split(';')
+				.skip(1)
+				.find_map(|param| {
+					let (name, value) = param.split_once('=')?;
+
+					name.trim().eq_ignore_ascii_case("q").then_some(value)
+				})
+				.and_then(|q| q.parse::<f32>().ok())
+				.map_or(1.0, |q| q.clamp(0.0, 1.0));
+
+			(entry, q)
+		})
+		.collect::<Vec<_>>();
+
+	// `sort_by` is stable, so entries with equal weights keep their order.
+	entries.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+	entries
+}
+
+/// Parses an `Accept` header into its media ranges, ordered by descending
+/// quality value. Entries that fail to parse are skipped, and entries with a
+/// quality value of zero are kept, since they exclude whatever they match.
+fn all_media_ranges(header: &str) -> Vec<(mime::Mime, f32)> {
+	all_weighted(header)
+		.into_iter()
+		.filter_map(|(range, q)| Some((range.parse::<mime::Mime>().ok()?, q)))
+		.collect()
+}
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for Accept
+where
+	S: Send + Sync + 'static,
+{
+	type Rejection = Infallible;
+
+	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		Ok(Self::from_request_head(
+			&parts.uri,
+			&parts.headers,
+			&parts.extensions,
+		))
+	}
+}
+
+// Only reads headers, so it adds nothing to the generated documentation.
+#[cfg(feature = "aide")]
+impl aide::operation::OperationInput for Accept {}
+
+/// Extractor for the request's desired response character set, read from the
+/// `Accept-Charset` header.
+///
+/// Only affects text formats (JSON, YAML, and TOML). Use with
+/// [`Codec::to_response_with_charset`](crate::Codec::to_response_with_charset)
+/// to transcode the encoded output.
+#[cfg(feature = "encoding")]
+#[derive(Debug, Clone, Copy)]
+pub struct AcceptCharset(Option<&'static encoding_rs::Encoding>);
+
+#[cfg(feature = "encoding")]
+impl Default for AcceptCharset {
+	fn default() -> Self {
+		Self(Some(encoding_rs::UTF_8))
+	}
+}
+
+#[cfg(feature = "encoding")]
+impl AcceptCharset {
+	/// Returns the most preferred character set that can be encoded, or `None`
+	/// if none of the acceptable character sets are supported.
+	#[inline]
+	#[must_use]
+	pub fn encoding(self) -> Option<&'static encoding_rs::Encoding> {
+		self.0
+	}
+
+	/// Resolves the desired response character set from the given request
+	/// headers. Defaults to UTF-8 if the `Accept-Charset` header is absent.
+	#[must_use]
+	pub fn from_headers(headers: &HeaderMap) -> Self {
+		let Some(header) = headers.get(header::ACCEPT_CHARSET) else {
+			return Self::default();
+		};
+
+		let Ok(header) = header.to_str() else {
+			return Self(None);
+		};
+
+		Self(weighted(header).into_iter().find_map(|(entry, _)| {
+			let label = entry.split(';').next().unwrap_or_default().trim();
+
+			if label == "*" {
+				return Some(encoding_rs::UTF_8);
+			}
+
+			let encoding = encoding_rs::Encoding::for_label(label.as_bytes())?;
+
+			// `encoding_rs` can only decode UTF-16, so it is encoded manually.
+			(encoding == encoding_rs::UTF_16LE
+				|| encoding == encoding_rs::UTF_16BE
+				|| encoding.output_encoding() == encoding)
+				.then_some(encoding)
+		}))
+	}
+
+	/// Transcodes UTF-8 text into the desired character set.
+	///
+	/// Returns `None` if no acceptable character set is supported, or if the
+	/// text contains characters that cannot be represented in it.
+	pub(crate) fn encode(self, text: &str) -> Option<Vec<u8>> {
+		let encoding = self.0?;
+
+		if encoding == encoding_rs::UTF_16LE {
+			return Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect());
+		}
+
+		if encoding == encoding_rs::UTF_16BE {
+			return Some(text.encode_utf16().flat_map(u16::to_be_bytes).collect());
+		}
+
+		let (bytes, _, had_errors) = encoding.encode(text);
+
+		(!had_errors).then(|| bytes.into_owned())
+	}
+}
+
+#[cfg(feature = "encoding")]
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for AcceptCharset
+where
+	S: Send + Sync + 'static,
+{
+	type Rejection = Infallible;
+
+	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		Ok(Self::from_headers(&parts.headers))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use axum::http::{header, HeaderMap, HeaderValue};
+
+	use super::{Accept, ContentType};
+
+	fn accept(value: &'static str) -> Accept {
+		let mut headers = HeaderMap::new();
+		headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+
+		Accept::from_headers(&headers)
+	}
+
+	#[test]
+	fn test_preferred_for_size() {
+		let options = [ContentType::Cbor, ContentType::Yaml, ContentType::Bitcode];
+
+		assert_eq!(
+			ContentType::preferred_for_size(0, &options),
+			ContentType::Yaml
+		);
+		assert_eq!(
+			ContentType::preferred_for_size(ContentType::BINARY_SIZE_THRESHOLD, &options),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			ContentType::preferred_for_size(0, &[ContentType::MsgPack]),
+			ContentType::MsgPack
+		);
+		assert_eq!(
+			ContentType::preferred_for_size(0, &[]),
+			ContentType::default()
+		);
+	}
+
+	#[test]
+	fn test_optional_constructors() {
+		assert_eq!(ContentType::json(), Some(ContentType::Json));
+		assert_eq!(ContentType::msgpack(), Some(ContentType::MsgPack));
+		assert_eq!(ContentType::bincode(), Some(ContentType::Bincode));
+		assert_eq!(ContentType::bitcode(), Some(ContentType::Bitcode));
+		assert_eq!(ContentType::cbor(), Some(ContentType::Cbor));
+		assert_eq!(ContentType::bson(), Some(ContentType::Bson));
+		assert_eq!(ContentType::yaml(), Some(ContentType::Yaml));
+		assert_eq!(ContentType::toml(), Some(ContentType::Toml));
+		assert_eq!(ContentType::ron(), Some(ContentType::Ron));
+	}
+
+	#[test]
+	fn test_accept_quality_values() {
+		let accept = accept("application/json;q=0.5, application/vnd.msgpack");
+
+		assert_eq!(accept.content_type(), ContentType::MsgPack);
+		assert!(!accept.prefers_html());
+	}
+
+	#[test]
+	fn test_accept_exclusions() {
+		assert_eq!(
+			accept("application/json;q=0, */*").content_type(),
+			ContentType::MsgPack
+		);
+		assert_eq!(
+			accept("*/*, application/json;q=0, application/vnd.msgpack;q=0").content_type(),
+			ContentType::Bincode
+		);
+		assert_eq!(
+			accept("application/json;q=0, application/*;q=0.5, text/yaml").content_type(),
+			ContentType::Yaml
+		);
+		assert_eq!(
+			accept("application/cbor;q=0.1, */*").content_type(),
+			ContentType::Json
+		);
+
+		let accept = accept("text/html;q=0, text/toml, application/json;q=0");
+
+		assert_eq!(accept.content_type(), ContentType::Toml);
+		assert!(!accept.prefers_html());
+	}
+
+	#[test]
+	fn test_accept_skips_unknown_ranges() {
+		let accept = accept("text/html, application/cbor;q=0.9, */*;q=0.8");
+
+		assert_eq!(accept.content_type(), ContentType::Cbor);
+		assert!(accept.prefers_html());
+	}
+
+	#[test]
+	fn test_accept_browser_default() {
+		let accept = accept("text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8");
+
+		assert_eq!(accept.content_type(), ContentType::default());
+		assert!(accept.prefers_html());
+	}
+
+	#[test]
+	fn test_accept_structured_suffix_quality() {
+		use super::{negotiate, ContentTypeNegotiator};
+
+		assert_eq!(
+			accept("application/vnd.api.v3+cbor;q=0.8").content_type(),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			accept("application/vnd.api.v3+cbor;q=0.8, application/vnd.api.v3+json;q=0.9").content_type(),
+			ContentType::Json
+		);
+		assert_eq!(
+			accept("application/vnd.api.v3+json;q=0.5, application/vnd.api.v3+cbor").content_type(),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			accept("application/vnd.api+json; version=3; q=0.2, application/vnd.api+yaml; q=0.7")
+				.content_type(),
+			ContentType::Yaml
+		);
+		assert_eq!(
+			accept("application/vnd.api.v3+cbor;q=0, application/vnd.api.v3+json;q=0.1").content_type(),
+			ContentType::Json
+		);
+		assert_eq!(
+			accept("application/vnd.api.v3+json; Q=0.3, application/vnd.api.v3+cbor; q=0.4")
+				.content_type(),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			negotiate(
+				Some("application/vnd.api.v3+json;q=0.4, application/vnd.api.v2+msgpack;q=0.6"),
+				None
+			),
+			ContentType::MsgPack
+		);
+
+		let negotiator = ContentTypeNegotiator::new().prefer(&[ContentType::Json, ContentType::Cbor]);
+
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static(
+				"application/vnd.api.v3+json;q=0.3, application/vnd.api.v3+cbor;q=0.8"
+			)),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static(
+				"application/vnd.api.v3+cbor;q=0.8, application/*;q=0.9"
+			)),
+			ContentType::Json
+		);
+	}
+
+	#[test]
+	fn test_negotiator_exclusions() {
+		use super::ContentTypeNegotiator;
+
+		let negotiator = ContentTypeNegotiator::new().prefer(&[ContentType::Json, ContentType::Cbor]);
+
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static("application/json;q=0, */*")),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static("*/*, application/cbor;q=0")),
+			ContentType::Json
+		);
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static(
+				"application/json;q=0, application/*;q=0.5"
+			)),
+			ContentType::Cbor
+		);
+	}
+
+	#[test]
+	fn test_negotiator_for_browsers() {
+		use super::ContentTypeNegotiator;
+
+		let negotiator = ContentTypeNegotiator::new()
+			.prefer(&[ContentType::MsgPack, ContentType::Json])
+			.for_browsers(ContentType::Json);
+
+		for accept in [
+			"text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8",
+			"text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+			"text/html, */*",
+		] {
+			assert_eq!(
+				negotiator.negotiate(&HeaderValue::from_static(accept)),
+				ContentType::Json,
+				"{accept}"
+			);
+		}
+
+		// Browsers that ask for a format explicitly, such as `fetch` calls,
+		// are negotiated as usual.
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static(
+				"text/html, application/vnd.msgpack;q=0.9, */*;q=0.8"
+			)),
+			ContentType::MsgPack
+		);
+		assert_eq!(
+			negotiator.negotiate(&HeaderValue::from_static("*/*")),
+			ContentType::MsgPack
+		);
+		assert_eq!(
+			ContentTypeNegotiator::new()
+				.prefer(&[ContentType::MsgPack, ContentType::Json])
+				.negotiate(&HeaderValue::from_static("text/html, */*;q=0.8")),
+			ContentType::MsgPack
+		);
+
+		// Parameters on the HTML range are ignored.
+		for accept in [
+			"text/html;charset=utf-8, */*;q=0.8",
+			"text/html; level=1, */*;q=0.8",
+			"application/xhtml+xml;charset=utf-8, */*;q=0.8",
+		] {
+			assert_eq!(
+				negotiator.negotiate(&HeaderValue::from_static(accept)),
+				ContentType::Json,
+				"{accept}"
+			);
+		}
+
+		// HTML that is excluded, or preferred less than other ranges, does not
+		// make the request look like a browser.
+		for accept in [
+			"text/html;q=0, */*",
+			"text/html;charset=utf-8;q=0, */*",
+			"text/html;q=0.1, */*",
+			"image/webp, text/html;q=0.9, */*;q=0.8",
+		] {
+			assert_eq!(
+				negotiator.negotiate(&HeaderValue::from_static(accept)),
+				ContentType::MsgPack,
+				"{accept}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_enabled_content_types() {
+		use super::{enabled_content_types, enabled_mime_types};
+
+		assert_eq!(enabled_content_types(), ContentType::all());
+		assert_eq!(enabled_mime_types().count(), enabled_content_types().len());
+
+		for mime in enabled_mime_types() {
+			assert!(mime.parse::<ContentType>().is_ok(), "{mime}");
+		}
+	}
+
+	#[test]
+	fn test_negotiate() {
+		use super::negotiate;
+
+		assert_eq!(
+			negotiate(
+				Some("text/html, application/cbor;q=0.9"),
+				Some("application/json")
+			),
+			ContentType::Cbor
+		);
+		assert_eq!(
+			negotiate(Some("text/html"), Some("application/cbor")),
+			ContentType::default()
+		);
+		assert_eq!(
+			negotiate(None, Some("application/vnd.msgpack; charset=utf-8")),
+			ContentType::MsgPack
+		);
+		assert_eq!(negotiate(None, Some("text/html")), ContentType::default());
+	}
+
+	#[test]
+	fn test_negotiate_exclusions() {
+		use super::{negotiate, ContentTypeNegotiator};
+
+		let negotiator = ContentTypeNegotiator::new();
+
+		for header in [
+			"application/json;q=0, */*",
+			"*/*;q=0.5, application/json;q=0, application/cbor;q=0.2",
+			"application/json;q=0, application/*;q=0.5, text/yaml",
+			"application/cbor;q=0.1, */*",
+			"text/html, application/xhtml+xml, */*;q=0.8",
+			"application/json;q=0, text/html",
+			"application/json;q=0, application/vnd.msgpack;q=0",
+		] {
+			let expected = accept(header).content_type();
+
+			assert_eq!(negotiate(Some(header), None), expected, "{header}");
+			assert_eq!(
+				negotiator.negotiate(&HeaderValue::from_static(header)),
+				expected,
+				"{header}"
+			);
+		}
+
+		assert_eq!(
+			negotiate(Some("application/json;q=0, */*"), None),
+			ContentType::MsgPack
+		);
+		assert_eq!(
+			negotiate(Some("application/json;q=0, text/html"), None),
+			ContentType::MsgPack
+		);
+	}
+
+	#[test]
+	fn test_octet_stream_mapping() {
+		use axum::http::Extensions;
+
+		use super::OctetStream;
+
+		let mut headers = HeaderMap::new();
+		headers.insert(
+			header::CONTENT_TYPE,
+			HeaderValue::from_static("application/octet-stream"),
+		);
+
+		let mut extensions = Extensions::new();
+		assert_eq!(
+			ContentType::from_request_headers(&headers, &extensions),
+			ContentType::default()
+		);
+
+		extensions.insert(OctetStream(ContentType::Bincode));
+		assert_eq!(
+			ContentType::from_request_headers(&headers, &extensions),
+			ContentType::Bincode
+		);
+	}
+
+	#[test]
+	fn test_format_query() {
+		use axum::http::{Extensions, Uri};
+
+		use super::FormatQuery;
+
+		let mut headers = HeaderMap::new();
+		headers.insert(header::ACCEPT, HeaderValue::from_static("text/html"));
+
+		let uri = Uri::from_static("/status?page=2&format=yaml");
+		let mut extensions = Extensions::new();
+
+		let accept = Accept::from_request_head(&uri, &headers, &extensions);
+		assert_eq!(accept.content_type(), ContentType::default());
+		assert!(accept.prefers_html());
+
+		extensions.insert(FormatQuery::default());
+
+		let accept = Accept::from_request_head(&uri, &headers, &extensions);
+		assert_eq!(accept.content_type(), ContentType::Yaml);
+		assert!(!accept.prefers_html());
+
+		extensions.insert(FormatQuery("as"));
+		assert_eq!(
+			Accept::from_request_head(&uri, &headers, &extensions).content_type(),
+			ContentType::default()
+		);
+
+		let uri = Uri::from_static("/status?as=xml");
+		assert_eq!(
+			Accept::from_request_head(&uri, &headers, &extensions).content_type(),
+			ContentType::default()
+		);
+
+		// Values are percent-decoded, and may be MIME types.
+		extensions.insert(FormatQuery::default());
+
+		for (uri, expected) in [
+			("/status?format=application%2Fjson", ContentType::Json),
+			("/status?format=application/vnd.msgpack", ContentType::MsgPack),
+			("/status?format=text%2Fyaml%3B%20charset%3Dutf-8", ContentType::Yaml),
+			("/status?f%6Frmat=%63bor", ContentType::Cbor),
+		] {
+			let uri = Uri::from_static(uri);
+
+			assert_eq!(
+				Accept::from_request_head(&uri, &headers, &extensions).content_type(),
+				expected,
+				"{uri}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_format_cookie() {
+		use axum::http::{Extensions, Uri};
+
+		use super::{FormatCookie, FormatQuery};
+
+		let uri = Uri::from_static("/status");
+		let mut headers = HeaderMap::new();
+		headers.insert(
+			header::COOKIE,
+			HeaderValue::from_static("session=abc; format=cbor"),
+		);
+
+		let mut extensions = Extensions::new();
+		assert_eq!(
+			Accept::from_request_head(&uri, &headers, &extensions).content_type(),
+			ContentType::default()
+		);
+
+		extensions.insert(FormatCookie::default());
+
+		let content_type = |headers: &HeaderMap, extensions: &Extensions| {
+			Accept::from_request_head(&uri, headers, extensions).content_type()
+		};
+
+		assert_eq!(content_type(&headers, &extensions), ContentType::Cbor);
+
+		headers.insert(header::ACCEPT, HeaderValue::from_static("*/*;q=0.8"));
+		assert_eq!(content_type(&headers, &extensions), ContentType::Cbor);
+
+		headers.insert(header::ACCEPT, HeaderValue::from_static("application/yaml"));
+		assert_eq!(content_type(&headers, &extensions), ContentType::Yaml);
+
+		// A wildcard excluded with `q=0` does not accept anything.
+		headers.insert(
+			header::ACCEPT,
+			HeaderValue::from_static("*/*;q=0, application/yaml"),
+		);
+		assert_eq!(content_type(&headers, &extensions), ContentType::Yaml);
+
+		headers.insert(header::ACCEPT, HeaderValue::from_static("*/*;q=0"));
+		assert_eq!(content_type(&headers, &extensions), ContentType::default());
+
+		headers.remove(header::ACCEPT);
+		headers.append(header::COOKIE, HeaderValue::from_static("theme=dark"));
+		assert_eq!(content_type(&headers, &extensions), ContentType::Cbor);
+
+		extensions.insert(FormatQuery::default());
+		assert_eq!(
+			Accept::from_request_head(
+				&Uri::from_static("/status?format=toml"),
+				&headers,
+				&extensions
+			)
+			.content_type(),
+			ContentType::Toml
+		);
+
+		extensions.insert(FormatCookie("fmt"));
+		assert_eq!(content_type(&headers, &extensions), ContentType::default());
+
+		headers.insert(header::COOKIE, HeaderValue::from_static("fmt=xml"));
+		assert_eq!(content_type(&headers, &extensions), ContentType::default());
+	}
+
+	#[cfg(feature = "encoding")]
+	#[test]
+	fn test_accept_charset() {
+		use super::AcceptCharset;
+
+		let mut headers = HeaderMap::new();
+		headers.insert(
+			header::ACCEPT_CHARSET,
+			HeaderValue::from_static("x-unknown, utf-16le;q=0.9, utf-8;q=0.1"),
+		);
+
+		let charset = AcceptCharset::from_headers(&headers);
+
+		assert_eq!(charset.encoding(), Some(encoding_rs::UTF_16LE));
+		assert_eq!(charset.encode("hi"), Some(vec![b'h', 0, b'i', 0]));
+
+		headers.insert(
+			header::ACCEPT_CHARSET,
+			HeaderValue::from_static("iso-8859-1"),
+		);
+
+		let charset = AcceptCharset::from_headers(&headers);
 
-		Ok(Self(header))
+		assert_eq!(
+			charset.encode("caf\u{e9}"),
+			Some(vec![b'c', b'a', b'f', 0xe9])
+		);
+		assert_eq!(charset.encode("\u{1f600}"), None);
 	}
 }
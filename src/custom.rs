@@ -0,0 +1,140 @@
+//! Support for formats that are not shipped with this crate.
+//!
+//! Custom codecs are registered once at startup with [`register`], which
+//! returns the [`ContentType`] that represents them. From then on, requests
+//! with a matching `Content-Type` (or `Accept`) header are decoded (or
+//! encoded) with the custom codec, before falling back to the built-in
+//! formats.
+//!
+//! Since the codec must work for any type, values are passed through
+//! [`erased_serde`]. As a result, only `serde`-based formats can be
+//! registered.
+//!
+//! # Examples
+//!
+//! ```edition2021
+//! # use axum_codec::{custom::{self, CustomCodec}, Codec, ContentType};
+//! # use axum::BoxError;
+//! #
+//! struct Json5;
+//!
+//! impl CustomCodec for Json5 {
+//!   fn content_type(&self) -> &'static str {
+//!     "application/json5"
+//!   }
+//!
+//!   fn encode(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, BoxError> {
+//!     // A real implementation would use a JSON5 serializer.
+//!     Ok(serde_json::to_vec(value)?)
+//!   }
+//!
+//!   fn decode(
+//!     &self,
+//!     bytes: &[u8],
+//!     visit: &mut dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), erased_serde::Error>,
+//!   ) -> Result<(), BoxError> {
+//!     let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+//!
+//!     Ok(visit(&mut <dyn erased_serde::Deserializer>::erase(&mut deserializer))?)
+//!   }
+//! }
+//!
+//! #[axum_codec::apply(encode, decode)]
+//! #[derive(Debug, PartialEq)]
+//! struct Point {
+//!   x: i32,
+//!   y: i32,
+//! }
+//!
+//! # fn main() {
+//! let json5 = custom::register(Json5);
+//!
+//! assert_eq!(json5.as_str(), "application/json5");
+//! assert_eq!("application/json5".parse::<ContentType>().unwrap(), json5);
+//!
+//! let bytes = Codec(Point { x: 1, y: 2 }).to_bytes(json5).unwrap();
+//! let Codec(point) = Codec::<Point>::from_bytes(&bytes, json5).unwrap();
+//!
+//! assert_eq!(point, Point { x: 1, y: 2 });
+//! # }
+//! ```
+
+use std::sync::RwLock;
+
+use axum::BoxError;
+
+use crate::ContentType;
+
+/// A format that can be registered at runtime with [`register`].
+pub trait CustomCodec: Send + Sync + 'static {
+	/// The MIME type of the format, such as `application/json5`.
+	///
+	/// Requests are matched against this value, ignoring any parameters.
+	fn content_type(&self) -> &'static str;
+
+	/// Returns `true` if the encoded output is always valid UTF-8.
+	fn is_text(&self) -> bool {
+		false
+	}
+
+	/// Serializes the value.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the value could not be serialized.
+	fn encode(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, BoxError>;
+
+	/// Creates a deserializer for the given bytes and passes it to `visit`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `visit` fails, or if the bytes could not be read.
+	fn decode(
+		&self,
+		bytes: &[u8],
+		visit: &mut dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), erased_serde::Error>,
+	) -> Result<(), BoxError>;
+}
+
+/// An opaque identifier for a registered [`CustomCodec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomId(usize);
+
+static REGISTRY: RwLock<Vec<&'static dyn CustomCodec>> = RwLock::new(Vec::new());
+
+/// Registers a custom codec, returning the [`ContentType`] that represents it.
+///
+/// Registering a codec with the same MIME type as an existing custom codec
+/// replaces it for new lookups. Codecs are never unregistered, so this should
+/// only be called during startup.
+pub fn register<C: CustomCodec>(codec: C) -> ContentType {
+	let mut registry = REGISTRY
+		.write()
+		.unwrap_or_else(std::sync::PoisonError::into_inner);
+
+	registry.push(Box::leak(Box::new(codec)));
+
+	ContentType::Custom(CustomId(registry.len() - 1))
+}
+
+/// Finds the most recently registered codec with the given MIME type.
+pub(crate) fn find(essence: &str) -> Option<ContentType> {
+	let registry = REGISTRY
+		.read()
+		.unwrap_or_else(std::sync::PoisonError::into_inner);
+
+	registry
+		.iter()
+		.rposition(|codec| codec.content_type().eq_ignore_ascii_case(essence))
+		.map(|index| ContentType::Custom(CustomId(index)))
+}
+
+/// Returns the codec with the given identifier.
+pub(crate) fn get(id: CustomId) -> &'static dyn CustomCodec {
+	let registry = REGISTRY
+		.read()
+		.unwrap_or_else(std::sync::PoisonError::into_inner);
+
+	// Identifiers can only be created by `register`, so they are always valid.
+	registry[id.0]
+}
@@ -21,7 +21,7 @@ where
 	#[cfg(feature = "json")]
 	#[inline]
 	pub fn from_json(bytes: &[u8]) -> Result<Self, serde_json::Error> {
-		serde_json::from_slice(bytes).map(Self)
+		crate::json::from_slice(bytes).map(Self)
 	}
 
 	/// Attempts to deserialize the given bytes as [MessagePack](https://msgpack.org).
@@ -43,15 +43,32 @@ where
 	/// Does not perform any validation if the `validator` feature is enabled. For
 	/// validation, use [`Self::from_bytes`].
 	///
+	/// Before decoding, every length declared by the input is checked against the
+	/// size of the input, so a small payload cannot request a large allocation.
+	///
 	/// # Errors
 	///
 	/// See [`ciborium::from_slice`].
 	#[cfg(feature = "cbor")]
 	#[inline]
 	pub fn from_cbor(bytes: &[u8]) -> Result<Self, ciborium::de::Error<std::io::Error>> {
+		check_cbor_lengths(bytes)?;
 		ciborium::from_reader(bytes).map(Self)
 	}
 
+	/// Attempts to deserialize the given bytes as [BSON](https://bsonspec.org).
+	/// Does not perform any validation if the `validator` feature is enabled. For
+	/// validation, use [`Self::from_bytes`].
+	///
+	/// # Errors
+	///
+	/// See [`bson::from_slice`].
+	#[cfg(feature = "bson")]
+	#[inline]
+	pub fn from_bson(bytes: &[u8]) -> Result<Self, bson::de::Error> {
+		bson::from_slice(bytes).map(Self)
+	}
+
 	/// Attempts to deserialize the given text as [YAML](https://yaml.org).
 	/// Does not perform any validation if the `validator` feature is enabled. For
 	/// validation, use [`Self::from_bytes`].
@@ -61,8 +78,8 @@ where
 	/// See [`serde_yaml::from_slice`].
 	#[cfg(feature = "yaml")]
 	#[inline]
-	pub fn from_yaml(text: &str) -> Result<Self, serde_yaml::Error> {
-		serde_yaml::from_str(text).map(Self)
+	pub fn from_yaml(text: &str) -> Result<Self, crate::yaml::Error> {
+		crate::yaml::from_str(text).map(Self)
 	}
 
 	/// Attempts to deserialize the given text as [TOML](https://toml.io).
@@ -77,6 +94,239 @@ where
 	pub fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
 		toml::from_str(text).map(Self)
 	}
+
+	/// Attempts to deserialize the given text as [RON](https://github.com/ron-rs/ron).
+	/// Does not perform any validation if the `validator` feature is enabled. For
+	/// validation, use [`Self::from_bytes`].
+	///
+	/// # Errors
+	///
+	/// See [`ron::from_str`].
+	#[cfg(feature = "ron")]
+	#[inline]
+	pub fn from_ron(text: &str) -> Result<Self, ron::error::SpannedError> {
+		ron::from_str(text).map(Self)
+	}
+
+	/// Attempts to deserialize the given bytes as an HTML form
+	/// (`application/x-www-form-urlencoded`).
+	/// Does not perform any validation if the `validator` feature is enabled.
+	///
+	/// Unlike the strict decoder used by [`axum::Form`] (`serde_urlencoded`),
+	/// repeated keys such as `tag=a&tag=b` (submitted by checkbox groups and
+	/// multi-selects) are collected into sequences like `Vec<String>`. A key
+	/// that appears only once can still be decoded into a sequence.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Codec;
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Filter {
+	///   query: String,
+	///   tag: Vec<String>,
+	/// }
+	///
+	/// # fn main() {
+	/// let Codec(filter) = Codec::<Filter>::from_form_multi(b"query=rust&tag=a&tag=b").unwrap();
+	///
+	/// assert_eq!(filter.tag, ["a", "b"]);
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`serde_html_form::from_bytes`].
+	#[cfg(feature = "form")]
+	#[inline]
+	pub fn from_form_multi(bytes: &[u8]) -> Result<Self, serde_html_form::de::Error> {
+		serde_html_form::from_bytes(bytes).map(Self)
+	}
+
+	/// Attempts to deserialize the given [`serde_json::Value`].
+	/// Does not perform any validation if the `validator` feature is enabled. For
+	/// validation, use [`Self::from_value`].
+	///
+	/// # Errors
+	///
+	/// See [`serde_json::from_value`].
+	#[cfg(feature = "json")]
+	#[inline]
+	pub fn from_json_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+		serde_json::from_value(value).map(Self)
+	}
+
+	/// Attempts to deserialize the given [`rmpv::Value`].
+	/// Does not perform any validation if the `validator` feature is enabled. For
+	/// validation, use [`Self::from_value`].
+	///
+	/// # Errors
+	///
+	/// See [`rmpv::ext::from_value`].
+	#[cfg(feature = "msgpack")]
+	#[inline]
+	pub fn from_msgpack_value(value: rmpv::Value) -> Result<Self, rmp_serde::decode::Error> {
+		rmpv::ext::from_value(value)
+			.map(Self)
+			.map_err(|err| rmp_serde::decode::Error::Uncategorized(err.to_string()))
+	}
+
+	/// Attempts to deserialize the given [`ciborium::Value`].
+	/// Does not perform any validation if the `validator` feature is enabled. For
+	/// validation, use [`Self::from_value`].
+	///
+	/// # Errors
+	///
+	/// See [`ciborium::Value::deserialized`].
+	#[cfg(feature = "cbor")]
+	#[inline]
+	pub fn from_cbor_value(
+		value: &ciborium::Value,
+	) -> Result<Self, ciborium::de::Error<std::io::Error>> {
+		value
+			.deserialized()
+			.map(Self)
+			.map_err(|err| ciborium::de::Error::Semantic(None, err.to_string()))
+	}
+
+	#[cfg(feature = "custom")]
+	fn from_custom(bytes: &[u8], id: crate::custom::CustomId) -> Result<Self, CodecRejection> {
+		let mut value = None;
+
+		crate::custom::get(id)
+			.decode(bytes, &mut |deserializer| {
+				value = Some(erased_serde::deserialize(deserializer)?);
+				Ok(())
+			})
+			.map_err(CodecRejection::Custom)?;
+
+		value
+			.map(Self)
+			.ok_or_else(|| CodecRejection::Custom("custom codec did not produce a value".into()))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<T> Codec<T> {
+	/// Attempts to deserialize the given bytes as [JSON](https://www.json.org)
+	/// with a [`DeserializeSeed`](serde::de::DeserializeSeed), for stateful
+	/// decoding such as interning strings.
+	/// Does not perform any validation if the `validator` feature is enabled.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Codec;
+	/// # use serde::{de::DeserializeSeed, Deserialize, Deserializer};
+	/// #
+	/// struct Prefixed<'a>(&'a str);
+	///
+	/// impl<'de> DeserializeSeed<'de> for Prefixed<'_> {
+	///   type Value = String;
+	///
+	///   fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+	///     let name = String::deserialize(deserializer)?;
+	///
+	///     Ok(format!("{}{name}", self.0))
+	///   }
+	/// }
+	///
+	/// # fn main() {
+	/// let Codec(key) = Codec::from_json_seed(Prefixed("user:"), b"\"alice\"").unwrap();
+	///
+	/// assert_eq!(key, "user:alice");
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`serde_json::from_slice`].
+	#[cfg(feature = "json")]
+	pub fn from_json_seed<'de, S>(seed: S, bytes: &'de [u8]) -> Result<Self, serde_json::Error>
+	where
+		S: serde::de::DeserializeSeed<'de, Value = T>,
+	{
+		let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+		let value = seed.deserialize(&mut deserializer)?;
+
+		deserializer.end()?;
+
+		Ok(Self(value))
+	}
+
+	/// Attempts to deserialize the given bytes as the specified [`ContentType`]
+	/// with a [`DeserializeSeed`](serde::de::DeserializeSeed).
+	/// Does not perform any validation if the `validator` feature is enabled.
+	///
+	/// Only JSON, `MessagePack`, BSON, YAML, TOML and RON are supported. CBOR
+	/// does not expose a deserializer that can drive a seed, and Bincode and
+	/// Bitcode do not use `serde`, so they (and custom formats) return
+	/// [`CodecRejection::UnsupportedSeed`].
+	///
+	/// Like [`Self::from_bytes`], a leading UTF-8 byte order mark is ignored for
+	/// text formats.
+	///
+	/// # Errors
+	///
+	/// See [`CodecRejection`].
+	#[cfg_attr(
+		not(any(
+			feature = "json",
+			feature = "msgpack",
+			feature = "bson",
+			feature = "yaml",
+			feature = "toml",
+			feature = "ron"
+		)),
+		allow(unused_variables, clippy::needless_pass_by_value)
+	)]
+	pub fn from_bytes_seed<'de, S>(
+		seed: S,
+		bytes: &'de [u8],
+		content_type: ContentType,
+	) -> Result<Self, CodecRejection>
+	where
+		S: serde::de::DeserializeSeed<'de, Value = T>,
+	{
+		let bytes = strip_utf8_bom(bytes, content_type);
+		let value = match content_type {
+			#[cfg(feature = "json")]
+			ContentType::Json => Self::from_json_seed(seed, bytes)?.0,
+			#[cfg(feature = "msgpack")]
+			ContentType::MsgPack => {
+				let mut deserializer = rmp_serde::Deserializer::new(bytes).with_human_readable();
+
+				seed.deserialize(&mut deserializer)?
+			}
+			#[cfg(feature = "bson")]
+			ContentType::Bson => {
+				let document = bson::from_slice::<bson::Document>(bytes)?;
+
+				seed.deserialize(bson::Deserializer::new(bson::Bson::Document(document)))?
+			}
+			#[cfg(feature = "yaml")]
+			ContentType::Yaml => seed.deserialize(crate::yaml::Deserializer::from_slice(bytes))?,
+			#[cfg(feature = "toml")]
+			ContentType::Toml => {
+				let text = core::str::from_utf8(bytes)?;
+
+				seed
+					.deserialize(toml::Deserializer::new(text))
+					.map_err(|err| crate::rejection::TomlError::new(err, text))?
+			}
+			#[cfg(feature = "ron")]
+			ContentType::Ron => {
+				let mut deserializer = ron::Deserializer::from_bytes(bytes)?;
+
+				seed
+					.deserialize(&mut deserializer)
+					.and_then(|value| deserializer.end().map(|()| value))
+					.map_err(|err| deserializer.span_error(err))?
+			}
+			#[allow(unreachable_patterns)]
+			_ => return Err(CodecRejection::UnsupportedSeed(content_type)),
+		};
+
+		#[allow(unreachable_code)]
+		Ok(Self(value))
+	}
 }
 
 impl<T> Codec<T> {
@@ -112,8 +362,47 @@ impl<T> Codec<T> {
 		bitcode::decode(bytes).map(Self)
 	}
 
+	/// Attempts to deserialize an already-parsed, self-describing value, such as
+	/// a [`serde_json::Value`] produced by a middleware.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Codec;
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Greeting {
+	///   hello: String,
+	/// }
+	///
+	/// # fn main() {
+	/// let value = serde_json::json!({ "hello": "world" });
+	/// let Codec(greeting) = Codec::<Greeting>::from_value(value).unwrap();
+	///
+	/// assert_eq!(greeting.hello, "world");
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`CodecRejection`].
+	#[cfg(feature = "serde")]
+	pub fn from_value<V>(value: V) -> Result<Self, CodecRejection>
+	where
+		T: CodecDecode,
+		V: DynamicValue,
+	{
+		let codec = Self(value.deserialize_into()?);
+
+		#[cfg(feature = "validator")]
+		validator::Validate::validate(&codec)?;
+
+		Ok(codec)
+	}
+
 	/// Attempts to deserialize the given bytes as the specified [`ContentType`].
 	///
+	/// For text formats, a leading UTF-8 byte order mark (often added by Windows
+	/// editors) is ignored.
+	///
 	/// # Errors
 	///
 	/// See [`CodecRejection`].
@@ -121,6 +410,7 @@ impl<T> Codec<T> {
 	where
 		T: CodecDecode,
 	{
+		let bytes = strip_utf8_bom(bytes, content_type);
 		let codec = match content_type {
 			#[cfg(feature = "json")]
 			ContentType::Json => Self::from_json(bytes)?,
@@ -132,10 +422,20 @@ impl<T> Codec<T> {
 			ContentType::Bitcode => Self::from_bitcode(bytes)?,
 			#[cfg(feature = "cbor")]
 			ContentType::Cbor => Self::from_cbor(bytes)?,
+			#[cfg(feature = "bson")]
+			ContentType::Bson => Self::from_bson(bytes)?,
 			#[cfg(feature = "yaml")]
 			ContentType::Yaml => Self::from_yaml(core::str::from_utf8(bytes)?)?,
 			#[cfg(feature = "toml")]
-			ContentType::Toml => Self::from_toml(core::str::from_utf8(bytes)?)?,
+			ContentType::Toml => {
+				let text = core::str::from_utf8(bytes)?;
+
+				Self::from_toml(text).map_err(|err| crate::rejection::TomlError::new(err, text))?
+			}
+			#[cfg(feature = "ron")]
+			ContentType::Ron => Self::from_ron(core::str::from_utf8(bytes)?)?,
+			#[cfg(feature = "custom")]
+			ContentType::Custom(id) => Self::from_custom(bytes, id)?,
 		};
 
 		#[cfg(feature = "validator")]
@@ -143,4 +443,482 @@ impl<T> Codec<T> {
 
 		Ok(codec)
 	}
+
+	/// Attempts to deserialize the given bytes as the specified [`ContentType`],
+	/// rejecting input longer than `LIMIT` bytes before decoding it.
+	///
+	/// This allows choosing a limit per call site instead of for a whole router
+	/// with [`DefaultBodyLimit`](axum::extract::DefaultBodyLimit).
+	///
+	/// The limit is also passed to the formats that can bound their own
+	/// allocations, so a small payload cannot declare a huge collection:
+	///
+	/// - Bincode uses it as its byte limit, which counts the memory claimed for
+	///   each collection (its declared length times the size of an element) as
+	///   well as the bytes read. Collections of elements that are larger in
+	///   memory than on the wire may need a higher limit.
+	/// - CBOR already rejects declared lengths that exceed the input, so they
+	///   are bounded by the limit as well.
+	///
+	/// The other formats only have their input capped. They are decoded with
+	/// `serde`, which pre-allocates at most 1 MiB per collection regardless of
+	/// its declared length.
+	///
+	/// ```edition2021
+	/// # use axum_codec::{Codec, ContentType};
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Greeting {
+	///   hello: String,
+	/// }
+	///
+	/// # fn main() {
+	/// let bytes = br#"{"hello":"world"}"#;
+	///
+	/// assert!(Codec::<Greeting>::from_bytes_with_limit::<64>(bytes, ContentType::Json).is_ok());
+	/// assert!(Codec::<Greeting>::from_bytes_with_limit::<8>(bytes, ContentType::Json).is_err());
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns [`CodecRejection::PayloadTooLarge`] (which has a status code of
+	/// `413 Payload Too Large`) if the input is too long, or if Bincode exceeds
+	/// the limit while decoding. See [`CodecRejection`] for other errors.
+	pub fn from_bytes_with_limit<const LIMIT: usize>(
+		bytes: &[u8],
+		content_type: ContentType,
+	) -> Result<Self, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		if bytes.len() > LIMIT {
+			return Err(CodecRejection::PayloadTooLarge { limit: LIMIT });
+		}
+
+		#[cfg(feature = "bincode")]
+		if content_type == ContentType::Bincode {
+			let config = bincode::config::standard().with_limit::<LIMIT>();
+			let codec = match bincode::decode_from_slice(bytes, config) {
+				Ok((value, _)) => Self(value),
+				Err(bincode::error::DecodeError::LimitExceeded) => {
+					return Err(CodecRejection::PayloadTooLarge { limit: LIMIT });
+				}
+				Err(err) => return Err(err.into()),
+			};
+
+			#[cfg(feature = "validator")]
+			validator::Validate::validate(&codec)?;
+
+			return Ok(codec);
+		}
+
+		Self::from_bytes(bytes, content_type)
+	}
+
+	/// Attempts to deserialize base64-encoded bytes as the specified
+	/// [`ContentType`], for binary payloads sent over channels that only carry
+	/// text.
+	///
+	/// Both padded and unpadded standard base64 are accepted, and whitespace
+	/// (such as the line breaks inserted by MIME encoders) is ignored. The
+	/// [`Codec`] extractor calls this automatically for requests with a
+	/// `Content-Transfer-Encoding: base64` header.
+	///
+	/// ```edition2021
+	/// # use axum_codec::{Codec, ContentType};
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Greeting {
+	///   hello: String,
+	/// }
+	///
+	/// # fn main() {
+	/// let Codec(greeting) =
+	///   Codec::<Greeting>::from_base64_bytes(b"eyJoZWxsbyI6IndvcmxkIn0=", ContentType::Json).unwrap();
+	///
+	/// assert_eq!(greeting.hello, "world");
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns [`CodecRejection::Base64`] if the input is not valid base64. See
+	/// [`Self::from_bytes`] for other errors.
+	#[cfg(feature = "base64")]
+	pub fn from_base64_bytes(bytes: &[u8], content_type: ContentType) -> Result<Self, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		Self::from_bytes(&decode_base64(bytes)?, content_type)
+	}
+
+	/// Attempts to deserialize the output of a reader as the specified
+	/// [`ContentType`], validating it if the `validator` feature is enabled.
+	///
+	/// JSON, `MessagePack` and Bincode are decoded directly from the reader, so
+	/// memory use does not depend on the size of the input. Every other format
+	/// needs the whole input at once, so it is read into memory and decoded with
+	/// [`Self::from_bytes`].
+	///
+	/// # Errors
+	///
+	/// Returns [`CodecRejection::Body`] if the reader fails. See
+	/// [`Self::from_bytes`] for other errors.
+	pub fn from_reader<R: std::io::Read>(
+		reader: R,
+		content_type: ContentType,
+	) -> Result<Self, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		use std::io::{BufRead, Read};
+
+		let mut reader = std::io::BufReader::new(reader);
+
+		if content_type.is_text() {
+			let buf = reader.fill_buf().map_err(io_rejection)?;
+
+			if buf.starts_with(UTF8_BOM) {
+				reader.consume(UTF8_BOM.len());
+			}
+		}
+
+		#[cfg_attr(
+			not(any(feature = "json", feature = "msgpack", feature = "bincode")),
+			allow(unused_variables)
+		)]
+		let codec = match content_type {
+			#[cfg(feature = "json")]
+			ContentType::Json => Self(serde_json::from_reader(reader)?),
+			#[cfg(feature = "msgpack")]
+			ContentType::MsgPack => {
+				let mut deserializer = rmp_serde::Deserializer::new(reader).with_human_readable();
+
+				Self(serde::Deserialize::deserialize(&mut deserializer)?)
+			}
+			#[cfg(feature = "bincode")]
+			ContentType::Bincode => Self(bincode::decode_from_std_read(
+				&mut reader,
+				bincode::config::standard(),
+			)?),
+			#[allow(unreachable_patterns)]
+			_ => {
+				let mut bytes = Vec::new();
+				reader.read_to_end(&mut bytes).map_err(io_rejection)?;
+
+				return Self::from_bytes(&bytes, content_type);
+			}
+		};
+
+		#[cfg(feature = "validator")]
+		#[allow(unreachable_code)]
+		validator::Validate::validate(&codec)?;
+
+		#[allow(unreachable_code)]
+		Ok(codec)
+	}
+
+	/// Attempts to deserialize the given bytes as the specified [`ContentType`],
+	/// returning the content type the value was decoded as alongside it.
+	///
+	/// Useful for audit logging and metrics, which can record the format without
+	/// having to carry it separately.
+	///
+	/// ```edition2021
+	/// # use axum_codec::{Codec, ContentType};
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Greeting {
+	///   hello: String,
+	/// }
+	///
+	/// # fn main() {
+	/// let (Codec(greeting), content_type) =
+	///   Codec::<Greeting>::from_bytes_typed(b"{\"hello\": \"world\"}", ContentType::Json).unwrap();
+	///
+	/// assert_eq!(greeting.hello, "world");
+	/// assert_eq!(content_type, ContentType::Json);
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`CodecRejection`].
+	pub fn from_bytes_typed(
+		bytes: &[u8],
+		content_type: ContentType,
+	) -> Result<(Self, ContentType), CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		Self::from_bytes(bytes, content_type).map(|codec| (codec, content_type))
+	}
+
+	/// Attempts to deserialize the given bytes as the specified [`ContentType`]
+	/// into an [`Arc`](std::sync::Arc), ready to be stored in shared state.
+	///
+	/// Every format can decode `Codec<Arc<T>>` directly, but `validator` does
+	/// not implement `Validate` for `Arc<T>`, so this is the only way to get an
+	/// `Arc` when the `validator` feature is enabled. The value is moved into
+	/// the `Arc` after validation, so it is never cloned.
+	///
+	/// ```edition2021
+	/// # use std::sync::Arc;
+	/// # use axum_codec::{Codec, ContentType};
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Catalog {
+	///   items: Vec<String>,
+	/// }
+	///
+	/// # fn main() {
+	/// let Codec(catalog) =
+	///   Codec::<Catalog>::from_bytes_arc(b"{\"items\": [\"a\"]}", ContentType::Json).unwrap();
+	///
+	/// let shared: Arc<Catalog> = catalog;
+	/// assert_eq!(shared.items, ["a"]);
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`Self::from_bytes`].
+	pub fn from_bytes_arc(
+		bytes: &[u8],
+		content_type: ContentType,
+	) -> Result<Codec<std::sync::Arc<T>>, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		Self::from_bytes(bytes, content_type).map(|Codec(data)| Codec(std::sync::Arc::new(data)))
+	}
+
+	/// Attempts to deserialize the given bytes as a [JSON](https://www.json.org)
+	/// array, decoding (and validating) each element independently.
+	///
+	/// Unlike decoding a `Vec<T>`, a malformed element does not fail the whole
+	/// batch. The returned results are in the same order as the elements, so
+	/// their indices can be used to report partial failures.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Codec;
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Item {
+	///   id: u32,
+	/// }
+	///
+	/// # fn main() {
+	/// let items = Codec::<Item>::from_bytes_batch(b"[{\"id\": 1}, {\"id\": \"two\"}]").unwrap();
+	///
+	/// assert_eq!(items[0].as_ref().unwrap().id, 1);
+	/// assert!(items[1].is_err());
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns an error if the bytes are not a JSON array. Errors for individual
+	/// elements are returned in the inner results.
+	#[cfg(feature = "json")]
+	pub fn from_bytes_batch(bytes: &[u8]) -> Result<Vec<Result<Self, CodecRejection>>, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		let elements = serde_json::from_slice::<Vec<&serde_json::value::RawValue>>(bytes)?;
+
+		Ok(
+			elements
+				.into_iter()
+				.map(|element| Self::from_bytes(element.get().as_bytes(), ContentType::Json))
+				.collect(),
+		)
+	}
+
+	/// Attempts to deserialize the given bytes as a
+	/// [JSON text sequence](https://www.rfc-editor.org/rfc/rfc7464)
+	/// (`application/json-seq`), decoding (and validating) each record.
+	///
+	/// Each record is preceded by a record separator (`0x1E`), and empty records
+	/// are skipped. To decode a sequence as it is being received, use
+	/// [`CodecStream`](crate::CodecStream) instead.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Codec;
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Event {
+	///   id: u32,
+	/// }
+	///
+	/// # fn main() {
+	/// let events = Codec::<Event>::from_json_seq(b"\x1e{\"id\": 1}\n\x1e{\"id\": 2}\n").unwrap();
+	///
+	/// assert_eq!(events[1].id, 2);
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns the error of the first record that fails to decode. See
+	/// [`CodecRejection`].
+	#[cfg(feature = "json")]
+	pub fn from_json_seq(bytes: &[u8]) -> Result<Vec<Self>, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		bytes
+			.split(|&b| b == crate::stream::RECORD_SEPARATOR)
+			.filter(|record| !record.trim_ascii().is_empty())
+			.map(|record| Self::from_bytes(record, ContentType::Json))
+			.collect()
+	}
+}
+
+/// A self-describing value that can be deserialized into any type, such as
+/// [`serde_json::Value`], [`rmpv::Value`], or [`ciborium::Value`].
+///
+/// Used by [`Codec::from_value`].
+#[cfg(feature = "serde")]
+pub trait DynamicValue {
+	/// Deserializes the value into `T`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the value does not match the shape of `T`.
+	fn deserialize_into<T>(self) -> Result<T, CodecRejection>
+	where
+		T: serde::de::DeserializeOwned;
+}
+
+#[cfg(feature = "json")]
+impl DynamicValue for serde_json::Value {
+	fn deserialize_into<T>(self) -> Result<T, CodecRejection>
+	where
+		T: serde::de::DeserializeOwned,
+	{
+		Ok(Codec::from_json_value(self)?.0)
+	}
+}
+
+#[cfg(feature = "msgpack")]
+impl DynamicValue for rmpv::Value {
+	fn deserialize_into<T>(self) -> Result<T, CodecRejection>
+	where
+		T: serde::de::DeserializeOwned,
+	{
+		Ok(Codec::from_msgpack_value(self)?.0)
+	}
+}
+
+#[cfg(feature = "cbor")]
+impl DynamicValue for ciborium::Value {
+	fn deserialize_into<T>(self) -> Result<T, CodecRejection>
+	where
+		T: serde::de::DeserializeOwned,
+	{
+		Ok(Codec::from_cbor_value(&self)?.0)
+	}
+}
+
+/// Returns `true` if the request declares its body as base64-encoded with a
+/// `Content-Transfer-Encoding` header.
+#[cfg(feature = "base64")]
+pub(crate) fn is_base64_transfer(headers: &axum::http::HeaderMap) -> bool {
+	headers
+		.get("content-transfer-encoding")
+		.and_then(|value| value.to_str().ok())
+		.is_some_and(|value| value.trim().eq_ignore_ascii_case("base64"))
+}
+
+/// Decodes padded or unpadded standard base64, ignoring whitespace.
+#[cfg(feature = "base64")]
+pub(crate) fn decode_base64(bytes: &[u8]) -> Result<Vec<u8>, CodecRejection> {
+	use base64::{
+		alphabet,
+		engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+		Engine,
+	};
+
+	const BASE64: GeneralPurpose = GeneralPurpose::new(
+		&alphabet::STANDARD,
+		GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+	);
+
+	let encoded = bytes
+		.iter()
+		.copied()
+		.filter(|b| !b.is_ascii_whitespace())
+		.collect::<Vec<_>>();
+
+	Ok(BASE64.decode(encoded)?)
+}
+
+/// The UTF-8 byte order mark, which some editors and Windows tools prepend to
+/// text files.
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Converts an I/O error from a reader into a rejection.
+pub(crate) fn io_rejection(err: std::io::Error) -> CodecRejection {
+	CodecRejection::Body(axum::Error::new(err))
+}
+
+/// Removes a leading UTF-8 byte order mark from the input of text formats,
+/// which most parsers (including `serde_json`) reject.
+fn strip_utf8_bom(bytes: &[u8], content_type: ContentType) -> &[u8] {
+	match bytes.strip_prefix(UTF8_BOM) {
+		Some(rest) if content_type.is_text() => rest,
+		_ => bytes,
+	}
+}
+
+/// Ensures that every length declared by a CBOR header fits in the remaining
+/// input. Each array element needs at least one byte, and each map entry at
+/// least two.
+#[cfg(feature = "cbor")]
+fn check_cbor_lengths(bytes: &[u8]) -> Result<(), ciborium::de::Error<std::io::Error>> {
+	use ciborium::de::Error;
+	use ciborium_io::Read;
+	use ciborium_ll::{Decoder, Header};
+
+	let mut decoder = Decoder::from(bytes);
+	let mut scratch = [0; 4096];
+
+	while decoder.offset() < bytes.len() {
+		let offset = decoder.offset();
+		let header = decoder.pull().map_err(|err| match err {
+			ciborium_ll::Error::Io(..) => Error::Io(std::io::ErrorKind::UnexpectedEof.into()),
+			ciborium_ll::Error::Syntax(offset) => Error::Syntax(offset),
+		})?;
+
+		let (len, min_size, skip) = match header {
+			Header::Bytes(Some(len)) | Header::Text(Some(len)) => (len, 1, true),
+			Header::Array(Some(len)) => (len, 1, false),
+			Header::Map(Some(len)) => (len, 2, false),
+			_ => continue,
+		};
+
+		if len.saturating_mul(min_size) > bytes.len() - decoder.offset() {
+			return Err(Error::Semantic(
+				Some(offset),
+				"declared length exceeds the size of the input".into(),
+			));
+		}
+
+		if skip {
+			let mut remaining = len;
+
+			while remaining > 0 {
+				let chunk = remaining.min(scratch.len());
+
+				decoder
+					.read_exact(&mut scratch[..chunk])
+					.map_err(|_| Error::Io(std::io::ErrorKind::UnexpectedEof.into()))?;
+				remaining -= chunk;
+			}
+		}
+	}
+
+	Ok(())
 }
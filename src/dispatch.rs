@@ -0,0 +1,301 @@
+use std::ops::{Deref, DerefMut};
+
+use axum::{
+	extract::{FromRequest, Request},
+	response::Response,
+};
+
+use crate::{
+	extract::RequestBody, Accept, Codec, CodecDecode, CodecRejection, ContentType, IntoCodecResponse,
+};
+
+/// Decodes a request body into one of several payload types, chosen by the
+/// value of a header instead of a tag inside the body.
+///
+/// Implement this for an enum and extract it with [`Dispatched`].
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{dispatch::{Dispatch, Dispatched, Payload}, CodecRejection};
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct CreateUser {
+///   name: String,
+/// }
+///
+/// #[axum_codec::apply(decode)]
+/// struct DeleteUser {
+///   id: u64,
+/// }
+///
+/// enum Command {
+///   Create(CreateUser),
+///   Delete(DeleteUser),
+/// }
+///
+/// impl Dispatch for Command {
+///   const HEADER: &'static str = "x-command-type";
+///
+///   fn dispatch(payload: Payload<'_>) -> Result<Self, CodecRejection> {
+///     match payload.discriminator() {
+///       "create" => payload.decode().map(Self::Create),
+///       "delete" => payload.decode().map(Self::Delete),
+///       _ => Err(payload.unknown()),
+///     }
+///   }
+/// }
+///
+/// async fn command(Dispatched(command): Dispatched<Command>) -> &'static str {
+///   match command {
+///     Command::Create(..) => "created",
+///     Command::Delete(..) => "deleted",
+///   }
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub trait Dispatch: Sized {
+	/// The name of the header containing the discriminator.
+	const HEADER: &'static str;
+
+	/// Decodes the payload based on its discriminator.
+	///
+	/// Return [`Payload::unknown`] if the discriminator does not match any
+	/// variant.
+	///
+	/// # Errors
+	///
+	/// See [`Payload::decode`] and [`Payload::unknown`].
+	fn dispatch(payload: Payload<'_>) -> Result<Self, CodecRejection>;
+}
+
+/// A request body along with the discriminator that decides which type to
+/// decode it into. Passed to [`Dispatch::dispatch`].
+#[derive(Clone, Copy, Debug)]
+pub struct Payload<'a> {
+	header: &'static str,
+	discriminator: &'a str,
+	bytes: &'a [u8],
+	content_type: ContentType,
+}
+
+impl<'a> Payload<'a> {
+	/// Returns the value of the discriminator header.
+	#[must_use]
+	pub fn discriminator(&self) -> &'a str {
+		self.discriminator
+	}
+
+	/// Returns the content type of the body.
+	#[must_use]
+	pub fn content_type(&self) -> ContentType {
+		self.content_type
+	}
+
+	/// Decodes the body into `T`, validating it if the `validator` feature
+	/// is enabled.
+	///
+	/// # Errors
+	///
+	/// See [`Codec::from_bytes`].
+	pub fn decode<T: CodecDecode>(self) -> Result<T, CodecRejection> {
+		Codec::from_bytes(self.bytes, self.content_type).map(|Codec(data)| data)
+	}
+
+	/// Returns the rejection for a discriminator that does not match any
+	/// variant.
+	#[must_use]
+	pub fn unknown(&self) -> CodecRejection {
+		CodecRejection::UnknownDiscriminator {
+			header: self.header,
+			value: self.discriminator.into(),
+		}
+	}
+}
+
+/// Extractor for types implementing [`Dispatch`].
+///
+/// Rejects the request with `400 Bad Request` if the discriminator header is
+/// missing or not valid UTF-8.
+pub struct Dispatched<T>(pub T);
+
+impl<T> Dispatched<T> {
+	/// Consumes the [`Dispatched`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T> Deref for Dispatched<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Dispatched<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for Dispatched<T>
+where
+	T: Dispatch,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+
+		let discriminator = match req.headers().get(T::HEADER) {
+			Some(value) => {
+				value
+					.to_str()
+					.map(str::to_owned)
+					.map_err(|_| CodecRejection::UnknownDiscriminator {
+						header: T::HEADER,
+						value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+					})
+			}
+			None => Err(CodecRejection::MissingDiscriminator { header: T::HEADER }),
+		}
+		.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		let body = RequestBody::from_request(req, state)
+			.await
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		T::dispatch(Payload {
+			header: T::HEADER,
+			discriminator: &discriminator,
+			bytes: &body.bytes,
+			content_type: body.content_type,
+		})
+		.map(Self)
+		.map_err(|e| e.into_codec_response(accept.into()))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for Dispatched<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		body::Body,
+		extract::FromRequest,
+		http::{Request, StatusCode},
+	};
+
+	use super::{Dispatch, Dispatched, Payload};
+	use crate::CodecRejection;
+
+	#[crate::apply(decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Rename {
+		name: String,
+	}
+
+	#[crate::apply(decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Delete {
+		id: u64,
+	}
+
+	#[derive(Debug, PartialEq)]
+	enum Command {
+		Rename(Rename),
+		Delete(Delete),
+	}
+
+	impl Dispatch for Command {
+		const HEADER: &'static str = "x-command-type";
+
+		fn dispatch(payload: Payload<'_>) -> Result<Self, CodecRejection> {
+			match payload.discriminator() {
+				"rename" => payload.decode().map(Self::Rename),
+				"delete" => payload.decode().map(Self::Delete),
+				_ => Err(payload.unknown()),
+			}
+		}
+	}
+
+	fn request(command: Option<&str>, body: &'static str) -> Request<Body> {
+		let mut request = Request::builder().header("content-type", "application/json");
+
+		if let Some(command) = command {
+			request = request.header("x-command-type", command);
+		}
+
+		request.body(Body::from(body)).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_dispatch() {
+		let Ok(Dispatched(command)) =
+			Dispatched::<Command>::from_request(request(Some("rename"), "{\"name\":\"a\"}"), &()).await
+		else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(command, Command::Rename(Rename { name: "a".into() }));
+
+		let Ok(Dispatched(command)) =
+			Dispatched::<Command>::from_request(request(Some("delete"), "{\"id\":5}"), &()).await
+		else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(command, Command::Delete(Delete { id: 5 }));
+	}
+
+	#[cfg(feature = "base64")]
+	#[tokio::test]
+	async fn test_dispatch_base64() {
+		let mut request = request(Some("delete"), "eyJpZCI6NX0=");
+		request.headers_mut().insert(
+			"content-transfer-encoding",
+			axum::http::HeaderValue::from_static("base64"),
+		);
+
+		let Ok(Dispatched(command)) = Dispatched::<Command>::from_request(request, &()).await else {
+			panic!("expected the base64 body to decode");
+		};
+
+		assert_eq!(command, Command::Delete(Delete { id: 5 }));
+	}
+
+	#[tokio::test]
+	async fn test_dispatch_rejections() {
+		for request in [
+			request(None, "5"),
+			request(Some("archive"), "5"),
+			request(Some("rename"), "5"),
+		] {
+			let Err(response) = Dispatched::<Command>::from_request(request, &()).await else {
+				panic!("expected the request to be rejected");
+			};
+
+			assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+		}
+	}
+}
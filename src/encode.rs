@@ -1,6 +1,6 @@
 use axum::response::{IntoResponse, Response};
 
-use crate::{Codec, ContentType};
+use crate::{Codec, CodecDecode, CodecRejection, ContentType, IntoCodecResponse};
 
 crate::macros::__private_encode_trait! {
 	/// Encoder trait for encoding a value into any supported format.
@@ -10,10 +10,70 @@ crate::macros::__private_encode_trait! {
 	/// in the future.
 }
 
+/// Advertises the approximate encoded size of a value, so that buffers can be
+/// allocated once instead of growing while the value is encoded.
+///
+/// [`Codec::to_bytes_hinted`] and [`Codec::encode_into_hinted`] reserve the
+/// returned number of bytes up front. The hint can be exact for formats with
+/// a predictable layout (such as Bincode) and a rough guess for the others.
+/// Returning `None` reserves nothing.
+///
+/// It can be implemented by hand, or with the `size_hint` option of
+/// [`apply`](crate::apply):
+///
+/// ```edition2021
+/// # use axum_codec::{Codec, ContentType, SizeHint};
+/// #
+/// #[axum_codec::apply(encode, size_hint = 16 + self.name.len())]
+/// struct User {
+///   id: u64,
+///   name: String,
+/// }
+///
+/// # fn main() {
+/// let user = User { id: 1, name: "Alice".into() };
+/// assert_eq!(user.size_hint(ContentType::Json), Some(21));
+///
+/// let bytes = Codec(user).to_bytes_hinted(ContentType::Json).unwrap();
+/// assert!(bytes.capacity() >= 21);
+/// # }
+/// ```
+pub trait SizeHint {
+	/// Returns the approximate number of bytes needed to encode the value as
+	/// the given [`ContentType`], if known.
+	fn size_hint(&self, content_type: ContentType) -> Option<usize>;
+}
+
+impl<T: SizeHint + ?Sized> SizeHint for &T {
+	#[inline]
+	fn size_hint(&self, content_type: ContentType) -> Option<usize> {
+		T::size_hint(self, content_type)
+	}
+}
+
+impl<T: SizeHint + ?Sized> SizeHint for Box<T> {
+	#[inline]
+	fn size_hint(&self, content_type: ContentType) -> Option<usize> {
+		T::size_hint(self, content_type)
+	}
+}
+
+impl<T: SizeHint + ?Sized> SizeHint for std::sync::Arc<T> {
+	#[inline]
+	fn size_hint(&self, content_type: ContentType) -> Option<usize> {
+		T::size_hint(self, content_type)
+	}
+}
+
 /// Errors that can occur during encoding.
 ///
 /// In debug mode this will include the error message. In release mode it will
 /// only include a status code of `500 Internal Server Error`.
+///
+/// With the `pretty-errors` feature, [`Codec::to_response`] instead renders
+/// the error as a [`Message`](crate::rejection::Message) in the negotiated
+/// format, so clients always receive a body they can parse. The message only
+/// contains the underlying error in debug mode.
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
@@ -26,28 +86,233 @@ pub enum Error {
 	#[cfg(feature = "cbor")]
 	#[error(transparent)]
 	Cbor(#[from] ciborium::ser::Error<std::io::Error>),
+	#[cfg(feature = "bson")]
+	#[error(transparent)]
+	Bson(#[from] bson::ser::Error),
 	#[cfg(feature = "bincode")]
 	#[error(transparent)]
 	Bincode(#[from] bincode::error::EncodeError),
 	#[cfg(feature = "yaml")]
 	#[error(transparent)]
-	Yaml(#[from] serde_yaml::Error),
+	Yaml(#[from] crate::yaml::Error),
 	#[cfg(feature = "toml")]
 	#[error(transparent)]
 	Toml(#[from] toml::ser::Error),
+	#[cfg(feature = "ron")]
+	#[error(transparent)]
+	Ron(#[from] ron::Error),
+	#[cfg(feature = "custom")]
+	#[error(transparent)]
+	Custom(axum::BoxError),
+	#[cfg(feature = "serde")]
+	#[error("{0} cannot be encoded from a value that only implements `serde::Serialize`")]
+	Unsupported(ContentType),
+}
+
+impl Error {
+	/// Returns the HTTP status code for the error.
+	#[must_use]
+	pub fn status_code(&self) -> axum::http::StatusCode {
+		#[cfg(feature = "serde")]
+		if let Self::Unsupported(..) = self {
+			return axum::http::StatusCode::NOT_ACCEPTABLE;
+		}
+
+		axum::http::StatusCode::INTERNAL_SERVER_ERROR
+	}
+
+	/// Returns a pretty [`Message`](crate::rejection::Message) representing
+	/// the error.
+	///
+	/// The underlying error is only included in debug mode, so that internal
+	/// details are not leaked to clients.
+	#[cfg(feature = "pretty-errors")]
+	#[must_use]
+	pub fn message(&self) -> crate::rejection::Message {
+		#[cfg(feature = "serde")]
+		if let Self::Unsupported(content_type) = self {
+			return crate::rejection::Message {
+				code: "not_acceptable",
+				content: format!("The response cannot be encoded as {content_type}."),
+			};
+		}
+
+		crate::rejection::Message {
+			code: "internal",
+			#[cfg(debug_assertions)]
+			content: self.to_string(),
+			#[cfg(not(debug_assertions))]
+			content: "The response could not be encoded.".into(),
+		}
+	}
 }
 
 impl IntoResponse for Error {
 	fn into_response(self) -> Response {
-		use axum::http::StatusCode;
+		#[cfg(feature = "serde")]
+		if let Self::Unsupported(..) = self {
+			return self.status_code().into_response();
+		}
 
 		#[cfg(debug_assertions)]
-		return (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response();
+		return (self.status_code(), self.to_string()).into_response();
 		#[cfg(not(debug_assertions))]
-		StatusCode::INTERNAL_SERVER_ERROR.into_response()
+		self.status_code().into_response()
+	}
+}
+
+#[cfg(not(feature = "pretty-errors"))]
+impl IntoCodecResponse for Error {
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		let _ = content_type;
+
+		self.into_response()
 	}
 }
 
+#[cfg(feature = "pretty-errors")]
+impl IntoCodecResponse for Error {
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		// Fall back to an empty body if the message cannot be encoded either, which
+		// would otherwise recurse forever.
+		let Ok(mut response) = Codec(self.message()).try_to_response(content_type) else {
+			return self.into_response();
+		};
+
+		*response.status_mut() = self.status_code();
+		response
+	}
+}
+
+#[cfg(all(feature = "aide", feature = "pretty-errors"))]
+impl aide::OperationOutput for Error {
+	type Inner = crate::rejection::Message;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<Self::Inner>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<Self::Inner>::inferred_responses(ctx, operation)
+	}
+}
+
+#[cfg(all(feature = "aide", not(feature = "pretty-errors")))]
+impl aide::OperationOutput for Error {
+	type Inner = String;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<String>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<String>::inferred_responses(ctx, operation)
+	}
+}
+
+/// Errors that can occur while converting a body between formats with
+/// [`convert_bytes`].
+#[derive(Debug, thiserror::Error)]
+pub enum ConvertError {
+	/// The input could not be decoded in the source format.
+	#[error("failed to decode the input: {0}")]
+	Decode(#[from] CodecRejection),
+	/// The decoded value could not be encoded in the target format.
+	#[error("failed to encode the output: {0}")]
+	Encode(#[from] Error),
+}
+
+/// Converts a body from one format to another by decoding it as `T` in the
+/// `from` format and encoding the result in the `to` format.
+///
+/// Going through `T` means the body is validated against the type (and with
+/// the `validator` feature, its validation rules) on the way, so this is
+/// suitable for proxies that translate between clients and services that
+/// speak different formats.
+///
+/// ```edition2021
+/// # use axum_codec::{convert_bytes, ContentType};
+/// #
+/// #[axum_codec::apply(encode, decode)]
+/// struct Point {
+///   x: i32,
+///   y: i32,
+/// }
+///
+/// # fn main() {
+/// let yaml = convert_bytes::<Point>(br#"{"x":1,"y":2}"#, ContentType::Json, ContentType::Yaml).unwrap();
+///
+/// assert_eq!(yaml, b"x: 1\ny: 2\n");
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// See [`ConvertError`].
+pub fn convert_bytes<T>(
+	bytes: &[u8],
+	from: ContentType,
+	to: ContentType,
+) -> Result<Vec<u8>, ConvertError>
+where
+	T: CodecDecode + CodecEncode,
+{
+	let value = Codec::<T>::from_bytes(bytes, from)?;
+
+	Ok(value.to_bytes(to)?)
+}
+
+/// The output style used by [`Codec::to_yaml_with`].
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum YamlStyle {
+	/// Nested values on their own indented lines. This is what
+	/// [`Codec::to_yaml`] produces.
+	#[default]
+	Block,
+	/// Nested values written inline with `{}` and `[]`, on a single line.
+	///
+	/// Neither YAML backend (`serde_yaml`, or `serde_yml` with the
+	/// `yaml-serde-yml` feature) can emit flow style, so with either backend
+	/// this is the output of [`serde_json::to_string`] followed by a newline.
+	/// JSON is a subset of YAML's flow style, so it parses as the same value,
+	/// except that non-finite floats are written as `null` and map keys must be
+	/// strings (or integers, which are quoted).
+	#[cfg(feature = "json")]
+	Flow,
+}
+
+/// The output style used by [`Codec::to_toml_with`].
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TomlStyle {
+	/// Nested tables as `[table]` sections. This is what [`Codec::to_toml`]
+	/// produces.
+	#[default]
+	Standard,
+	/// Like [`TomlStyle::Standard`], but with arrays split over multiple lines.
+	Pretty,
+	/// Every top-level key on a single line, with nested tables written as
+	/// inline tables (`key = { a = 1 }`). Best suited to small maps.
+	///
+	/// Keys are written in sorted order, rather than the order of the fields.
+	Inline,
+}
+
 #[cfg(feature = "serde")]
 impl<T> Codec<T>
 where
@@ -55,13 +320,20 @@ where
 {
 	/// Attempts to serialize the given value as [JSON](https://www.json.org).
 	///
+	/// JSON has no representation for `NaN` or infinite floats, so they are
+	/// encoded as `null` instead of failing. This loses information: a client
+	/// cannot tell them apart from a missing value, and an `f64` field will
+	/// fail to decode from the output. Use `Option<f64>` for fields that may
+	/// not be finite, or a format that supports them (YAML, TOML, RON,
+	/// `MessagePack`, CBOR, BSON, Bincode or Bitcode).
+	///
 	/// # Errors
 	///
 	/// See [`serde_json::to_vec`].
 	#[cfg(feature = "json")]
 	#[inline]
 	pub fn to_json(&self) -> Result<Vec<u8>, serde_json::Error> {
-		serde_json::to_vec(&self.0)
+		crate::json::to_vec(&self.0)
 	}
 
 	/// Attempts to serialize the given value as [MessagePack](https://msgpack.org).
@@ -75,6 +347,34 @@ where
 		rmp_serde::to_vec_named(&self.0)
 	}
 
+	/// Attempts to serialize the given value as [MessagePack](https://msgpack.org),
+	/// converting every integer, float and boolean map key into a string.
+	///
+	/// Struct fields are always encoded with string keys, but maps such as
+	/// `HashMap<u32, T>` keep their native key types. Many JavaScript
+	/// `MessagePack` decoders turn maps into plain objects, which silently
+	/// stringify (or reject) non-string keys, so use this when a map's keys must
+	/// survive the trip to a browser. Note that `rmp_serde` cannot decode the
+	/// stringified keys back into integers.
+	///
+	/// # Errors
+	///
+	/// See [`rmp_serde::to_vec_named`].
+	#[cfg(feature = "msgpack")]
+	pub fn to_msgpack_string_keys(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+		let bytes = self.to_msgpack()?;
+		let mut value = rmpv::decode::read_value(&mut bytes.as_slice())
+			.map_err(<rmp_serde::encode::Error as serde::ser::Error>::custom)?;
+
+		stringify_msgpack_keys(&mut value);
+
+		let mut buf = Vec::with_capacity(bytes.len());
+		rmpv::encode::write_value(&mut buf, &value)
+			.map_err(rmp_serde::encode::Error::InvalidValueWrite)?;
+
+		Ok(buf)
+	}
+
 	/// Attempts to serialize the given value as [CBOR](https://cbor.io).
 	///
 	/// # Errors
@@ -88,6 +388,20 @@ where
 		Ok(buf)
 	}
 
+	/// Attempts to serialize the given value as [BSON](https://bsonspec.org).
+	///
+	/// BSON documents must be maps, so only structs (and maps) can be encoded
+	/// at the top level.
+	///
+	/// # Errors
+	///
+	/// See [`bson::to_vec`].
+	#[cfg(feature = "bson")]
+	#[inline]
+	pub fn to_bson(&self) -> Result<Vec<u8>, bson::ser::Error> {
+		bson::to_vec(&self.0)
+	}
+
 	/// Attempts to serialize the given value as [YAML](https://yaml.org).
 	///
 	/// # Errors
@@ -95,8 +409,8 @@ where
 	/// See [`serde_yaml::to_vec`].
 	#[cfg(feature = "yaml")]
 	#[inline]
-	pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
-		serde_yaml::to_string(&self.0)
+	pub fn to_yaml(&self) -> Result<String, crate::yaml::Error> {
+		crate::yaml::to_string(&self.0)
 	}
 
 	/// Attempts to serialize the given value as [TOML](https://toml.io).
@@ -109,6 +423,143 @@ where
 	pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
 		toml::to_string(&self.0)
 	}
+
+	/// Attempts to serialize the given value as [RON](https://github.com/ron-rs/ron).
+	///
+	/// # Errors
+	///
+	/// See [`ron::to_string`].
+	#[cfg(feature = "ron")]
+	#[inline]
+	pub fn to_ron(&self) -> Result<String, ron::Error> {
+		ron::to_string(&self.0)
+	}
+
+	/// Attempts to serialize the given value as [YAML](https://yaml.org) with
+	/// the given [`YamlStyle`].
+	///
+	/// # Errors
+	///
+	/// See [`serde_yaml::to_string`] for [`YamlStyle::Block`] and
+	/// [`serde_json::to_string`] for [`YamlStyle::Flow`].
+	#[cfg(feature = "yaml")]
+	pub fn to_yaml_with(&self, style: YamlStyle) -> Result<String, Error> {
+		Ok(match style {
+			YamlStyle::Block => self.to_yaml()?,
+			#[cfg(feature = "json")]
+			YamlStyle::Flow => {
+				let mut yaml = serde_json::to_string(&self.0)?;
+				yaml.push('\n');
+				yaml
+			}
+		})
+	}
+
+	/// Attempts to serialize the given value as [TOML](https://toml.io) with
+	/// the given [`TomlStyle`].
+	///
+	/// # Errors
+	///
+	/// See [`toml::to_string`].
+	#[cfg(feature = "toml")]
+	pub fn to_toml_with(&self, style: TomlStyle) -> Result<String, toml::ser::Error> {
+		match style {
+			TomlStyle::Standard => self.to_toml(),
+			TomlStyle::Pretty => toml::to_string_pretty(&self.0),
+			TomlStyle::Inline => {
+				use serde::Serialize;
+
+				let table = toml::Table::try_from(&self.0)?;
+				let mut buf = String::new();
+
+				for (key, value) in table {
+					if !key.is_empty()
+						&& key
+							.bytes()
+							.all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+					{
+						buf.push_str(&key);
+					} else {
+						key.serialize(toml::ser::ValueSerializer::new(&mut buf))?;
+					}
+
+					buf.push_str(" = ");
+					value.serialize(toml::ser::ValueSerializer::new(&mut buf))?;
+					buf.push('\n');
+				}
+
+				Ok(buf)
+			}
+		}
+	}
+
+	#[cfg(feature = "custom")]
+	fn to_custom(&self, id: crate::custom::CustomId) -> Result<Vec<u8>, Error> {
+		crate::custom::get(id)
+			.encode(&self.0 as &dyn erased_serde::Serialize)
+			.map_err(Error::Custom)
+	}
+
+	/// Attempts to serialize the given value as the specified [`ContentType`],
+	/// only requiring the value to implement [`serde::Serialize`].
+	///
+	/// This is useful for values without a fixed shape, such as a
+	/// `BTreeMap<String, serde_json::Value>` assembled at runtime.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Unsupported`] for formats that are not backed by
+	/// `serde` (Bincode and Bitcode). See [`Error`] for other errors.
+	pub fn to_serde_bytes(&self, content_type: ContentType) -> Result<Vec<u8>, Error> {
+		Ok(match content_type {
+			#[cfg(feature = "json")]
+			ContentType::Json => self.to_json()?,
+			#[cfg(feature = "msgpack")]
+			ContentType::MsgPack => self.to_msgpack()?,
+			#[cfg(feature = "cbor")]
+			ContentType::Cbor => self.to_cbor()?,
+			#[cfg(feature = "bson")]
+			ContentType::Bson => self.to_bson()?,
+			#[cfg(feature = "yaml")]
+			ContentType::Yaml => self.to_yaml()?.into_bytes(),
+			#[cfg(feature = "toml")]
+			ContentType::Toml => self.to_toml()?.into_bytes(),
+			#[cfg(feature = "ron")]
+			ContentType::Ron => self.to_ron()?.into_bytes(),
+			#[cfg(feature = "custom")]
+			ContentType::Custom(id) => self.to_custom(id)?,
+			#[allow(unreachable_patterns)]
+			content_type => return Err(Error::Unsupported(content_type)),
+		})
+	}
+}
+
+/// Recursively replaces scalar map keys with their string representation.
+#[cfg(feature = "msgpack")]
+fn stringify_msgpack_keys(value: &mut rmpv::Value) {
+	use rmpv::Value;
+
+	match value {
+		Value::Map(entries) => {
+			for (key, value) in entries {
+				let string = match key {
+					Value::Integer(n) => Some(n.to_string()),
+					Value::F32(n) => Some(n.to_string()),
+					Value::F64(n) => Some(n.to_string()),
+					Value::Boolean(b) => Some(b.to_string()),
+					_ => None,
+				};
+
+				if let Some(string) = string {
+					*key = Value::from(string);
+				}
+
+				stringify_msgpack_keys(value);
+			}
+		}
+		Value::Array(items) => items.iter_mut().for_each(stringify_msgpack_keys),
+		_ => {}
+	}
 }
 
 impl<T> Codec<T> {
@@ -160,10 +611,97 @@ impl<T> Codec<T> {
 			ContentType::Bitcode => self.to_bitcode(),
 			#[cfg(feature = "cbor")]
 			ContentType::Cbor => self.to_cbor()?,
+			#[cfg(feature = "bson")]
+			ContentType::Bson => self.to_bson()?,
 			#[cfg(feature = "yaml")]
 			ContentType::Yaml => self.to_yaml()?.into_bytes(),
 			#[cfg(feature = "toml")]
 			ContentType::Toml => self.to_toml()?.into_bytes(),
+			#[cfg(feature = "ron")]
+			ContentType::Ron => self.to_ron()?.into_bytes(),
+			#[cfg(feature = "custom")]
+			ContentType::Custom(id) => self.to_custom(id)?,
 		})
 	}
+
+	/// Attempts to serialize the given value as the specified [`ContentType`],
+	/// appending the output to `buf`.
+	///
+	/// Unlike [`Codec::to_bytes`], this reuses the capacity of `buf`, so callers
+	/// that know the approximate encoded size (or encode many values in a row)
+	/// can pre-allocate a single buffer with [`Vec::with_capacity`] instead of
+	/// reallocating for every value. Formats that support streaming write into
+	/// `buf` directly.
+	///
+	/// If an error is returned, `buf` may contain partially encoded output.
+	///
+	/// # Errors
+	///
+	/// See [`Error`].
+	pub fn encode_into(&self, content_type: ContentType, buf: &mut Vec<u8>) -> Result<(), Error>
+	where
+		T: CodecEncode,
+	{
+		match content_type {
+			#[cfg(feature = "json")]
+			ContentType::Json => crate::json::to_writer(buf, &self.0)?,
+			#[cfg(feature = "msgpack")]
+			ContentType::MsgPack => rmp_serde::encode::write_named(buf, &self.0)?,
+			#[cfg(feature = "bincode")]
+			ContentType::Bincode => {
+				bincode::encode_into_std_write(&self.0, buf, bincode::config::standard())?;
+			}
+			#[cfg(feature = "bitcode")]
+			ContentType::Bitcode => buf.extend_from_slice(&self.to_bitcode()),
+			#[cfg(feature = "cbor")]
+			ContentType::Cbor => ciborium::into_writer(&self.0, buf)?,
+			#[cfg(feature = "bson")]
+			ContentType::Bson => bson::to_document(&self.0)?.to_writer(buf)?,
+			#[cfg(feature = "yaml")]
+			ContentType::Yaml => crate::yaml::to_writer(buf, &self.0)?,
+			#[cfg(feature = "toml")]
+			ContentType::Toml => buf.extend_from_slice(self.to_toml()?.as_bytes()),
+			#[cfg(feature = "ron")]
+			ContentType::Ron => ron::ser::to_writer(buf, &self.0)?,
+			#[cfg(feature = "custom")]
+			ContentType::Custom(id) => buf.extend_from_slice(&self.to_custom(id)?),
+		}
+
+		Ok(())
+	}
+
+	/// Like [`Codec::encode_into`], but first reserves the number of bytes
+	/// given by the value's [`SizeHint`].
+	///
+	/// # Errors
+	///
+	/// See [`Error`].
+	pub fn encode_into_hinted(&self, content_type: ContentType, buf: &mut Vec<u8>) -> Result<(), Error>
+	where
+		T: CodecEncode + SizeHint,
+	{
+		if let Some(size) = self.0.size_hint(content_type) {
+			buf.reserve(size);
+		}
+
+		self.encode_into(content_type, buf)
+	}
+
+	/// Like [`Codec::to_bytes`], but allocates the number of bytes given by the
+	/// value's [`SizeHint`] up front, instead of growing the buffer while
+	/// encoding.
+	///
+	/// # Errors
+	///
+	/// See [`Error`].
+	pub fn to_bytes_hinted(&self, content_type: ContentType) -> Result<Vec<u8>, Error>
+	where
+		T: CodecEncode + SizeHint,
+	{
+		let mut buf = Vec::new();
+
+		self.encode_into_hinted(content_type, &mut buf)?;
+
+		Ok(buf)
+	}
 }
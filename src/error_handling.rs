@@ -0,0 +1,164 @@
+//! Converting errors from fallible middleware into codec responses.
+
+use core::{
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+};
+use std::convert::Infallible;
+
+use axum::{
+	extract::Request,
+	http::StatusCode,
+	response::{IntoResponse, Response},
+	BoxError,
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{rejection::Message, Accept, Codec, IntoCodecResponse};
+
+/// A [`Layer`] that converts errors from the inner service into a
+/// [`Message`], encoded with the content type negotiated from the request's
+/// `Accept` header.
+///
+/// This is similar to
+/// [`HandleErrorLayer`](axum::error_handling::HandleErrorLayer),
+/// but keeps error responses consistent with the rest of the API instead of
+/// requiring every error handler to pick a format.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{error_handling::CodecErrorLayer, rejection::Message};
+/// # use axum::{http::StatusCode, routing::get, BoxError, Router};
+/// #
+/// fn handle_error(err: BoxError) -> (StatusCode, Message) {
+///   (StatusCode::SERVICE_UNAVAILABLE, Message {
+///     code: "unavailable",
+///     content: err.to_string(),
+///   })
+/// }
+///
+/// # fn main() {
+/// let router: Router = Router::new()
+///   .route("/", get(|| async { "Hello, world!" }))
+///   .layer(CodecErrorLayer::new(handle_error));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CodecErrorLayer<F> {
+	f: F,
+}
+
+impl<F> CodecErrorLayer<F> {
+	/// Creates a new [`CodecErrorLayer`] that converts errors with the given
+	/// function.
+	pub fn new(f: F) -> Self {
+		Self { f }
+	}
+}
+
+impl<S, F: Clone> Layer<S> for CodecErrorLayer<F> {
+	type Service = CodecError<S, F>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		CodecError {
+			inner,
+			f: self.f.clone(),
+		}
+	}
+}
+
+/// The service created by [`CodecErrorLayer`].
+#[derive(Clone)]
+pub struct CodecError<S, F> {
+	inner: S,
+	f: F,
+}
+
+impl<S, F> Service<Request> for CodecError<S, F>
+where
+	S: Service<Request> + Clone + Send + 'static,
+	S::Response: IntoResponse,
+	S::Error: Into<BoxError>,
+	S::Future: Send,
+	F: Fn(BoxError) -> (StatusCode, Message) + Clone + Send + 'static,
+{
+	type Error = Infallible;
+	type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+	type Response = Response;
+
+	fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		// Readiness is driven in `call`, so that its errors can be converted too.
+		Poll::Ready(Ok(()))
+	}
+
+	fn call(&mut self, req: Request) -> Self::Future {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+		let f = self.f.clone();
+		let mut inner = self.inner.clone();
+
+		Box::pin(async move {
+			let ready: Result<(), BoxError> = core::future::poll_fn(|cx| inner.poll_ready(cx))
+				.await
+				.map_err(Into::into);
+
+			let result = match ready {
+				Ok(()) => inner.call(req).await.map_err(Into::into),
+				Err(err) => Err(err),
+			};
+
+			Ok(match result {
+				Ok(response) => response.into_response(),
+				Err(err) => {
+					let (status, message) = f(err);
+
+					(status, Codec(message)).into_codec_response(accept.into())
+				}
+			})
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		body::Body,
+		http::{header, Request, StatusCode},
+		BoxError,
+	};
+	use tower::{service_fn, ServiceExt};
+
+	use super::CodecErrorLayer;
+	use crate::{rejection::Message, ContentType};
+
+	#[tokio::test]
+	async fn test_error_is_negotiated() {
+		let service = tower_layer::Layer::layer(
+			&CodecErrorLayer::new(|err: BoxError| {
+				(StatusCode::SERVICE_UNAVAILABLE, Message {
+					code: "unavailable",
+					content: err.to_string(),
+				})
+			}),
+			service_fn(|_: Request<Body>| async { Err::<(), BoxError>("overloaded".into()) }),
+		);
+
+		let response = service
+			.oneshot(
+				Request::builder()
+					.header(header::ACCEPT, "application/cbor")
+					.body(Body::empty())
+					.unwrap(),
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::Cbor.into_header()
+		);
+	}
+}
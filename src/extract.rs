@@ -1,5 +1,8 @@
-use core::fmt;
-use std::ops::{Deref, DerefMut};
+use core::{fmt, future::Future, marker::PhantomData, time::Duration};
+use std::{
+	ops::{Deref, DerefMut},
+	time::Instant,
+};
 
 use axum::{
 	body::Bytes,
@@ -8,7 +11,10 @@ use axum::{
 	response::{IntoResponse, Response},
 };
 
-use crate::{Accept, CodecDecode, CodecEncode, CodecRejection, ContentType, IntoCodecResponse};
+use crate::{
+	format::FormatSet, Accept, CodecDecode, CodecEncode, CodecRejection, ContentType,
+	IntoCodecResponse,
+};
 
 /// Codec extractor / response.
 ///
@@ -44,6 +50,10 @@ use crate::{Accept, CodecDecode, CodecEncode, CodecRejection, ContentType, IntoC
 /// assert_eq!(data.hello, "world");
 /// # }
 /// ```
+///
+/// The standard comparison and formatting traits are implemented whenever `T`
+/// implements them, so codecs can be compared directly in tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Codec<T>(pub T);
 
 impl<T> Codec<T>
@@ -57,16 +67,314 @@ where
 
 	/// Converts the inner value into a response with the given content type.
 	///
-	/// If serialization fails, the rejection is converted into a response. See
-	/// [`encode::Error`](crate::encode::Error) for possible errors.
+	/// If serialization fails, the error is converted into a response in the
+	/// same format. See [`encode::Error`](crate::encode::Error) for possible
+	/// errors.
+	///
+	/// With the `validate-responses` feature, the inner value is validated first.
+	/// Since sending invalid data is a bug in the server rather than the client,
+	/// a failure is logged (with the `tracing` feature) and an empty
+	/// `500 Internal Server Error` response is returned instead. The
+	/// `validator::ValidationErrors` are stored in the response extensions, so
+	/// middleware can report them without the `tracing` feature.
 	pub fn to_response<C: Into<ContentType>>(&self, content_type: C) -> Response {
+		let content_type = content_type.into();
+
+		#[cfg(feature = "validate-responses")]
+		if let Some(response) = self.invalid_response(content_type) {
+			return response;
+		}
+
+		self
+			.try_to_response(content_type)
+			.unwrap_or_else(|e| e.into_codec_response(content_type))
+	}
+
+	/// Converts the inner value into a response with the content type
+	/// negotiated by `accept`, skipping formats that cannot represent the
+	/// value's [`Shape`](crate::Shape).
+	///
+	/// For example, a client that prefers TOML receives a `Vec<T>` in the next
+	/// acceptable format instead of an encoding error, since TOML documents
+	/// must be tables. See [`Accept::content_type_for`].
+	///
+	/// ```edition2021
+	/// # use axum::response::Response;
+	/// # use axum_codec::{Accept, Codec};
+	/// #
+	/// #[axum_codec::apply(encode)]
+	/// struct User {
+	///   name: String,
+	/// }
+	///
+	/// async fn users(accept: Accept) -> Response {
+	///   Codec(vec![User { name: "Alice".into() }]).to_response_for(accept)
+	/// }
+	/// #
+	/// # fn main() {}
+	/// ```
+	pub fn to_response_for(&self, accept: Accept) -> Response {
+		#[cfg(feature = "serde")]
+		return self.to_response(accept.content_type_for(crate::Shape::of(&self.0)));
+		#[cfg(not(feature = "serde"))]
+		self.to_response(accept)
+	}
+
+	/// Converts the inner value into a response with the given content type,
+	/// along with a `Content-Digest` header ([RFC 9530](https://www.rfc-editor.org/rfc/rfc9530))
+	/// holding the SHA-256 hash of the encoded body.
+	///
+	/// Clients that persist the response can use the header to verify its
+	/// integrity. Otherwise, this behaves like [`Codec::to_response`].
+	#[cfg(feature = "digest")]
+	pub fn to_response_with_checksum<C: Into<ContentType>>(&self, content_type: C) -> Response {
+		use axum::http::{HeaderName, HeaderValue};
+		use base64::Engine;
+		use sha2::Digest;
+
+		let content_type = content_type.into();
+
+		#[cfg(feature = "validate-responses")]
+		if let Some(response) = self.invalid_response(content_type) {
+			return response;
+		}
+
+		let bytes = match self.to_bytes(content_type) {
+			Ok(bytes) => bytes,
+			Err(error) => return error.into_codec_response(content_type),
+		};
+
+		let hash = base64::engine::general_purpose::STANDARD.encode(sha2::Sha256::digest(&bytes));
+		let digest = HeaderValue::try_from(format!("sha-256=:{hash}:"));
+
+		let mut response =
+			([(header::CONTENT_TYPE, content_type.into_header())], bytes).into_response();
+
+		if let Ok(digest) = digest {
+			response
+				.headers_mut()
+				.insert(HeaderName::from_static("content-digest"), digest);
+		}
+
+		response
+	}
+
+	/// Validates the inner value, logging the errors and returning a
+	/// `500 Internal Server Error` response (with the errors in its extensions)
+	/// if it is invalid.
+	#[cfg(feature = "validate-responses")]
+	fn invalid_response(&self, content_type: ContentType) -> Option<Response> {
+		let Err(errors) = validator::Validate::validate(&self.0) else {
+			return None;
+		};
+
+		#[cfg(feature = "tracing")]
+		tracing::error!(
+			type_name = core::any::type_name::<T>(),
+			content_type = %content_type,
+			error = ?errors,
+			"refusing to send an invalid response: {errors}",
+		);
+		#[cfg(not(feature = "tracing"))]
+		let _ = content_type;
+
+		let mut response = axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+		response.extensions_mut().insert(errors);
+
+		Some(response)
+	}
+
+	/// Converts the inner value into a response with the given content type,
+	/// returning the error if serialization fails instead of converting it into
+	/// a response.
+	///
+	/// Useful for handling encoding failures explicitly, such as logging them
+	/// and responding with a fallback. Unlike [`Codec::to_response`], the value
+	/// is not validated, even with the `validate-responses` feature.
+	///
+	/// # Errors
+	///
+	/// See [`encode::Error`](crate::encode::Error).
+	pub fn try_to_response<C: Into<ContentType>>(
+		&self,
+		content_type: C,
+	) -> Result<Response, crate::encode::Error> {
+		let content_type = content_type.into();
+		let bytes = self.to_bytes(content_type)?;
+
+		Ok(([(header::CONTENT_TYPE, content_type.into_header())], bytes).into_response())
+	}
+
+	/// Encodes the inner value and returns it along with a response builder
+	/// with the `Content-Type` header already set.
+	///
+	/// This is an escape hatch for handlers that need control over parts of the
+	/// response that [`Codec::to_response`] does not expose, such as the HTTP
+	/// version. Like [`Codec::try_to_response`], the value is not validated,
+	/// even with the `validate-responses` feature.
+	///
+	/// ```edition2021
+	/// # use axum::{body::Body, http::{StatusCode, Version}, response::Response};
+	/// # use axum_codec::{Codec, ContentType};
+	/// #
+	/// #[axum_codec::apply(encode)]
+	/// struct Job {
+	///   id: u64,
+	/// }
+	///
+	/// # fn main() {
+	/// let (builder, body) = Codec(Job { id: 1 }).into_builder(ContentType::Json).unwrap();
+	///
+	/// let response: Response = builder
+	///   .status(StatusCode::ACCEPTED)
+	///   .version(Version::HTTP_11)
+	///   .body(Body::from(body))
+	///   .unwrap();
+	/// # }
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`encode::Error`](crate::encode::Error).
+	pub fn into_builder<C: Into<ContentType>>(
+		self,
+		content_type: C,
+	) -> Result<(axum::http::response::Builder, Vec<u8>), crate::encode::Error> {
+		let content_type = content_type.into();
+		let bytes = self.to_bytes(content_type)?;
+
+		Ok((
+			Response::builder().header(header::CONTENT_TYPE, content_type.into_header()),
+			bytes,
+		))
+	}
+
+	/// Validates the inner value before converting it into a response with the
+	/// given content type.
+	///
+	/// If validation fails, a `422 Unprocessable Entity` response describing the
+	/// errors is returned instead. This is opt-in, as validating every response
+	/// is rarely necessary.
+	#[cfg(feature = "validator")]
+	pub fn into_validated_response<C: Into<ContentType>>(self, content_type: C) -> Response
+	where
+		T: validator::Validate,
+	{
+		use axum::http::StatusCode;
+
+		let content_type = content_type.into();
+
+		if let Err(errors) = self.0.validate() {
+			let mut response = CodecRejection::from(errors).into_codec_response(content_type);
+
+			*response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+			return response;
+		}
+
+		self.to_response(content_type)
+	}
+
+	/// Converts the inner value into a response with the given content type,
+	/// transcoding text formats into the character set requested by
+	/// [`AcceptCharset`](crate::AcceptCharset).
+	///
+	/// Binary formats are unaffected. If the output cannot be represented in
+	/// any of the acceptable character sets, a `406 Not Acceptable` response is
+	/// returned instead.
+	#[cfg(feature = "encoding")]
+	pub fn to_response_with_charset<C: Into<ContentType>>(
+		&self,
+		content_type: C,
+		charset: crate::AcceptCharset,
+	) -> Response {
+		use axum::http::{HeaderValue, StatusCode};
+
 		let content_type = content_type.into();
 		let bytes = match self.to_bytes(content_type) {
 			Ok(bytes) => bytes,
-			Err(rejection) => return rejection.into_response(),
+			Err(error) => return error.into_codec_response(content_type),
+		};
+
+		// The character set only applies to text formats, so binary formats are
+		// never rejected because of it.
+		if !content_type.is_text() {
+			return ([(header::CONTENT_TYPE, content_type.into_header())], bytes).into_response();
+		}
+
+		let Some(encoding) = charset.encoding() else {
+			return StatusCode::NOT_ACCEPTABLE.into_response();
 		};
 
-		([(header::CONTENT_TYPE, content_type.into_header())], bytes).into_response()
+		if encoding == encoding_rs::UTF_8 {
+			return ([(header::CONTENT_TYPE, content_type.into_header())], bytes).into_response();
+		}
+
+		let Some(bytes) = core::str::from_utf8(&bytes)
+			.ok()
+			.and_then(|text| charset.encode(text))
+		else {
+			return StatusCode::NOT_ACCEPTABLE.into_response();
+		};
+
+		let header = HeaderValue::from_str(&format!(
+			"{}; charset={}",
+			content_type.as_str(),
+			encoding.name()
+		))
+		.unwrap_or_else(|_| content_type.into_header());
+
+		([(header::CONTENT_TYPE, header)], bytes).into_response()
+	}
+}
+
+impl<T> Codec<T> {
+	/// Maps the inner value, keeping it wrapped in a [`Codec`].
+	pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Codec<U> {
+		Codec(f(self.0))
+	}
+
+	/// Maps the inner value with a fallible function, keeping it wrapped in a
+	/// [`Codec`].
+	///
+	/// Since `Result<T, E>` implements [`IntoCodecResponse`] when both `T` and
+	/// `E` do, the result can be returned directly from a handler as long as the
+	/// error is a codec response too (such as [`CodecRejection`] or a
+	/// `(StatusCode, Codec<E>)` tuple).
+	///
+	/// # Errors
+	///
+	/// Returns the error returned by `f`.
+	///
+	/// # Examples
+	///
+	/// ```edition2021
+	/// # use axum_codec::{Codec, IntoCodecResponse};
+	/// # use axum::http::StatusCode;
+	/// #
+	/// #[axum_codec::apply(decode)]
+	/// struct Input {
+	///   value: String,
+	/// }
+	///
+	/// #[axum_codec::apply(encode)]
+	/// struct Output {
+	///   value: u32,
+	/// }
+	///
+	/// async fn parse(input: Codec<Input>) -> impl IntoCodecResponse {
+	///   input.try_map(|input| {
+	///     input
+	///       .value
+	///       .parse()
+	///       .map(|value| Output { value })
+	///       .map_err(|_| (StatusCode::BAD_REQUEST, "expected a number"))
+	///   })
+	/// }
+	/// #
+	/// # fn main() {}
+	/// ```
+	pub fn try_map<U, E, F: FnOnce(T) -> Result<U, E>>(self, f: F) -> Result<Codec<U>, E> {
+		f(self.0).map(Codec)
 	}
 }
 
@@ -104,19 +412,67 @@ where
 
 		let req = Request::from_parts(parts, body);
 
-		let content_type = req
-			.headers()
-			.get(header::CONTENT_TYPE)
-			.and_then(ContentType::from_header)
-			.unwrap_or_default();
-
-		let bytes = Bytes::from_request(req, state)
+		RequestBody::from_request(req, state)
 			.await
-			.map_err(|e| CodecRejection::from(e).into_codec_response(accept.into()))?;
-		let data =
-			Codec::from_bytes(&bytes, content_type).map_err(|e| e.into_codec_response(accept.into()))?;
+			.and_then(|body| body.decode())
+			.map_err(|e| e.into_codec_response(accept.into()))
+	}
+}
+
+/// A request body read by the codec extractors.
+pub(crate) struct RequestBody {
+	/// The format of the body, from its `Content-Type` header.
+	pub(crate) content_type: ContentType,
+	/// The body as it was received.
+	pub(crate) raw: Bytes,
+	/// The body to decode. With the `base64` feature, this is `raw` decoded
+	/// from base64 if the request has a `Content-Transfer-Encoding: base64`
+	/// header.
+	pub(crate) bytes: Bytes,
+}
+
+impl RequestBody {
+	/// Reads the body of a request, rejecting requests with conflicting
+	/// `Content-Type` headers.
+	pub(crate) async fn from_request<S>(req: Request, state: &S) -> Result<Self, CodecRejection>
+	where
+		S: Send + Sync + 'static,
+	{
+		ContentType::ensure_unambiguous(req.headers())?;
+
+		let content_type = ContentType::from_request_headers(req.headers(), req.extensions());
+		#[cfg(feature = "base64")]
+		let base64 = crate::decode::is_base64_transfer(req.headers());
+
+		let raw = Bytes::from_request(req, state).await?;
+
+		#[cfg(feature = "metrics")]
+		crate::metrics::record_decode(content_type, raw.len());
+
+		#[cfg(feature = "base64")]
+		let bytes = if base64 {
+			crate::decode::decode_base64(&raw)?.into()
+		} else {
+			raw.clone()
+		};
+		#[cfg(not(feature = "base64"))]
+		let bytes = raw.clone();
+
+		Ok(Self {
+			content_type,
+			raw,
+			bytes,
+		})
+	}
+
+	/// Decodes the body with [`Codec::from_bytes`].
+	pub(crate) fn decode<T: CodecDecode>(&self) -> Result<Codec<T>, CodecRejection> {
+		let result = Codec::from_bytes(&self.bytes, self.content_type);
+
+		#[cfg(feature = "metrics")]
+		crate::metrics::record_outcome(self.content_type, &result);
 
-		Ok(data)
+		result
 	}
 }
 
@@ -169,25 +525,1412 @@ where
 	}
 }
 
-#[cfg(test)]
-mod test {
-	use super::{Codec, ContentType};
+/// Codec extractor / response that always responds in the format the request
+/// was sent in, instead of negotiating it from the `Accept` header.
+///
+/// The second field holds the [`ContentType`] of the request body (from its
+/// `Content-Type` header), which is used to encode the response and any
+/// rejection. Use [`SymmetricCodec::map`] to turn the decoded request into a
+/// response while keeping the format. This is useful for RPC-style endpoints
+/// where clients expect replies in the format they speak.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::SymmetricCodec;
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Ping {
+///   id: u32,
+/// }
+///
+/// #[axum_codec::apply(encode)]
+/// struct Pong {
+///   id: u32,
+/// }
+///
+/// async fn ping(request: SymmetricCodec<Ping>) -> SymmetricCodec<Pong> {
+///   request.map(|ping| Pong { id: ping.id })
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct SymmetricCodec<T>(pub T, pub ContentType);
 
-	#[crate::apply(decode)]
-	#[derive(Debug, PartialEq, Eq)]
-	struct Data {
-		hello: String,
+impl<T> SymmetricCodec<T> {
+	/// Consumes the [`SymmetricCodec`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
 	}
 
-	#[test]
-	fn test_json_codec() {
-		let bytes = b"{\"hello\": \"world\"}";
+	/// Returns the [`ContentType`] the request was sent in.
+	#[must_use]
+	pub fn content_type(&self) -> ContentType {
+		self.1
+	}
 
-		let Codec(data) = Codec::<Data>::from_bytes(bytes, ContentType::Json).unwrap();
+	/// Maps the inner value, keeping the same [`ContentType`].
+	pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> SymmetricCodec<U> {
+		SymmetricCodec(f(self.0), self.1)
+	}
+}
 
-		assert_eq!(data, Data {
-			hello: "world".into()
-		});
+impl<T> Deref for SymmetricCodec<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for SymmetricCodec<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for SymmetricCodec<T>
+where
+	T: CodecDecode,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let content_type = ContentType::from_request_headers(req.headers(), req.extensions());
+
+		let Codec(data) = RequestBody::from_request(req, state)
+			.await
+			.and_then(|body| body.decode())
+			.map_err(|e| e.into_codec_response(content_type))?;
+
+		Ok(Self(data, content_type))
+	}
+}
+
+#[cfg(not(feature = "aide"))]
+impl<T> IntoCodecResponse for SymmetricCodec<T>
+where
+	T: CodecEncode,
+{
+	fn into_codec_response(self, _content_type: ContentType) -> Response {
+		Codec(self.0).to_response(self.1)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> IntoCodecResponse for SymmetricCodec<T>
+where
+	T: CodecEncode + schemars::JsonSchema,
+{
+	fn into_codec_response(self, _content_type: ContentType) -> Response {
+		Codec(self.0).to_response(self.1)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for SymmetricCodec<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationOutput for SymmetricCodec<T>
+where
+	T: schemars::JsonSchema,
+{
+	type Inner = T;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<T>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_responses(ctx, operation)
+	}
+}
+
+/// Codec extractor that also carries the response [`ContentType`] negotiated
+/// from the request's `Accept` header.
+///
+/// This bundles extracting [`Codec`] and [`Accept`] into one type, for
+/// handlers that build the response themselves. Use [`Negotiated::respond`]
+/// to encode a value in the negotiated format.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum::response::Response;
+/// # use axum_codec::Negotiated;
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct CreateUser {
+///   name: String,
+/// }
+///
+/// #[axum_codec::apply(encode)]
+/// struct User {
+///   id: u64,
+///   name: String,
+/// }
+///
+/// async fn create(request: Negotiated<CreateUser>) -> Response {
+///   let user = User {
+///     id: 1,
+///     name: request.name.clone(),
+///   };
+///
+///   request.respond(user)
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct Negotiated<T>(pub T, pub ContentType);
+
+impl<T> Negotiated<T> {
+	/// Consumes the [`Negotiated`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+
+	/// Returns the negotiated response content type.
+	pub fn content_type(&self) -> ContentType {
+		self.1
+	}
+
+	/// Encodes the given value in the negotiated format.
+	///
+	/// See [`Codec::to_response`].
+	pub fn respond<U: CodecEncode>(&self, value: U) -> Response {
+		Codec(value).to_response(self.1)
+	}
+}
+
+impl<T> Deref for Negotiated<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Negotiated<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for Negotiated<T>
+where
+	T: CodecDecode,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let content_type =
+			Accept::from_request_head(req.uri(), req.headers(), req.extensions()).content_type();
+		let Codec(data) = Codec::from_request(req, state).await?;
+
+		Ok(Self(data, content_type))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for Negotiated<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+/// Codec extractor that yields `T::default()` when the request body is empty.
+///
+/// Behaves like [`Codec`] for non-empty bodies. Useful for partial updates,
+/// where an empty body means "no changes". The default value is still
+/// validated if the `validator` feature is enabled.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::CodecOrDefault;
+/// #
+/// #[axum_codec::apply(decode)]
+/// #[derive(Default)]
+/// struct UpdateUser {
+///   name: Option<String>,
+/// }
+///
+/// async fn update(CodecOrDefault(update): CodecOrDefault<UpdateUser>) -> String {
+///   update.name.unwrap_or_else(|| "no changes".into())
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct CodecOrDefault<T>(pub T);
+
+impl<T> CodecOrDefault<T> {
+	/// Consumes the [`CodecOrDefault`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T> Deref for CodecOrDefault<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for CodecOrDefault<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for CodecOrDefault<T>
+where
+	T: CodecDecode + Default,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+
+		let body = RequestBody::from_request(req, state)
+			.await
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		if !body.raw.is_empty() {
+			let Codec(data) = body
+				.decode()
+				.map_err(|e| e.into_codec_response(accept.into()))?;
+
+			return Ok(Self(data));
+		}
+
+		let data = T::default();
+
+		#[cfg(feature = "validator")]
+		validator::Validate::validate(&data)
+			.map_err(|e| CodecRejection::from(e).into_codec_response(accept.into()))?;
+
+		Ok(Self(data))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for CodecOrDefault<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+/// Codec extractor that also measures how long decoding the body took.
+///
+/// The elapsed time only covers [`Codec::from_bytes`] (including validation,
+/// if the `validator` feature is enabled), not reading the body itself. This is
+/// useful for recording metrics or logging slow requests.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::TimedCodec;
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Upload {
+///   items: Vec<u64>,
+/// }
+///
+/// async fn upload(TimedCodec(upload, elapsed): TimedCodec<Upload>) -> String {
+///   format!("decoded {} items in {elapsed:?}", upload.items.len())
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct TimedCodec<T>(pub T, pub Duration);
+
+impl<T> TimedCodec<T> {
+	/// Consumes the [`TimedCodec`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+
+	/// Returns how long it took to decode the body.
+	pub fn elapsed(&self) -> Duration {
+		self.1
+	}
+}
+
+impl<T> Deref for TimedCodec<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for TimedCodec<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for TimedCodec<T>
+where
+	T: CodecDecode,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+
+		let body = RequestBody::from_request(req, state)
+			.await
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		let start = Instant::now();
+		let Codec(data) = body
+			.decode()
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		Ok(Self(data, start.elapsed()))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for TimedCodec<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+/// Codec extractor that keeps the raw body alongside the decoded value.
+///
+/// This is useful for verifying webhook signatures, which are computed over
+/// the exact bytes that were sent rather than over a re-encoded value. For
+/// bodies sent with `Content-Transfer-Encoding: base64` (with the `base64`
+/// feature), `raw` holds the body as it was sent, before base64 decoding.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum::http::StatusCode;
+/// # use axum_codec::CodecWithRaw;
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Event {
+///   kind: String,
+/// }
+///
+/// # fn verify(_body: &[u8]) -> bool { true }
+/// async fn webhook(event: CodecWithRaw<Event>) -> StatusCode {
+///   if !verify(&event.raw) {
+///     return StatusCode::UNAUTHORIZED;
+///   }
+///
+///   println!("received {}", event.value.kind);
+///   StatusCode::NO_CONTENT
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct CodecWithRaw<T> {
+	/// The decoded value.
+	pub value: T,
+	/// The body as it was received.
+	pub raw: Bytes,
+}
+
+impl<T> CodecWithRaw<T> {
+	/// Consumes the [`CodecWithRaw`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.value
+	}
+
+	/// Consumes the [`CodecWithRaw`] and returns the value and the raw body.
+	pub fn into_parts(self) -> (T, Bytes) {
+		(self.value, self.raw)
+	}
+}
+
+impl<T> Deref for CodecWithRaw<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.value
+	}
+}
+
+impl<T> DerefMut for CodecWithRaw<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.value
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for CodecWithRaw<T>
+where
+	T: CodecDecode,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+
+		let body = RequestBody::from_request(req, state)
+			.await
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		let Codec(value) = body
+			.decode()
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		Ok(Self {
+			value,
+			raw: body.raw,
+		})
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for CodecWithRaw<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+/// Validation that needs access to the application state, such as checking
+/// that a username is not taken yet.
+///
+/// Implement this for a request type and extract it with [`ValidatedCodec`]
+/// to run the validation after the body has been decoded (and validated with
+/// `validator`, if the feature is enabled).
+///
+/// Implementations can use `async fn`, without `#[async_trait]`. The returned
+/// future must be [`Send`].
+pub trait ValidateWithState<S>: Send + Sync
+where
+	S: Sync,
+{
+	/// The error returned when validation fails, encoded in the format
+	/// negotiated from the request's `Accept` header.
+	///
+	/// [`CodecRejection`] can be used to report `validator::ValidationErrors`
+	/// in the same way as stateless validation.
+	type Rejection: IntoCodecResponse;
+
+	/// Validates the decoded value against the application state.
+	///
+	/// # Errors
+	///
+	/// Returns a rejection if the value is invalid.
+	fn validate_with_state(
+		&self,
+		state: &S,
+	) -> impl Future<Output = Result<(), Self::Rejection>> + Send;
+}
+
+/// Codec extractor that runs [`ValidateWithState`] after decoding.
+///
+/// Behaves like [`Codec`] otherwise, so it can be used as a drop-in
+/// replacement when validation depends on the application state.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use std::{collections::HashSet, sync::Arc};
+/// # use axum::{extract::State, http::StatusCode};
+/// # use axum_codec::{ValidateWithState, ValidatedCodec};
+/// #
+/// #[derive(Clone)]
+/// struct AppState {
+///   usernames: Arc<HashSet<String>>,
+/// }
+///
+/// #[axum_codec::apply(decode)]
+/// struct Register {
+///   username: String,
+/// }
+///
+/// impl ValidateWithState<AppState> for Register {
+///   type Rejection = (StatusCode, &'static str);
+///
+///   async fn validate_with_state(&self, state: &AppState) -> Result<(), Self::Rejection> {
+///     if state.usernames.contains(&self.username) {
+///       return Err((StatusCode::CONFLICT, "username is taken"));
+///     }
+///
+///     Ok(())
+///   }
+/// }
+///
+/// async fn register(ValidatedCodec(input): ValidatedCodec<Register>) -> String {
+///   format!("welcome, {}!", input.username)
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct ValidatedCodec<T>(pub T);
+
+impl<T> ValidatedCodec<T> {
+	/// Consumes the [`ValidatedCodec`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T> Deref for ValidatedCodec<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for ValidatedCodec<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for ValidatedCodec<T>
+where
+	T: CodecDecode + ValidateWithState<S>,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+		let Codec(data) = Codec::<T>::from_request(req, state).await?;
+
+		data
+			.validate_with_state(state)
+			.await
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		Ok(Self(data))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for ValidatedCodec<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+/// Codec extractor that only accepts the formats in the [`FormatSet`] `F`,
+/// even if more formats are enabled.
+///
+/// Requests in any other format are rejected with
+/// [`CodecRejection::UnsupportedContentType`] (which has a status code of
+/// `415 Unsupported Media Type`) before the body is read. Otherwise, this
+/// behaves like the [`Codec`] extractor.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{
+/// #   format::{Json, MsgPack, Only},
+/// #   RestrictedCodec,
+/// # };
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Event {
+///   name: String,
+/// }
+///
+/// async fn ingest(RestrictedCodec(event, _): RestrictedCodec<Event, Only<(Json, MsgPack)>>) -> String {
+///   event.name
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct RestrictedCodec<T, F>(pub T, pub PhantomData<F>);
+
+impl<T, F> RestrictedCodec<T, F> {
+	/// Consumes the [`RestrictedCodec`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T, F> Deref for RestrictedCodec<T, F> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, F> DerefMut for RestrictedCodec<T, F> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, F, S> FromRequest<S> for RestrictedCodec<T, F>
+where
+	T: CodecDecode,
+	F: FormatSet,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let accept = Accept::from_request_head(req.uri(), req.headers(), req.extensions());
+
+		ContentType::ensure_unambiguous(req.headers())
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		let content_type = ContentType::from_request_headers(req.headers(), req.extensions());
+
+		if !F::contains(content_type) {
+			return Err(
+				CodecRejection::UnsupportedContentType {
+					content_type,
+					supported: F::CONTENT_TYPES,
+				}
+				.into_codec_response(accept.into()),
+			);
+		}
+
+		let Codec(data) = Codec::<T>::from_request(req, state).await?;
+
+		Ok(Self(data, PhantomData))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T, F> aide::operation::OperationInput for RestrictedCodec<T, F>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{Codec, ContentType};
+
+	#[crate::apply(decode)]
+	#[derive(Debug, PartialEq, Eq)]
+	struct Data {
+		hello: String,
+	}
+
+	#[test]
+	fn test_json_codec() {
+		let bytes = b"{\"hello\": \"world\"}";
+
+		let Codec(data) = Codec::<Data>::from_bytes(bytes, ContentType::Json).unwrap();
+
+		assert_eq!(data, Data {
+			hello: "world".into()
+		});
+	}
+
+	#[test]
+	fn test_codec_comparison() {
+		use std::collections::HashSet;
+
+		let bytes = b"{\"hello\": \"world\"}";
+
+		let codec = Codec::<Data>::from_bytes(bytes, ContentType::Json).unwrap();
+
+		assert_eq!(
+			codec,
+			Codec(Data {
+				hello: "world".into()
+			})
+		);
+		assert_ne!(codec, Codec(Data { hello: String::new() }));
+
+		let mut set = HashSet::new();
+		set.insert(Codec(1));
+		assert!(set.contains(&Codec(1)));
+		assert_eq!(format!("{:?}", Codec(1)), "Codec(1)");
+	}
+
+	#[cfg(feature = "validator")]
+	#[test]
+	fn test_validated_response() {
+		use axum::http::StatusCode;
+
+		#[crate::apply(encode, decode)]
+		struct Age {
+			#[validate(range(max = 150))]
+			age: u8,
+		}
+
+		let response = Codec(Age { age: 200 }).into_validated_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+		let response = Codec(Age { age: 42 }).into_validated_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::OK);
+	}
+
+	#[test]
+	fn test_try_to_response() {
+		use crate::encode;
+
+		#[crate::apply(encode)]
+		struct Primitive(u32);
+
+		let response = Codec(Primitive(1))
+			.try_to_response(ContentType::Json)
+			.unwrap();
+		assert_eq!(
+			response.headers()[axum::http::header::CONTENT_TYPE],
+			ContentType::Json.into_header()
+		);
+
+		// TOML documents must be tables.
+		assert!(matches!(
+			Codec(Primitive(1)).try_to_response(ContentType::Toml),
+			Err(encode::Error::Toml(..))
+		));
+	}
+
+	#[test]
+	fn test_into_builder() {
+		use axum::{body::Body, http::StatusCode};
+
+		#[crate::apply(encode)]
+		struct Job {
+			id: u64,
+		}
+
+		#[crate::apply(encode)]
+		struct Primitive(u32);
+
+		let (builder, body) = Codec(Job { id: 1 })
+			.into_builder(ContentType::Json)
+			.unwrap();
+		let response = builder
+			.status(StatusCode::ACCEPTED)
+			.body(Body::from(body))
+			.unwrap();
+
+		assert_eq!(response.status(), StatusCode::ACCEPTED);
+		assert_eq!(
+			response.headers()[axum::http::header::CONTENT_TYPE],
+			ContentType::Json.into_header()
+		);
+
+		// TOML documents must be tables.
+		assert!(Codec(Primitive(1)).into_builder(ContentType::Toml).is_err());
+	}
+
+	#[cfg(feature = "pretty-errors")]
+	#[tokio::test]
+	async fn test_encode_error_is_negotiated() {
+		use axum::{body::to_bytes, http::StatusCode};
+
+		#[crate::apply(encode)]
+		struct Primitive(u32);
+
+		#[crate::apply(decode)]
+		struct OwnedMessage {
+			code: String,
+		}
+
+		// TOML documents must be tables, but the error message is one.
+		let response = Codec(Primitive(1)).to_response(ContentType::Toml);
+		assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+		assert_eq!(
+			response.headers()[axum::http::header::CONTENT_TYPE],
+			ContentType::Toml.into_header()
+		);
+
+		let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let Codec(message) = Codec::<OwnedMessage>::from_bytes(&bytes, ContentType::Toml).unwrap();
+
+		assert_eq!(message.code, "internal");
+	}
+
+	#[test]
+	fn test_to_response_for_skips_unsupported_shapes() {
+		use axum::http::{header, HeaderMap, HeaderValue};
+
+		use crate::Accept;
+
+		#[crate::apply(encode)]
+		struct Item {
+			id: u32,
+		}
+
+		let mut headers = HeaderMap::new();
+		headers.insert(
+			header::ACCEPT,
+			HeaderValue::from_static("text/toml, application/bson;q=0.9, application/cbor;q=0.5"),
+		);
+		let accept = Accept::from_headers(&headers);
+
+		let response = Codec(Item { id: 1 }).to_response_for(accept);
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::Toml.into_header()
+		);
+
+		let response = Codec(vec![Item { id: 1 }]).to_response_for(accept);
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::Cbor.into_header()
+		);
+
+		headers.insert(header::ACCEPT, HeaderValue::from_static("text/toml"));
+		let response = Codec(vec![Item { id: 1 }]).to_response_for(Accept::from_headers(&headers));
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::default().into_header()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_conflicting_content_types() {
+		use axum::{
+			body::Body,
+			extract::FromRequest,
+			http::{header, Request, StatusCode},
+		};
+
+		let request = |content_types: [&str; 2]| {
+			let mut builder = Request::builder();
+
+			for content_type in content_types {
+				builder = builder.header(header::CONTENT_TYPE, content_type);
+			}
+
+			builder.body(Body::from("{\"hello\":\"world\"}")).unwrap()
+		};
+
+		let Err(response) =
+			Codec::<Data>::from_request(request(["application/json", "application/msgpack"]), &()).await
+		else {
+			panic!("expected conflicting content types to be rejected");
+		};
+
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+		assert!(
+			Codec::<Data>::from_request(request(["application/json", "application/json"]), &())
+				.await
+				.is_ok()
+		);
+	}
+
+	#[cfg(feature = "encoding")]
+	#[test]
+	fn test_unsupported_charset_only_rejects_text() {
+		use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+
+		use crate::AcceptCharset;
+
+		#[crate::apply(encode)]
+		struct Greeting {
+			hello: String,
+		}
+
+		let mut headers = HeaderMap::new();
+		headers.insert(header::ACCEPT_CHARSET, HeaderValue::from_static("x-unknown"));
+
+		let charset = AcceptCharset::from_headers(&headers);
+		let data = Codec(Greeting {
+			hello: "world".into(),
+		});
+
+		assert_eq!(
+			data
+				.to_response_with_charset(ContentType::Json, charset)
+				.status(),
+			StatusCode::NOT_ACCEPTABLE
+		);
+		assert_eq!(
+			data
+				.to_response_with_charset(ContentType::MsgPack, charset)
+				.status(),
+			StatusCode::OK
+		);
+	}
+
+	#[cfg(feature = "base64")]
+	#[tokio::test]
+	async fn test_base64_transfer_encoding() {
+		use axum::{
+			body::Body,
+			extract::FromRequest,
+			http::{header, Request},
+		};
+
+		use super::{CodecOrDefault, SymmetricCodec, TimedCodec};
+
+		#[crate::apply(decode)]
+		#[derive(Default)]
+		struct Update {
+			hello: Option<String>,
+		}
+
+		let request = || {
+			Request::builder()
+				.header(header::CONTENT_TYPE, "application/json")
+				.header("content-transfer-encoding", "BASE64")
+				.body(Body::from("eyJoZWxsbyI6\r\nIndvcmxkIn0="))
+				.unwrap()
+		};
+
+		let Ok(Codec(data)) = Codec::<Data>::from_request(request(), &()).await else {
+			panic!("expected the base64 body to decode");
+		};
+
+		assert_eq!(data, Data {
+			hello: "world".into()
+		});
+
+		let Ok(SymmetricCodec(data, _)) = SymmetricCodec::<Data>::from_request(request(), &()).await
+		else {
+			panic!("expected the base64 body to decode");
+		};
+
+		assert_eq!(data.hello, "world");
+
+		let Ok(TimedCodec(data, _)) = TimedCodec::<Data>::from_request(request(), &()).await else {
+			panic!("expected the base64 body to decode");
+		};
+
+		assert_eq!(data.hello, "world");
+
+		let Ok(CodecOrDefault(update)) = CodecOrDefault::<Update>::from_request(request(), &()).await
+		else {
+			panic!("expected the base64 body to decode");
+		};
+
+		assert_eq!(update.hello.as_deref(), Some("world"));
+	}
+
+	#[tokio::test]
+	async fn test_codec_or_default() {
+		use axum::{body::Body, extract::FromRequest, http::Request};
+
+		use super::CodecOrDefault;
+
+		#[crate::apply(decode)]
+		#[derive(Debug, Default, PartialEq, Eq)]
+		struct Update {
+			title: Option<String>,
+		}
+
+		let request = |body: &'static str| {
+			Request::builder()
+				.header("content-type", "application/json")
+				.body(Body::from(body))
+				.unwrap()
+		};
+
+		let Ok(CodecOrDefault(update)) = CodecOrDefault::<Update>::from_request(request(""), &()).await
+		else {
+			panic!("expected an empty body to decode");
+		};
+
+		assert_eq!(update, Update::default());
+
+		let Ok(CodecOrDefault(update)) =
+			CodecOrDefault::<Update>::from_request(request("{\"title\":\"a\"}"), &()).await
+		else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(update.title.as_deref(), Some("a"));
+
+		assert!(CodecOrDefault::<Update>::from_request(request("{"), &())
+			.await
+			.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_timed_codec() {
+		use axum::{body::Body, extract::FromRequest, http::Request};
+
+		use super::TimedCodec;
+
+		let request = |body: &'static str| {
+			Request::builder()
+				.header("content-type", "application/json")
+				.body(Body::from(body))
+				.unwrap()
+		};
+
+		let Ok(TimedCodec(data, elapsed)) =
+			TimedCodec::<Data>::from_request(request("{\"hello\":\"world\"}"), &()).await
+		else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(data, Data {
+			hello: "world".into()
+		});
+		assert!(elapsed < core::time::Duration::from_secs(1));
+
+		assert!(TimedCodec::<Data>::from_request(request("{"), &())
+			.await
+			.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_codec_with_raw() {
+		use axum::{body::Body, extract::FromRequest, http::Request};
+
+		use super::CodecWithRaw;
+
+		let body = "{ \"hello\" : \"world\" }";
+		let request = Request::builder()
+			.header("content-type", "application/json")
+			.body(Body::from(body))
+			.unwrap();
+
+		let Ok(codec) = CodecWithRaw::<Data>::from_request(request, &()).await else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(codec.raw, body);
+		assert_eq!(codec.into_inner(), Data {
+			hello: "world".into()
+		});
+
+		let request = Request::builder()
+			.header("content-type", "application/json")
+			.header("content-transfer-encoding", "base64")
+			.body(Body::from("eyJoZWxsbyI6IndvcmxkIn0="))
+			.unwrap();
+
+		let Ok(codec) = CodecWithRaw::<Data>::from_request(request, &()).await else {
+			panic!("expected the base64 body to decode");
+		};
+
+		assert_eq!(codec.raw, "eyJoZWxsbyI6IndvcmxkIn0=");
+		assert_eq!(codec.hello, "world");
+	}
+
+	#[tokio::test]
+	async fn test_restricted_codec() {
+		use axum::{
+			body::{to_bytes, Body},
+			extract::FromRequest,
+			http::{header, Request, StatusCode},
+		};
+
+		use super::RestrictedCodec;
+		use crate::format::{Json, MsgPack, Only};
+
+		type JsonOrMsgPack = RestrictedCodec<Data, Only<(Json, MsgPack)>>;
+
+		let request = |content_type: &str, body: Vec<u8>| {
+			Request::builder()
+				.header(header::CONTENT_TYPE, content_type)
+				.body(Body::from(body))
+				.unwrap()
+		};
+		let data = Data {
+			hello: "world".into(),
+		};
+
+		let json = br#"{"hello":"world"}"#.to_vec();
+		let msgpack = rmp_serde::to_vec_named(&serde_json::json!({ "hello": "world" })).unwrap();
+
+		for (content_type, bytes) in [
+			(ContentType::Json, json.clone()),
+			(ContentType::MsgPack, msgpack),
+		] {
+			let Ok(codec) = JsonOrMsgPack::from_request(request(content_type.as_str(), bytes), &()).await
+			else {
+				panic!("expected {content_type} to be accepted");
+			};
+
+			assert_eq!(codec.into_inner(), data);
+		}
+
+		// The body is not read, since the format is rejected first.
+		let Err(response) =
+			JsonOrMsgPack::from_request(request("application/cbor", Vec::new()), &()).await
+		else {
+			panic!("expected CBOR to be rejected");
+		};
+
+		assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+		let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let body = String::from_utf8_lossy(&body);
+
+		assert!(
+			body.contains("application/json, application/vnd.msgpack"),
+			"{body}"
+		);
+
+		let result =
+			RestrictedCodec::<Data, Only<MsgPack>>::from_request(request("application/json", json), &())
+				.await;
+
+		assert!(result.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_negotiated() {
+		use axum::{
+			body::Body,
+			extract::FromRequest,
+			http::{header, Request},
+		};
+
+		use super::Negotiated;
+
+		#[crate::apply(encode)]
+		struct Reply {
+			hello: String,
+		}
+
+		let request = Request::builder()
+			.header(header::CONTENT_TYPE, "application/json")
+			.header(header::ACCEPT, "application/cbor")
+			.body(Body::from("{\"hello\":\"world\"}"))
+			.unwrap();
+
+		let Ok(request) = Negotiated::<Data>::from_request(request, &()).await else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(request.content_type(), ContentType::Cbor);
+
+		let response = request.respond(Reply {
+			hello: request.hello.clone(),
+		});
+
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::Cbor.into_header()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_validated_codec() {
+		use axum::{
+			body::Body,
+			extract::FromRequest,
+			http::{Request, StatusCode},
+		};
+
+		use super::{ValidateWithState, ValidatedCodec};
+
+		struct Taken(&'static str);
+
+		impl ValidateWithState<Taken> for Data {
+			type Rejection = (StatusCode, &'static str);
+
+			async fn validate_with_state(&self, state: &Taken) -> Result<(), Self::Rejection> {
+				if self.hello == state.0 {
+					Err((StatusCode::CONFLICT, "taken"))
+				} else {
+					Ok(())
+				}
+			}
+		}
+
+		let request = |hello: &str| {
+			Request::builder()
+				.header("content-type", "application/json")
+				.body(Body::from(format!("{{\"hello\":\"{hello}\"}}")))
+				.unwrap()
+		};
+
+		let Ok(ValidatedCodec(data)) =
+			ValidatedCodec::<Data>::from_request(request("world"), &Taken("there")).await
+		else {
+			panic!("expected the request to be accepted");
+		};
+
+		assert_eq!(data.hello, "world");
+
+		let Err(response) =
+			ValidatedCodec::<Data>::from_request(request("there"), &Taken("there")).await
+		else {
+			panic!("expected the request to be rejected");
+		};
+
+		assert_eq!(response.status(), StatusCode::CONFLICT);
+	}
+
+	#[cfg(feature = "digest")]
+	#[test]
+	fn test_response_with_checksum() {
+		#[crate::apply(encode)]
+		struct Primitive(u32);
+
+		let response = Codec(Primitive(1)).to_response_with_checksum(ContentType::Json);
+
+		// SHA-256 of `1`
+		assert_eq!(
+			response.headers()["content-digest"],
+			"sha-256=:a4ayc/80/OGda4BO/1o/V0etpOqiLx1JwB5S3beHW0s=:"
+		);
+	}
+
+	#[cfg(feature = "validate-responses")]
+	#[test]
+	fn test_invalid_response_is_server_error() {
+		use axum::http::StatusCode;
+
+		#[crate::apply(encode)]
+		struct Percent {
+			#[validate(range(max = 100))]
+			value: u8,
+		}
+
+		let response = Codec(Percent { value: 101 }).to_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+		assert!(response
+			.extensions()
+			.get::<validator::ValidationErrors>()
+			.is_some_and(|errors| errors.field_errors().contains_key("value")));
+
+		let response = Codec(Percent { value: 50 }).to_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::OK);
+	}
+
+	#[tokio::test]
+	async fn test_symmetric_codec() {
+		use axum::{
+			extract::{FromRequest, Request},
+			http::header,
+		};
+
+		use super::SymmetricCodec;
+		use crate::IntoCodecResponse;
+
+		#[crate::apply(encode)]
+		struct Reply {
+			hello: String,
+		}
+
+		let request = Request::builder()
+			.header(header::CONTENT_TYPE, "application/vnd.msgpack")
+			.header(header::ACCEPT, "application/json")
+			.body(b"\x81\xa5hello\xa5world".to_vec().into())
+			.unwrap();
+
+		let codec = SymmetricCodec::<Data>::from_request(request, &())
+			.await
+			.unwrap();
+		assert_eq!(codec.content_type(), ContentType::MsgPack);
+
+		let response = codec
+			.map(|data| Reply { hello: data.hello })
+			.into_codec_response(ContentType::Json);
+
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::MsgPack.into_header()
+		);
+	}
+
+	#[test]
+	fn test_try_map() {
+		let codec = Codec(Data { hello: "42".into() });
+
+		let Codec(number) = codec.try_map(|data| data.hello.parse::<u8>()).unwrap();
+		assert_eq!(number, 42);
+
+		let codec = Codec(Data {
+			hello: "world".into(),
+		});
+
+		assert!(codec.try_map(|data| data.hello.parse::<u8>()).is_err());
 	}
 
 	#[test]
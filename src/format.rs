@@ -0,0 +1,88 @@
+//! Type-level names for the built-in formats, used to restrict the formats
+//! accepted by an endpoint with [`RestrictedCodec`](crate::RestrictedCodec).
+
+use core::marker::PhantomData;
+
+use crate::ContentType;
+
+/// A format that can be named at the type level.
+pub trait Format {
+	/// The content type of the format.
+	const CONTENT_TYPE: ContentType;
+}
+
+/// A set of formats that can be named at the type level, such as
+/// [`Only<(Json, MsgPack)>`](Only).
+pub trait FormatSet {
+	/// The content types in the set.
+	const CONTENT_TYPES: &'static [ContentType];
+
+	/// Returns `true` if the content type is in the set.
+	#[must_use]
+	fn contains(content_type: ContentType) -> bool {
+		Self::CONTENT_TYPES.contains(&content_type)
+	}
+}
+
+/// The set made up of a single [`Format`], such as `Only<Json>`, or a tuple of
+/// them, such as `Only<(Json, MsgPack)>`.
+pub struct Only<F>(PhantomData<F>);
+
+impl<A: Format> FormatSet for Only<A> {
+	const CONTENT_TYPES: &'static [ContentType] = &[A::CONTENT_TYPE];
+}
+
+macro_rules! formats {
+	($($(#[$meta:meta])* $feature:literal => $name:ident,)*) => {
+		$(
+			$(#[$meta])*
+			#[cfg(feature = $feature)]
+			#[derive(Debug, Clone, Copy)]
+			pub enum $name {}
+
+			#[cfg(feature = $feature)]
+			impl Format for $name {
+				const CONTENT_TYPE: ContentType = ContentType::$name;
+			}
+		)*
+	};
+}
+
+formats! {
+	/// JSON (`application/json`).
+	"json" => Json,
+	/// `MessagePack` (`application/vnd.msgpack`).
+	"msgpack" => MsgPack,
+	/// Bincode (`application/vnd.bincode`).
+	"bincode" => Bincode,
+	/// Bitcode (`application/vnd.bitcode`).
+	"bitcode" => Bitcode,
+	/// CBOR (`application/cbor`).
+	"cbor" => Cbor,
+	/// BSON (`application/bson`).
+	"bson" => Bson,
+	/// YAML (`application/x-yaml`).
+	"yaml" => Yaml,
+	/// TOML (`text/toml`).
+	"toml" => Toml,
+	/// RON (`application/ron`).
+	"ron" => Ron,
+}
+
+macro_rules! format_sets {
+	($($name:ident),*) => {
+		impl<$($name: Format),*> FormatSet for Only<($($name,)*)> {
+			const CONTENT_TYPES: &'static [ContentType] = &[$($name::CONTENT_TYPE),*];
+		}
+	};
+}
+
+format_sets!(A);
+format_sets!(A, B);
+format_sets!(A, B, C);
+format_sets!(A, B, C, D);
+format_sets!(A, B, C, D, E);
+format_sets!(A, B, C, D, E, F);
+format_sets!(A, B, C, D, E, F, G);
+format_sets!(A, B, C, D, E, F, G, H);
+format_sets!(A, B, C, D, E, F, G, H, I);
@@ -0,0 +1,271 @@
+//! Length-prefixed framing of values encoded with the same formats as
+//! [`Codec`], for building simple RPC streams (such as over HTTP/2) without
+//! full gRPC.
+//!
+//! Each frame is a 4-byte big-endian length followed by that many bytes of the
+//! encoded value, which is the same framing that
+//! [`CodecChunks`](crate::response::CodecChunks) uses for binary formats.
+
+use crate::{encode, Codec, CodecDecode, CodecEncode, CodecRejection, ContentType};
+
+/// The number of bytes used for the length prefix of each frame.
+pub const PREFIX_LEN: usize = 4;
+
+/// The default largest frame accepted by a [`FrameDecoder`], in bytes.
+pub const DEFAULT_MAX_LEN: usize = 2 * 1024 * 1024;
+
+/// Errors that can occur while encoding a frame with [`encode_frame`].
+#[derive(Debug, thiserror::Error)]
+pub enum FrameError {
+	/// The value could not be encoded.
+	#[error(transparent)]
+	Encode(#[from] encode::Error),
+	/// The encoded value does not fit in the 4-byte length prefix.
+	#[error("frame of {len} bytes does not fit in the length prefix")]
+	TooLarge { len: usize },
+}
+
+/// Encodes a value in the given format, prefixed with its length.
+///
+/// ```edition2021
+/// # use axum_codec::{framed, ContentType};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Ping {
+///   id: u32,
+/// }
+///
+/// # fn main() {
+/// let frame = framed::encode_frame(Ping { id: 1 }, ContentType::Json).unwrap();
+///
+/// assert_eq!(frame, b"\0\0\0\x08{\"id\":1}");
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// See [`FrameError`].
+pub fn encode_frame<T>(value: T, content_type: ContentType) -> Result<Vec<u8>, FrameError>
+where
+	T: CodecEncode,
+{
+	let mut buf = vec![0; PREFIX_LEN];
+
+	Codec(value).encode_into(content_type, &mut buf)?;
+
+	let len = buf.len() - PREFIX_LEN;
+	let prefix = u32::try_from(len).map_err(|_| FrameError::TooLarge { len })?;
+
+	buf[..PREFIX_LEN].copy_from_slice(&prefix.to_be_bytes());
+
+	Ok(buf)
+}
+
+/// Reads frames written by [`encode_frame`] from a buffer that is filled as
+/// bytes arrive, such as from a request body stream.
+///
+/// Frames may be split across chunks, or several frames may arrive in one
+/// chunk, so bytes are buffered until a whole frame is available.
+///
+/// ```edition2021
+/// # use axum_codec::{framed::{self, FrameDecoder}, ContentType};
+/// #
+/// #[axum_codec::apply(encode, decode)]
+/// #[derive(Debug, PartialEq)]
+/// struct Ping {
+///   id: u32,
+/// }
+///
+/// # fn main() {
+/// let frame = framed::encode_frame(Ping { id: 1 }, ContentType::Json).unwrap();
+/// let mut decoder = FrameDecoder::new(ContentType::Json);
+///
+/// decoder.extend_from_slice(&frame[..6]);
+/// assert_eq!(decoder.decode::<Ping>().unwrap(), None);
+///
+/// decoder.extend_from_slice(&frame[6..]);
+/// assert_eq!(decoder.decode::<Ping>().unwrap(), Some(Ping { id: 1 }));
+/// assert!(decoder.is_empty());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameDecoder {
+	buf: Vec<u8>,
+	/// The start of the first frame that has not been decoded yet. Decoded
+	/// frames are only removed from `buf` when more bytes are appended, so
+	/// decoding many buffered frames does not move the rest each time.
+	pos: usize,
+	content_type: ContentType,
+	max_len: usize,
+}
+
+impl FrameDecoder {
+	/// Creates a decoder for frames in the given format, accepting frames of up
+	/// to [`DEFAULT_MAX_LEN`] bytes.
+	#[must_use]
+	pub fn new(content_type: ContentType) -> Self {
+		Self {
+			buf: Vec::new(),
+			pos: 0,
+			content_type,
+			max_len: DEFAULT_MAX_LEN,
+		}
+	}
+
+	/// Sets the largest frame accepted, in bytes, not counting the prefix.
+	#[must_use]
+	pub fn with_max_len(mut self, max_len: usize) -> Self {
+		self.max_len = max_len;
+		self
+	}
+
+	/// Appends received bytes to the buffer.
+	pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+		self.buf.drain(..self.pos);
+		self.pos = 0;
+		self.buf.extend_from_slice(bytes);
+	}
+
+	/// Returns `true` if no bytes are buffered, meaning the stream ended on a
+	/// frame boundary.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.pos == self.buf.len()
+	}
+
+	/// Decodes the next frame and removes it from the buffer, validating it if
+	/// the `validator` feature is enabled.
+	///
+	/// Returns `Ok(None)` if the buffer does not contain a whole frame yet.
+	///
+	/// # Errors
+	///
+	/// Returns [`CodecRejection::PayloadTooLarge`] if the next frame is larger
+	/// than the limit. Its bytes are not consumed, so the stream should be
+	/// closed. See [`CodecRejection`] for other errors, after which the frame is
+	/// skipped.
+	pub fn decode<T>(&mut self) -> Result<Option<T>, CodecRejection>
+	where
+		T: CodecDecode,
+	{
+		let buf = &self.buf[self.pos..];
+
+		let Some(prefix) = buf.first_chunk::<PREFIX_LEN>() else {
+			return Ok(None);
+		};
+
+		let len = u32::from_be_bytes(*prefix) as usize;
+
+		if len > self.max_len {
+			return Err(CodecRejection::PayloadTooLarge {
+				limit: self.max_len,
+			});
+		}
+
+		let end = PREFIX_LEN + len;
+
+		if buf.len() < end {
+			return Ok(None);
+		}
+
+		let result = Codec::from_bytes(&buf[PREFIX_LEN..end], self.content_type);
+
+		self.pos += end;
+
+		result.map(|Codec(value)| Some(value))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{encode_frame, FrameDecoder, PREFIX_LEN};
+	use crate::{CodecRejection, ContentType};
+
+	#[crate::apply(encode, decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Call {
+		method: String,
+		id: u32,
+	}
+
+	fn call(id: u32) -> Call {
+		Call {
+			method: "ping".into(),
+			id,
+		}
+	}
+
+	#[test]
+	fn test_round_trip() {
+		for content_type in ContentType::all() {
+			let stream = [
+				encode_frame(call(1), *content_type).unwrap(),
+				encode_frame(call(2), *content_type).unwrap(),
+			]
+			.concat();
+
+			let mut decoder = FrameDecoder::new(*content_type);
+			let mut calls = Vec::new();
+
+			// Feed the bytes one at a time to split frames at every position.
+			for byte in &stream {
+				decoder.extend_from_slice(&[*byte]);
+
+				while let Some(call) = decoder.decode::<Call>().unwrap() {
+					calls.push(call);
+				}
+			}
+
+			assert_eq!(calls, [call(1), call(2)], "{content_type}");
+			assert!(decoder.is_empty(), "{content_type}");
+		}
+	}
+
+	#[test]
+	fn test_many_buffered_frames() {
+		let mut decoder = FrameDecoder::new(ContentType::Json);
+
+		for id in 0..100 {
+			decoder.extend_from_slice(&encode_frame(call(id), ContentType::Json).unwrap());
+		}
+
+		for id in 0..50 {
+			assert_eq!(decoder.decode::<Call>().unwrap(), Some(call(id)));
+		}
+
+		decoder.extend_from_slice(&encode_frame(call(100), ContentType::Json).unwrap());
+
+		for id in 50..=100 {
+			assert_eq!(decoder.decode::<Call>().unwrap(), Some(call(id)));
+		}
+
+		assert_eq!(decoder.decode::<Call>().unwrap(), None);
+		assert!(decoder.is_empty());
+	}
+
+	#[test]
+	fn test_errors() {
+		let frame = encode_frame(call(1), ContentType::Json).unwrap();
+		let mut decoder = FrameDecoder::new(ContentType::Json).with_max_len(4);
+
+		decoder.extend_from_slice(&frame);
+		assert!(matches!(
+			decoder.decode::<Call>(),
+			Err(CodecRejection::PayloadTooLarge { limit: 4 })
+		));
+
+		let mut decoder = FrameDecoder::new(ContentType::Json);
+
+		decoder.extend_from_slice(&[0, 0, 0, 2]);
+		decoder.extend_from_slice(b"{}");
+		decoder.extend_from_slice(&frame);
+
+		assert!(decoder.decode::<Call>().is_err());
+		assert_eq!(decoder.decode::<Call>().unwrap(), Some(call(1)));
+		assert!(decoder.is_empty());
+
+		decoder.extend_from_slice(&frame[..PREFIX_LEN]);
+		assert_eq!(decoder.decode::<Call>().unwrap(), None);
+		assert!(!decoder.is_empty());
+	}
+}
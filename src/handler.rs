@@ -3,6 +3,7 @@ use std::{future::Future, pin::Pin};
 use axum::{
 	extract::{FromRequest, FromRequestParts, Request},
 	handler::Handler,
+	http::request::Parts,
 	response::{IntoResponse, Response},
 };
 
@@ -126,6 +127,23 @@ where
 	}
 }
 
+/// Resolves the response format of a request once, before any extractor runs,
+/// and stores it in the request extensions.
+///
+/// [`Accept`] returns the stored value, so an `Accept` extracted by the
+/// handler (or resolved by an extractor rejecting the request) is always the
+/// one used to encode the returned value.
+fn negotiate(parts: &mut Parts) -> Accept {
+	let accept = Accept::from_request_head(&parts.uri, &parts.headers, &parts.extensions);
+
+	parts.extensions.insert(accept);
+
+	#[cfg(feature = "metrics")]
+	crate::metrics::record_negotiation(accept.into());
+
+	accept
+}
+
 impl<F, Fut, Res, S> CodecHandler<((),), (), Res, S> for F
 where
 	F: FnOnce() -> Fut + Clone + Send + 'static,
@@ -135,15 +153,12 @@ where
 {
 	type Future = Pin<Box<dyn Future<Output = Response> + Send>>;
 
-	fn call(self, req: Request, state: S) -> Self::Future {
+	fn call(self, req: Request, _state: S) -> Self::Future {
 		Box::pin(async move {
 			let (mut parts, ..) = req.into_parts();
+			let accept = negotiate(&mut parts);
 
-			let content_type = Accept::from_request_parts(&mut parts, &state)
-				.await
-				.unwrap();
-
-			self().await.into_codec_response(content_type.into())
+			self().await.into_codec_response(accept.into())
 		})
 	}
 }
@@ -168,7 +183,7 @@ macro_rules! impl_handler {
 				Box::pin(async move {
 					let (mut parts, body) = req.into_parts();
 
-					let content_type = Accept::from_request_parts(&mut parts, &state).await.unwrap();
+					let accept = negotiate(&mut parts);
 
 					$(
 						let $ty = match $ty::from_request_parts(&mut parts, &state).await {
@@ -185,7 +200,7 @@ macro_rules! impl_handler {
 					};
 
 					self($($ty,)* $last,).await
-						.into_codec_response(content_type.into())
+						.into_codec_response(accept.into())
 				})
 			}
 		}
@@ -223,3 +238,60 @@ macro_rules! all_the_tuples {
 }
 
 all_the_tuples!(impl_handler);
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		body::Body,
+		http::{header, HeaderMap, HeaderValue, Request},
+		Extension, Router,
+	};
+	use tower::ServiceExt;
+
+	use crate::{routing::get, Accept, Codec, ContentType};
+
+	#[crate::apply(encode, crate = "crate")]
+	struct Format(String);
+
+	async fn format(accept: Accept) -> Codec<Format> {
+		Codec(Format(accept.content_type().to_string()))
+	}
+
+	async fn call(router: Router, accept: &'static str) -> (HeaderValue, Vec<u8>) {
+		let response = router
+			.oneshot(
+				Request::builder()
+					.header(header::ACCEPT, accept)
+					.body(Body::empty())
+					.unwrap(),
+			)
+			.await
+			.unwrap();
+
+		let content_type = response.headers()[header::CONTENT_TYPE].clone();
+		let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+			.await
+			.unwrap();
+
+		(content_type, body.to_vec())
+	}
+
+	#[tokio::test]
+	async fn test_handler_and_extractor_agree() {
+		let router = Router::new().route("/", get(format).into());
+
+		let (content_type, body) = call(router.clone(), "application/yaml").await;
+
+		assert_eq!(content_type, ContentType::Yaml.into_header());
+		assert_eq!(body, b"application/x-yaml\n");
+
+		let mut headers = HeaderMap::new();
+		headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+
+		let router = router.layer(Extension(Accept::from_headers(&headers)));
+		let (content_type, body) = call(router, "application/yaml").await;
+
+		assert_eq!(content_type, ContentType::Json.into_header());
+		assert_eq!(body, b"\"application/json\"");
+	}
+}
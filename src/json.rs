@@ -0,0 +1,159 @@
+//! The JSON backend used for whole bodies, which is `serde_json` by default or
+//! the SIMD-accelerated `sonic-rs` with the `json-sonic-rs` feature.
+//!
+//! Errors are always reported as [`serde_json::Error`], so the public API is
+//! the same with either backend. With `sonic-rs`, syntax and EOF errors are
+//! reproduced with `serde_json` so that [`serde_json::Error::classify`] gives
+//! the same answer as without it. Streaming, seeded and [`serde_json::Value`]
+//! decoding always use `serde_json`.
+
+#[cfg(not(all(feature = "json-sonic-rs", not(feature = "arbitrary-precision"))))]
+pub(crate) use serde_json::{from_slice, to_vec, to_writer};
+
+#[cfg(all(feature = "json-sonic-rs", not(feature = "arbitrary-precision")))]
+pub(crate) use self::sonic::{from_slice, to_vec, to_writer};
+
+#[cfg(feature = "json-sonic-rs")]
+#[cfg_attr(feature = "arbitrary-precision", allow(dead_code))]
+mod sonic {
+	use serde::{de::Error as _, Deserialize, Serialize};
+
+	/// Converts a `sonic-rs` error into a [`serde_json::Error`], keeping its
+	/// message. Only I/O errors keep their category, every other error is
+	/// reported as [`serde_json::error::Category::Data`].
+	fn convert(err: sonic_rs::Error) -> serde_json::Error {
+		if err.is_io() {
+			serde_json::Error::io(std::io::Error::other(err))
+		} else {
+			serde_json::Error::custom(err)
+		}
+	}
+
+	pub(crate) fn from_slice<'de, T>(bytes: &'de [u8]) -> Result<T, serde_json::Error>
+	where
+		T: Deserialize<'de>,
+	{
+		sonic_rs::from_slice(bytes).map_err(|err| {
+			// `serde_json::Error` can only be created with the `Data` and `Io`
+			// categories from outside of `serde_json`, so malformed input is parsed
+			// again to get an equivalent error. This only happens for invalid
+			// bodies, so it does not slow down the common case.
+			if err.is_syntax() || err.is_eof() {
+				if let Err(err) = serde_json::from_slice::<T>(bytes) {
+					return err;
+				}
+			}
+
+			convert(err)
+		})
+	}
+
+	pub(crate) fn to_vec<T>(value: &T) -> Result<Vec<u8>, serde_json::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		sonic_rs::to_vec(value).map_err(convert)
+	}
+
+	pub(crate) fn to_writer<T>(buf: &mut Vec<u8>, value: &T) -> Result<(), serde_json::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		sonic_rs::to_writer(buf, value).map_err(convert)
+	}
+
+	#[cfg(test)]
+	mod test {
+		use std::collections::BTreeMap;
+
+		#[crate::apply(encode, decode, crate = "crate")]
+		#[derive(Debug, PartialEq)]
+		struct Data {
+			string: String,
+			float: Option<f64>,
+			map: BTreeMap<String, Vec<u32>>,
+		}
+
+		fn data() -> Data {
+			Data {
+				string: "hello \"world\" \u{1f600}".into(),
+				float: Some(1.5),
+				map: BTreeMap::from([("a".into(), vec![1, 2]), ("b".into(), vec![])]),
+			}
+		}
+
+		#[test]
+		fn test_matches_serde_json() {
+			let bytes = super::to_vec(&data()).unwrap();
+			assert_eq!(bytes, serde_json::to_vec(&data()).unwrap());
+
+			let mut buf = b"prefix".to_vec();
+			super::to_writer(&mut buf, &data()).unwrap();
+			assert_eq!(&buf[6..], bytes);
+
+			assert_eq!(super::from_slice::<Data>(&bytes).unwrap(), data());
+
+			let nan = Data {
+				float: Some(f64::NAN),
+				..data()
+			};
+			assert_eq!(
+				super::to_vec(&nan).unwrap(),
+				serde_json::to_vec(&nan).unwrap()
+			);
+		}
+
+		#[test]
+		fn test_errors() {
+			assert!(super::from_slice::<Data>(b"{").is_err());
+			assert!(super::from_slice::<Data>(b"{\"string\":1}").is_err());
+			assert!(super::from_slice::<Data>(b"\"\xff\"").is_err());
+		}
+
+		#[test]
+		fn test_error_categories() {
+			use serde_json::error::Category;
+
+			use crate::{Codec, CodecRejection, ContentType};
+
+			let classify = |body: &[u8]| match Codec::<Data>::from_bytes(body, ContentType::Json) {
+				Err(CodecRejection::Json(err)) => err.classify(),
+				_ => panic!("expected a JSON error"),
+			};
+
+			assert_eq!(classify(b"{"), Category::Eof);
+			assert_eq!(classify(b"{]"), Category::Syntax);
+			assert_eq!(classify(b"{\"string\":1}"), Category::Data);
+		}
+
+		#[tokio::test]
+		async fn test_extractor() {
+			use axum::{
+				body::Body,
+				extract::FromRequest,
+				http::{Request, StatusCode},
+			};
+
+			use crate::Codec;
+
+			let request = |body: Vec<u8>| {
+				Request::builder()
+					.header("content-type", "application/json")
+					.body(Body::from(body))
+					.unwrap()
+			};
+
+			let Ok(Codec(decoded)) =
+				Codec::<Data>::from_request(request(super::to_vec(&data()).unwrap()), &()).await
+			else {
+				panic!("expected the body to decode");
+			};
+			assert_eq!(decoded, data());
+
+			let Err(response) = Codec::<Data>::from_request(request(b"{]".to_vec()), &()).await else {
+				panic!("expected the body to be rejected");
+			};
+			assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+		}
+	}
+}
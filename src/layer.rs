@@ -0,0 +1,760 @@
+//! Layers for tagging responses from handlers that do not use
+//! [`Codec`](crate::Codec) with the negotiated content type, rejecting
+//! requests that accept no supported format, changing the status codes of
+//! rejections, and echoing a request id on every response.
+
+use core::{
+	future::{ready, Future},
+	pin::Pin,
+	sync::atomic::{AtomicU64, Ordering},
+	task::{Context, Poll},
+};
+use std::hash::{BuildHasher, RandomState};
+
+use axum::{
+	body::HttpBody,
+	extract::Request,
+	http::{header, HeaderName, HeaderValue, StatusCode},
+	response::Response,
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+	rejection::{Rejected, RejectionStatus},
+	Accept, CodecRejection, ContentType, IntoCodecResponse,
+};
+
+/// A [`Layer`] that sets the `Content-Type` of responses that do not have one
+/// to the content type negotiated from the request's `Accept` header.
+///
+/// This is useful for handlers that return plain axum types, such as
+/// pre-encoded bytes, while the rest of the API uses [`Codec`](crate::Codec).
+/// Responses that already have a `Content-Type`, and responses without a
+/// body, are left untouched.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::layer::CodecLayer;
+/// # use axum::{routing::get, Router};
+/// #
+/// # fn main() {
+/// let router: Router = Router::new()
+///   .route("/cached", get(|| async { axum::body::Body::from("{\"hello\":\"world\"}") }))
+///   .layer(CodecLayer::new());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CodecLayer {
+	_priv: (),
+}
+
+impl CodecLayer {
+	/// Creates a new [`CodecLayer`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+impl<S> Layer<S> for CodecLayer {
+	type Service = CodecService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		CodecService { inner }
+	}
+}
+
+/// The service created by [`CodecLayer`].
+#[derive(Clone, Debug)]
+pub struct CodecService<S> {
+	inner: S,
+}
+
+impl<S> Service<Request> for CodecService<S>
+where
+	S: Service<Request, Response = Response>,
+	S::Future: Send + 'static,
+{
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+	type Response = Response;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, req: Request) -> Self::Future {
+		let content_type =
+			Accept::from_request_head(req.uri(), req.headers(), req.extensions()).content_type();
+		let future = self.inner.call(req);
+
+		Box::pin(async move {
+			let mut response = future.await?;
+
+			let has_body = !matches!(
+				response.status(),
+				StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED
+			) && response.body().size_hint().exact() != Some(0);
+
+			if has_body && !response.headers().contains_key(header::CONTENT_TYPE) {
+				response
+					.headers_mut()
+					.insert(header::CONTENT_TYPE, content_type.into_header());
+			}
+
+			Ok(response)
+		})
+	}
+}
+
+/// A [`Layer`] that rejects requests whose `Accept` header does not match
+/// any enabled format with `406 Not Acceptable`, before the handler runs.
+///
+/// Without it, such requests are still handled and the response is sent in
+/// the default [`ContentType`]. Requests without an `Accept` header, with a
+/// wildcard such as `*/*`, or with a format chosen by a
+/// [`FormatQuery`](crate::FormatQuery) are always let through.
+///
+/// The rejection is encoded in the default [`ContentType`], since the client
+/// did not accept any of the others.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::layer::StrictNegotiationLayer;
+/// # use axum::{routing::get, Router};
+/// #
+/// # fn main() {
+/// let router: Router = Router::new()
+///   .route("/", get(|| async { "hello" }))
+///   .layer(StrictNegotiationLayer::new());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StrictNegotiationLayer {
+	_priv: (),
+}
+
+impl StrictNegotiationLayer {
+	/// Creates a new [`StrictNegotiationLayer`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+impl<S> Layer<S> for StrictNegotiationLayer {
+	type Service = StrictNegotiationService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		StrictNegotiationService { inner }
+	}
+}
+
+/// The service created by [`StrictNegotiationLayer`].
+#[derive(Clone, Debug)]
+pub struct StrictNegotiationService<S> {
+	inner: S,
+}
+
+impl<S> Service<Request> for StrictNegotiationService<S>
+where
+	S: Service<Request, Response = Response>,
+	S::Error: Send + 'static,
+	S::Future: Send + 'static,
+{
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+	type Response = Response;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, req: Request) -> Self::Future {
+		if !Accept::is_satisfiable(req.uri(), req.headers(), req.extensions()) {
+			return Box::pin(ready(Ok(
+				CodecRejection::NotAcceptable.into_codec_response(ContentType::default()),
+			)));
+		}
+
+		Box::pin(self.inner.call(req))
+	}
+}
+
+/// A [`Layer`] that changes the status code of responses rendered from a
+/// [`CodecRejection`], using the given [`RejectionStatus`].
+///
+/// The body is left as it is, so only the status code changes. Responses that
+/// were not rendered from a rejection are untouched.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{layer::RejectionStatusLayer, CodecRejection};
+/// # use axum::{http::StatusCode, routing::post, Router};
+/// #
+/// # fn main() {
+/// // Syntax errors stay `400 Bad Request`, but JSON that does not match the
+/// // expected type becomes `422 Unprocessable Entity`.
+/// let layer = RejectionStatusLayer::new(|rejection: &CodecRejection| match rejection {
+///   CodecRejection::Json(err) if err.is_data() => StatusCode::UNPROCESSABLE_ENTITY,
+///   rejection => rejection.status_code(),
+/// });
+///
+/// let router: Router = Router::new()
+///   .route("/", post(|| async { "hello" }))
+///   .layer(layer);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RejectionStatusLayer<R> {
+	status: R,
+}
+
+impl<R: RejectionStatus> RejectionStatusLayer<R> {
+	/// Creates a new [`RejectionStatusLayer`] with the given mapping.
+	#[must_use]
+	pub fn new(status: R) -> Self {
+		Self { status }
+	}
+}
+
+impl<S, R: Clone> Layer<S> for RejectionStatusLayer<R> {
+	type Service = RejectionStatusService<S, R>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		RejectionStatusService {
+			inner,
+			status: self.status.clone(),
+		}
+	}
+}
+
+/// The service created by [`RejectionStatusLayer`].
+#[derive(Clone, Debug)]
+pub struct RejectionStatusService<S, R> {
+	inner: S,
+	status: R,
+}
+
+impl<S, R> Service<Request> for RejectionStatusService<S, R>
+where
+	S: Service<Request, Response = Response>,
+	S::Future: Send + 'static,
+	R: RejectionStatus,
+{
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+	type Response = Response;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, req: Request) -> Self::Future {
+		let status = self.status.clone();
+		let future = self.inner.call(req);
+
+		Box::pin(async move {
+			let mut response = future.await?;
+
+			if let Some(rejected) = response.extensions().get::<Rejected>() {
+				let status = status.status_code(&rejected.rejection);
+
+				#[cfg(feature = "tracing")]
+				rejected.set_status(status);
+				*response.status_mut() = status;
+			}
+
+			Ok(response)
+		})
+	}
+}
+
+/// The identifier of a request, set by [`RequestIdLayer`].
+///
+/// Available to handlers as an extension, with
+/// `axum::Extension<RequestId>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(HeaderValue);
+
+impl RequestId {
+	/// Returns the id as a string, if it is valid UTF-8.
+	#[must_use]
+	pub fn as_str(&self) -> Option<&str> {
+		self.0.to_str().ok()
+	}
+
+	/// Returns the id as a header value.
+	#[must_use]
+	pub fn as_header(&self) -> &HeaderValue {
+		&self.0
+	}
+
+	/// Generates a new random id, formatted as 32 lowercase hex digits.
+	///
+	/// The ids are unique within a process and unlikely to collide across
+	/// processes, but they are not cryptographically secure.
+	fn generate() -> Self {
+		static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+		let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+		let high = RandomState::new().hash_one(count);
+		let low = RandomState::new().hash_one((count, std::time::SystemTime::now()));
+
+		Self(
+			HeaderValue::from_str(&format!("{high:016x}{low:016x}"))
+				.expect("hex digits are a valid header value"),
+		)
+	}
+}
+
+/// A [`Layer`] that gives every request an id and echoes it in the response,
+/// so a response (including [`Codec`](crate::Codec) rejections) can be matched
+/// with the logs of the request that produced it.
+///
+/// The id is taken from the `X-Request-Id` request header if the client or a
+/// proxy set one, and generated otherwise. It is added to the request
+/// extensions as a [`RequestId`] and set on the response, unless the handler
+/// already set the header itself.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::layer::{RequestId, RequestIdLayer};
+/// # use axum::{routing::get, Extension, Router};
+/// #
+/// async fn whoami(Extension(id): Extension<RequestId>) -> String {
+///   format!("request {}", id.as_str().unwrap_or("?"))
+/// }
+///
+/// # fn main() {
+/// let router: Router = Router::new()
+///   .route("/whoami", get(whoami))
+///   .layer(RequestIdLayer::new());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RequestIdLayer {
+	header: HeaderName,
+}
+
+impl RequestIdLayer {
+	/// Creates a new [`RequestIdLayer`] that uses the `X-Request-Id` header.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Uses a different header for the id, such as `X-Correlation-Id`.
+	#[must_use]
+	pub fn with_header(mut self, header: HeaderName) -> Self {
+		self.header = header;
+		self
+	}
+}
+
+impl Default for RequestIdLayer {
+	fn default() -> Self {
+		Self {
+			header: HeaderName::from_static("x-request-id"),
+		}
+	}
+}
+
+impl<S> Layer<S> for RequestIdLayer {
+	type Service = RequestIdService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		RequestIdService {
+			inner,
+			header: self.header.clone(),
+		}
+	}
+}
+
+/// The service created by [`RequestIdLayer`].
+#[derive(Clone, Debug)]
+pub struct RequestIdService<S> {
+	inner: S,
+	header: HeaderName,
+}
+
+impl<S> Service<Request> for RequestIdService<S>
+where
+	S: Service<Request, Response = Response>,
+	S::Future: Send + 'static,
+{
+	type Error = S::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+	type Response = Response;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, mut req: Request) -> Self::Future {
+		let id = match req.headers().get(&self.header) {
+			Some(value) if !value.is_empty() => RequestId(value.clone()),
+			_ => RequestId::generate(),
+		};
+
+		req.extensions_mut().insert(id.clone());
+
+		let header = self.header.clone();
+		let future = self.inner.call(req);
+
+		Box::pin(async move {
+			let mut response = future.await?;
+
+			response.headers_mut().entry(header).or_insert(id.0);
+
+			Ok(response)
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::convert::Infallible;
+
+	use axum::{
+		body::{to_bytes, Body},
+		http::{header, Request, StatusCode},
+		response::{IntoResponse, Response},
+	};
+	use tower::{service_fn, ServiceExt};
+
+	use super::{
+		CodecLayer, RejectionStatusLayer, RequestId, RequestIdLayer, StrictNegotiationLayer,
+	};
+	use crate::{CodecRejection, ContentType, IntoCodecResponse};
+
+	async fn call(response: fn() -> Response) -> Response {
+		let service = tower_layer::Layer::layer(
+			&CodecLayer::new(),
+			service_fn(move |_: Request<Body>| async move { Ok::<_, Infallible>(response()) }),
+		);
+
+		service
+			.oneshot(
+				Request::builder()
+					.header(header::ACCEPT, "application/cbor")
+					.body(Body::empty())
+					.unwrap(),
+			)
+			.await
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_sets_missing_content_type() {
+		let response = call(|| Response::new(Body::from(vec![0xa0]))).await;
+
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::Cbor.into_header()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_keeps_existing_content_type() {
+		let response = call(|| "plain text".into_response()).await;
+
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			"text/plain; charset=utf-8"
+		);
+
+		let response = call(|| Response::new(Body::empty())).await;
+
+		assert!(!response.headers().contains_key(header::CONTENT_TYPE));
+	}
+
+	async fn call_with_id(id: Option<&'static str>) -> Response {
+		let service = tower_layer::Layer::layer(
+			&RequestIdLayer::new(),
+			service_fn(|req: Request<Body>| async move {
+				let id = req.extensions().get::<RequestId>().unwrap().clone();
+
+				Ok::<_, Infallible>(
+					CodecRejection::ConflictingContentType
+						.into_codec_response(ContentType::Json)
+						.map(|_| Body::from(id.as_str().unwrap().to_owned())),
+				)
+			}),
+		);
+
+		let mut request = Request::builder();
+
+		if let Some(id) = id {
+			request = request.header("x-request-id", id);
+		}
+
+		service
+			.oneshot(request.body(Body::empty()).unwrap())
+			.await
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_request_id() {
+		let response = call_with_id(Some("abc")).await;
+
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+		assert_eq!(response.headers()["x-request-id"], "abc");
+		assert_eq!(
+			to_bytes(response.into_body(), usize::MAX).await.unwrap(),
+			"abc"
+		);
+
+		let first = call_with_id(None).await;
+		let second = call_with_id(None).await;
+
+		let id = first.headers()["x-request-id"].to_str().unwrap().to_owned();
+
+		assert_eq!(id.len(), 32);
+		assert_ne!(
+			first.headers()["x-request-id"],
+			second.headers()["x-request-id"]
+		);
+		assert_eq!(to_bytes(first.into_body(), usize::MAX).await.unwrap(), id);
+	}
+
+	#[tokio::test]
+	async fn test_strict_negotiation() {
+		let call = |accept: Option<&'static str>| async move {
+			let service = tower_layer::Layer::layer(
+				&StrictNegotiationLayer::new(),
+				service_fn(|_: Request<Body>| async { Ok::<_, Infallible>(Response::new(Body::empty())) }),
+			);
+
+			let mut request = Request::builder();
+
+			if let Some(accept) = accept {
+				request = request.header(header::ACCEPT, accept);
+			}
+
+			service
+				.oneshot(request.body(Body::empty()).unwrap())
+				.await
+				.unwrap()
+		};
+
+		for accept in [
+			None,
+			Some("application/cbor"),
+			Some("application/xml, application/json;q=0.5"),
+			Some("*/*"),
+			Some("application/*"),
+		] {
+			assert_eq!(call(accept).await.status(), StatusCode::OK, "{accept:?}");
+		}
+
+		for accept in [
+			"application/xml",
+			"text/html",
+			"image/*",
+			"application/json;q=0",
+			"application/json;q=0, application/vnd.msgpack;q=0, application/*;q=0",
+		] {
+			let response = call(Some(accept)).await;
+
+			assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE, "{accept}");
+			assert_eq!(
+				response.headers()[header::CONTENT_TYPE],
+				ContentType::default().into_header()
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_strict_negotiation_exclusions() {
+		use axum::Router;
+
+		use crate::{routing::get, Codec};
+
+		#[crate::apply(encode, crate = "crate")]
+		struct Data {
+			hello: String,
+		}
+
+		let router = Router::new()
+			.route(
+				"/",
+				get(|| async {
+					Codec(Data {
+						hello: "world".into(),
+					})
+				})
+				.into(),
+			)
+			.layer(StrictNegotiationLayer::new());
+
+		let call = |accept: &'static str| {
+			router.clone().oneshot(
+				Request::builder()
+					.header(header::ACCEPT, accept)
+					.body(Body::empty())
+					.unwrap(),
+			)
+		};
+
+		for (accept, content_type) in [
+			("application/json;q=0, */*", ContentType::MsgPack),
+			("*/*, application/json;q=0, application/vnd.msgpack;q=0", ContentType::Bincode),
+			("application/json;q=0, application/cbor;q=0.5", ContentType::Cbor),
+		] {
+			let response = call(accept).await.unwrap();
+
+			assert_eq!(response.status(), StatusCode::OK, "{accept}");
+			assert_eq!(
+				response.headers()[header::CONTENT_TYPE],
+				content_type.into_header(),
+				"{accept}"
+			);
+		}
+
+		assert_eq!(
+			call("application/json;q=0, text/html").await.unwrap().status(),
+			StatusCode::NOT_ACCEPTABLE
+		);
+	}
+
+	#[tokio::test]
+	async fn test_rejection_status() {
+		use axum::{routing::post, Router};
+
+		use crate::Codec;
+
+		#[crate::apply(decode, crate = "crate")]
+		struct Data {
+			#[allow(dead_code)]
+			hello: String,
+		}
+
+		let router = Router::new()
+			.route(
+				"/",
+				post(|Codec(_): Codec<Data>| async { StatusCode::NO_CONTENT }),
+			)
+			.layer(RejectionStatusLayer::new(
+				|rejection: &CodecRejection| match rejection {
+					CodecRejection::Json(err) if err.is_data() => StatusCode::UNPROCESSABLE_ENTITY,
+					rejection => rejection.status_code(),
+				},
+			));
+
+		let call = |body: &'static str| {
+			router.clone().oneshot(
+				Request::builder()
+					.method("POST")
+					.header(header::CONTENT_TYPE, "application/json")
+					.body(Body::from(body))
+					.unwrap(),
+			)
+		};
+
+		assert_eq!(
+			call(r#"{"hello":1}"#).await.unwrap().status(),
+			StatusCode::UNPROCESSABLE_ENTITY
+		);
+		assert_eq!(call("{").await.unwrap().status(), StatusCode::BAD_REQUEST);
+		assert_eq!(
+			call(r#"{"hello":"world"}"#).await.unwrap().status(),
+			StatusCode::NO_CONTENT
+		);
+	}
+
+	#[cfg(feature = "tracing")]
+	#[tokio::test]
+	async fn test_rejection_status_trace() {
+		use std::sync::{Arc, Mutex};
+
+		use axum::{routing::post, Router};
+		use tracing::{
+			field::{Field, Visit},
+			span, Event, Metadata, Subscriber,
+		};
+
+		use crate::Codec;
+
+		#[crate::apply(decode, crate = "crate")]
+		struct Data {
+			#[allow(dead_code)]
+			hello: String,
+		}
+
+		/// Records the `status` field of every event.
+		#[derive(Clone, Default)]
+		struct Statuses(Arc<Mutex<Vec<u64>>>);
+
+		impl Visit for Statuses {
+			fn record_u64(&mut self, field: &Field, value: u64) {
+				if field.name() == "status" {
+					self.0.lock().unwrap().push(value);
+				}
+			}
+
+			fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+		}
+
+		impl Subscriber for Statuses {
+			fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+				true
+			}
+
+			fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+				span::Id::from_u64(1)
+			}
+
+			fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+			fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+			fn event(&self, event: &Event<'_>) {
+				event.record(&mut self.clone());
+			}
+
+			fn enter(&self, _span: &span::Id) {}
+
+			fn exit(&self, _span: &span::Id) {}
+		}
+
+		let statuses = Statuses::default();
+		let _guard = tracing::subscriber::set_default(statuses.clone());
+
+		let router = Router::new()
+			.route(
+				"/",
+				post(|Codec(_): Codec<Data>| async { StatusCode::NO_CONTENT }),
+			)
+			.layer(RejectionStatusLayer::new(
+				|rejection: &CodecRejection| match rejection {
+					CodecRejection::Json(err) if err.is_data() => StatusCode::UNPROCESSABLE_ENTITY,
+					rejection => rejection.status_code(),
+				},
+			));
+
+		for body in [r#"{"hello":1}"#, "{"] {
+			let response = router
+				.clone()
+				.oneshot(
+					Request::builder()
+						.method("POST")
+						.header(header::CONTENT_TYPE, "application/json")
+						.body(Body::from(body))
+						.unwrap(),
+				)
+				.await
+				.unwrap();
+
+			drop(response);
+		}
+
+		assert_eq!(*statuses.0.lock().unwrap(), [422, 400]);
+	}
+}
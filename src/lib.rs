@@ -8,29 +8,74 @@
 		feature = "bincode",
 		feature = "bitcode",
 		feature = "cbor",
+		feature = "bson",
 		feature = "yaml",
-		feature = "toml"
+		feature = "toml",
+		feature = "ron"
 	)),
 	allow(unreachable_code, unused_variables)
 )]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "serde")]
+mod bignum;
+#[cfg(feature = "serde")]
+mod bytes;
 mod content;
+#[cfg(feature = "custom")]
+pub mod custom;
 mod decode;
-mod encode;
+pub mod dispatch;
+pub mod encode;
+#[cfg(feature = "pretty-errors")]
+pub mod error_handling;
 pub mod extract;
+pub mod format;
+#[cfg(feature = "framed")]
+pub mod framed;
 pub mod handler;
+#[cfg(feature = "json")]
+mod json;
+pub mod layer;
+#[cfg(feature = "metrics")]
+mod metrics;
 pub mod rejection;
 pub mod response;
 pub mod routing;
+mod shape;
+#[cfg(feature = "spool")]
+pub mod spool;
+#[cfg(feature = "sse")]
+pub mod sse;
+#[cfg(feature = "json")]
+mod stream;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "ws")]
+pub mod ws;
+#[cfg(feature = "yaml")]
+mod yaml;
 
-pub use content::{Accept, ContentType};
+#[cfg(feature = "encoding")]
+pub use content::AcceptCharset;
+pub use content::{
+	enabled_content_types, enabled_mime_types, negotiate, Accept, ContentType, ContentTypeNegotiator,
+	FormatCookie, FormatQuery, OctetStream,
+};
 pub use decode::CodecDecode;
-pub use encode::CodecEncode;
-pub use extract::Codec;
+#[cfg(feature = "serde")]
+pub use decode::DynamicValue;
+pub use encode::{convert_bytes, CodecEncode, SizeHint};
+pub use extract::{
+	Codec, CodecOrDefault, CodecWithRaw, Negotiated, RestrictedCodec, SymmetricCodec, TimedCodec,
+	ValidateWithState, ValidatedCodec,
+};
 pub use handler::CodecHandler;
-pub use rejection::CodecRejection;
+pub use rejection::{CodecRejection, CodecResultExt};
 pub use response::IntoCodecResponse;
+pub use shape::Shape;
+#[cfg(feature = "json")]
+pub use stream::CodecStream;
 
 #[doc(hidden)]
 pub mod __private {
@@ -44,12 +89,113 @@ pub mod __private {
 	pub use serde;
 	#[cfg(feature = "validator")]
 	pub use validator;
+
+	/// Used by `#[apply]` to validate the fields of tuple structs and enums,
+	/// which `#[derive(validator::Validate)]` does not support.
+	///
+	/// `(&Field(value)).validate_field()` resolves to [`ValidateField`] if the
+	/// value implements `Validate`, and to [`SkipField`] (which always
+	/// succeeds) otherwise.
+	#[cfg(feature = "validator")]
+	pub mod validate {
+		use validator::{Validate, ValidationErrors};
+
+		pub struct Field<'a, T: ?Sized>(pub &'a T);
+
+		pub trait ValidateField {
+			fn validate_field(&self) -> Result<(), ValidationErrors>;
+		}
+
+		impl<T: Validate + ?Sized> ValidateField for Field<'_, T> {
+			fn validate_field(&self) -> Result<(), ValidationErrors> {
+				self.0.validate()
+			}
+		}
+
+		pub trait SkipField {
+			fn validate_field(&self) -> Result<(), ValidationErrors> {
+				Ok(())
+			}
+		}
+
+		impl<T: ?Sized> SkipField for &Field<'_, T> {}
+	}
+
+	/// Used by `#[codec(json_bignum)]` fields as `#[serde(with = "...")]`.
+	#[cfg(feature = "serde")]
+	pub mod bignum {
+		pub use crate::bignum::{deserialize, serialize};
+	}
+
+	/// Used by `#[codec(bytes)]` fields as `#[serde(with = "...")]`.
+	#[cfg(feature = "serde")]
+	pub mod bytes {
+		pub use crate::bytes::{deserialize, serialize};
+	}
+
+	/// Implemented for every type that Bincode can encode. Used by
+	/// `#[apply(encode)]` to point at the offending field.
+	#[cfg(feature = "bincode")]
+	#[diagnostic::on_unimplemented(
+		message = "`{Self}` cannot be encoded with Bincode",
+		label = "this field type does not implement `bincode::Encode`",
+		note = "disable the `bincode` feature, or wrap the field in a type that implements \
+		        `bincode::Encode`"
+	)]
+	pub trait BincodeEncode: bincode::Encode {}
+
+	#[cfg(feature = "bincode")]
+	impl<T: bincode::Encode + ?Sized> BincodeEncode for T {}
+
+	/// Implemented for every type that Bincode can decode. Used by
+	/// `#[apply(decode)]` to point at the offending field.
+	#[cfg(feature = "bincode")]
+	#[diagnostic::on_unimplemented(
+		message = "`{Self}` cannot be decoded with Bincode",
+		label = "this field type does not implement `bincode::Decode`",
+		note = "disable the `bincode` feature, or wrap the field in a type that implements \
+		        `bincode::Decode`"
+	)]
+	pub trait BincodeDecode: bincode::Decode {}
+
+	#[cfg(feature = "bincode")]
+	impl<T: bincode::Decode> BincodeDecode for T {}
+
+	/// Implemented for every type that Bitcode can encode. Used by
+	/// `#[apply(encode)]` to point at the offending field.
+	#[cfg(feature = "bitcode")]
+	#[diagnostic::on_unimplemented(
+		message = "`{Self}` cannot be encoded with Bitcode",
+		label = "this field type does not implement `bitcode::Encode`",
+		note = "disable the `bitcode` feature, or wrap the field in a type that implements \
+		        `bitcode::Encode`"
+	)]
+	pub trait BitcodeEncode: bitcode::Encode {}
+
+	#[cfg(feature = "bitcode")]
+	impl<T: bitcode::Encode + ?Sized> BitcodeEncode for T {}
+
+	/// Implemented for every type that Bitcode can decode. Used by
+	/// `#[apply(decode)]` to point at the offending field.
+	#[cfg(feature = "bitcode")]
+	#[diagnostic::on_unimplemented(
+		message = "`{Self}` cannot be decoded with Bitcode",
+		label = "this field type does not implement `bitcode::Decode`",
+		note = "disable the `bitcode` feature, or wrap the field in a type that implements \
+		        `bitcode::Decode`"
+	)]
+	pub trait BitcodeDecode: bitcode::DecodeOwned {}
+
+	#[cfg(feature = "bitcode")]
+	impl<T: bitcode::DecodeOwned> BitcodeDecode for T {}
 }
 
 pub use axum_codec_macros as macros;
 #[cfg(feature = "macros")]
 pub use macros::apply;
 #[cfg(feature = "macros")]
+pub use macros::convert;
+#[cfg(feature = "macros")]
 pub use macros::debug_handler;
 #[cfg(feature = "macros")]
 pub use macros::debug_middleware;
@@ -86,6 +232,33 @@ mod test {
 		assert_eq!(decoded, data);
 	}
 
+	#[test]
+	fn test_msgpack_string_keys() {
+		use std::collections::BTreeMap;
+
+		#[apply(encode)]
+		struct Scores {
+			by_id: BTreeMap<u32, Vec<BTreeMap<bool, u8>>>,
+		}
+
+		let scores = Scores {
+			by_id: BTreeMap::from([(7, vec![BTreeMap::from([(true, 1)])])]),
+		};
+		let encoded = Codec(scores).to_msgpack_string_keys().unwrap();
+		let value = rmpv::decode::read_value(&mut encoded.as_slice()).unwrap();
+
+		assert_eq!(
+			value,
+			rmpv::Value::Map(vec![(
+				"by_id".into(),
+				rmpv::Value::Map(vec![(
+					"7".into(),
+					rmpv::Value::Array(vec![rmpv::Value::Map(vec![("true".into(), 1.into())])])
+				)])
+			)])
+		);
+	}
+
 	#[test]
 	fn test_json_roundtrip() {
 		let data = data();
@@ -106,6 +279,220 @@ mod test {
 		assert_eq!(decoded, data);
 	}
 
+	#[test]
+	fn test_bson_roundtrip() {
+		let data = data();
+		let encoded = Codec(&data).to_bson().unwrap();
+
+		let Codec(decoded) = Codec::<Data>::from_bson(&encoded).unwrap();
+
+		assert_eq!(decoded, data);
+	}
+
+	#[test]
+	fn test_cbor_rejects_oversized_lengths() {
+		// An array declaring 2^32 elements, followed by a single element.
+		let encoded = [0x9b, 0, 0, 0, 1, 0, 0, 0, 0, 0x01];
+
+		assert!(matches!(
+			Codec::<Vec<u8>>::from_cbor(&encoded),
+			Err(ciborium::de::Error::Semantic(Some(0), _))
+		));
+
+		// A byte string declaring 2^32 bytes.
+		let encoded = [0x5b, 0, 0, 0, 1, 0, 0, 0, 0, 0x01];
+
+		assert!(Codec::<Vec<u8>>::from_cbor(&encoded).is_err());
+	}
+
+	#[test]
+	fn test_from_bytes_with_limit() {
+		let encoded = Codec(data()).to_json().unwrap();
+
+		let Codec(decoded) =
+			Codec::<Data>::from_bytes_with_limit::<1024>(&encoded, ContentType::Json).unwrap();
+		assert_eq!(decoded, data());
+
+		let Err(rejection) = Codec::<Data>::from_bytes_with_limit::<16>(&encoded, ContentType::Json)
+		else {
+			panic!("expected the payload to be rejected");
+		};
+
+		assert_eq!(
+			rejection.status_code(),
+			axum::http::StatusCode::PAYLOAD_TOO_LARGE
+		);
+	}
+
+	#[test]
+	fn test_from_bytes_with_limit_declared_lengths() {
+		#[crate::apply(decode, crate = "crate")]
+		struct Batch {
+			#[allow(dead_code)]
+			items: Vec<u64>,
+		}
+
+		let encoded = Codec(data()).to_bincode().unwrap();
+
+		let Codec(decoded) =
+			Codec::<Data>::from_bytes_with_limit::<1024>(&encoded, ContentType::Bincode).unwrap();
+		assert_eq!(decoded, data());
+
+		// `items` declaring 2^32 elements in 5 bytes, which would otherwise
+		// allocate 32 GiB before reading any of them.
+		let encoded = [0xfc, 0, 0, 0, 1];
+
+		let Err(rejection) =
+			Codec::<Batch>::from_bytes_with_limit::<1024>(&encoded, ContentType::Bincode)
+		else {
+			panic!("expected the payload to be rejected");
+		};
+
+		assert_eq!(
+			rejection.status_code(),
+			axum::http::StatusCode::PAYLOAD_TOO_LARGE
+		);
+
+		// The same in CBOR and MessagePack.
+		for (content_type, encoded) in [
+			(ContentType::Cbor, &b"\xa1\x65items\x9a\xff\xff\xff\xff"[..]),
+			(ContentType::MsgPack, &b"\x81\xa5items\xdd\xff\xff\xff\xff"[..]),
+		] {
+			assert!(
+				Codec::<Batch>::from_bytes_with_limit::<1024>(encoded, content_type).is_err(),
+				"{content_type}"
+			);
+		}
+	}
+
+	#[cfg(feature = "arbitrary-precision")]
+	#[test]
+	fn test_json_arbitrary_precision() {
+		use std::collections::BTreeMap;
+
+		let json = br#"{"amount":12345678901234567890.123456789}"#;
+
+		let Codec(payment) = Codec::<BTreeMap<String, serde_json::Number>>::from_json(json).unwrap();
+		assert_eq!(
+			payment["amount"].to_string(),
+			"12345678901234567890.123456789"
+		);
+
+		assert_eq!(Codec(payment).to_json().unwrap(), json);
+	}
+
+	#[test]
+	fn test_from_dynamic_values() {
+		let json = serde_json::to_value(data()).unwrap();
+		let Codec(decoded) = Codec::<Data>::from_value(json).unwrap();
+		assert_eq!(decoded, data());
+
+		let msgpack = rmpv::ext::to_value(data()).unwrap();
+		let Codec(decoded) = Codec::<Data>::from_value(msgpack).unwrap();
+		assert_eq!(decoded, data());
+
+		let cbor = ciborium::Value::serialized(&data()).unwrap();
+		let Codec(decoded) = Codec::<Data>::from_value(cbor).unwrap();
+		assert_eq!(decoded, data());
+	}
+
+	#[test]
+	fn test_from_bytes_seed() {
+		use core::marker::PhantomData;
+
+		for content_type in [
+			ContentType::Json,
+			ContentType::MsgPack,
+			ContentType::Bson,
+			ContentType::Yaml,
+			ContentType::Toml,
+			ContentType::Ron,
+		] {
+			let encoded = Codec(data()).to_bytes(content_type).unwrap();
+			let Codec(decoded) =
+				Codec::from_bytes_seed(PhantomData::<Data>, &encoded, content_type).unwrap();
+
+			assert_eq!(decoded, data(), "{content_type}");
+		}
+
+		let encoded = Codec(data()).to_cbor().unwrap();
+		let Err(rejection) = Codec::from_bytes_seed(PhantomData::<Data>, &encoded, ContentType::Cbor)
+		else {
+			panic!("expected CBOR to be unsupported");
+		};
+
+		assert!(matches!(
+			rejection,
+			CodecRejection::UnsupportedSeed(ContentType::Cbor)
+		));
+		assert_eq!(
+			rejection.status_code(),
+			axum::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+		);
+	}
+
+	#[test]
+	fn test_decode_into_shared_pointers() {
+		for &content_type in ContentType::all() {
+			let bytes = Codec(data()).to_bytes(content_type).unwrap();
+
+			let Codec(shared) = Codec::<Data>::from_bytes_arc(&bytes, content_type).unwrap();
+			assert_eq!(*shared, data(), "{content_type}");
+
+			#[cfg(not(feature = "validator"))]
+			{
+				let Codec(shared) =
+					Codec::<std::sync::Arc<Data>>::from_bytes(&bytes, content_type).unwrap();
+				assert_eq!(*shared, data(), "{content_type}");
+
+				let Codec(boxed) = Codec::<Box<Data>>::from_bytes(&bytes, content_type).unwrap();
+				assert_eq!(*boxed, data(), "{content_type}");
+			}
+		}
+	}
+
+	#[test]
+	fn test_from_base64_bytes() {
+		use base64::Engine;
+
+		for &content_type in ContentType::all() {
+			let encoded = base64::engine::general_purpose::STANDARD
+				.encode(Codec(data()).to_bytes(content_type).unwrap());
+			let wrapped = encoded
+				.as_bytes()
+				.chunks(76)
+				.collect::<Vec<_>>()
+				.join(&b"\r\n"[..]);
+
+			let Codec(decoded) = Codec::<Data>::from_base64_bytes(&wrapped, content_type)
+				.unwrap_or_else(|err| panic!("{content_type}: {err}"));
+
+			assert_eq!(decoded, data(), "{content_type}");
+		}
+
+		assert!(matches!(
+			Codec::<Data>::from_base64_bytes(b"not base64!", ContentType::Json),
+			Err(CodecRejection::Base64(..))
+		));
+	}
+
+	#[test]
+	fn test_from_bytes_ignores_utf8_bom() {
+		for content_type in [
+			ContentType::Json,
+			ContentType::Yaml,
+			ContentType::Toml,
+			ContentType::Ron,
+		] {
+			let mut encoded = b"\xef\xbb\xbf".to_vec();
+			encoded.extend(Codec(data()).to_bytes(content_type).unwrap());
+
+			let Codec(decoded) = Codec::<Data>::from_bytes(&encoded, content_type).unwrap();
+
+			assert_eq!(decoded, data(), "{content_type}");
+		}
+	}
+
 	#[test]
 	fn test_yaml_roundtrip() {
 		let data = data();
@@ -126,6 +513,51 @@ mod test {
 		assert_eq!(decoded, data);
 	}
 
+	#[test]
+	fn test_ron_roundtrip() {
+		let data = data();
+		let encoded = Codec(&data).to_ron().unwrap();
+
+		let Codec(decoded) = Codec::<Data>::from_ron(&encoded).unwrap();
+
+		assert_eq!(decoded, data);
+	}
+
+	#[test]
+	fn test_yaml_and_toml_styles() {
+		use encode::{TomlStyle, YamlStyle};
+
+		#[apply(encode)]
+		struct Nested {
+			name: String,
+			data: Data,
+		}
+
+		let nested = Codec(Nested {
+			name: "nested".into(),
+			data: data(),
+		});
+
+		let yaml = nested.to_yaml_with(YamlStyle::Flow).unwrap();
+		assert_eq!(yaml, format!("{}\n", serde_json::to_string(&nested.0).unwrap()));
+		assert_eq!(yaml.lines().count(), 1);
+		assert_eq!(
+			crate::yaml::from_str::<crate::yaml::Value>(&yaml).unwrap(),
+			crate::yaml::from_str::<crate::yaml::Value>(&nested.to_yaml().unwrap()).unwrap()
+		);
+
+		let toml = nested.to_toml_with(TomlStyle::Inline).unwrap();
+		assert_eq!(
+			toml,
+			"data = { array = [1, 2, 3], boolean = true, integer = 42, string = \"hello\" }\nname = \
+			 \"nested\"\n"
+		);
+		assert_eq!(
+			toml::from_str::<toml::Table>(&toml).unwrap(),
+			toml::from_str::<toml::Table>(&nested.to_toml().unwrap()).unwrap()
+		);
+	}
+
 	#[test]
 	fn test_bincode_roundtrip() {
 		let data = data();
@@ -136,6 +568,548 @@ mod test {
 		assert_eq!(decoded, data);
 	}
 
+	#[test]
+	fn test_dynamic_serde_roundtrip() {
+		use std::collections::BTreeMap;
+
+		let mut map = BTreeMap::new();
+		map.insert("string".to_string(), serde_json::json!("hello"));
+		map.insert("array".to_string(), serde_json::json!([1, 2, 3]));
+		map.insert("object".to_string(), serde_json::json!({ "boolean": true }));
+
+		for content_type in [
+			ContentType::Json,
+			ContentType::MsgPack,
+			ContentType::Cbor,
+			ContentType::Yaml,
+			ContentType::Toml,
+		] {
+			let encoded = Codec(&map).to_serde_bytes(content_type).unwrap();
+
+			let Codec(decoded) = match content_type {
+				ContentType::Json => {
+					Codec::<BTreeMap<String, serde_json::Value>>::from_json(&encoded).unwrap()
+				}
+				ContentType::MsgPack => Codec::from_msgpack(&encoded).unwrap(),
+				ContentType::Cbor => Codec::from_cbor(&encoded).unwrap(),
+				ContentType::Yaml => Codec::from_yaml(core::str::from_utf8(&encoded).unwrap()).unwrap(),
+				_ => Codec::from_toml(core::str::from_utf8(&encoded).unwrap()).unwrap(),
+			};
+
+			assert_eq!(decoded, map, "{content_type}");
+		}
+
+		assert!(matches!(
+			Codec(&map).to_serde_bytes(ContentType::Bincode),
+			Err(encode::Error::Unsupported(ContentType::Bincode))
+		));
+	}
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Newtype(Data);
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Primitive(u32);
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Tuple(String, i32, bool);
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Unit;
+
+	fn roundtrip<T>(value: T, content_type: ContentType)
+	where
+		T: CodecEncode + CodecDecode + PartialEq + core::fmt::Debug,
+	{
+		let value = Codec(value);
+		let encoded = value.to_bytes(content_type).unwrap();
+		let Codec(decoded) = Codec::<T>::from_bytes(&encoded, content_type).unwrap();
+
+		assert_eq!(decoded, value.0, "{content_type}");
+	}
+
+	#[test]
+	fn test_struct_shapes_roundtrip() {
+		for &content_type in ContentType::all() {
+			roundtrip(Newtype(data()), content_type);
+
+			// TOML and BSON documents must be tables, so only newtypes around
+			// structs can be represented.
+			if matches!(content_type, ContentType::Toml | ContentType::Bson) {
+				continue;
+			}
+
+			roundtrip(Primitive(42), content_type);
+			roundtrip(Tuple("hello".into(), -1, true), content_type);
+			roundtrip(Unit, content_type);
+		}
+	}
+
+	#[test]
+	fn test_encode_into_appends() {
+		let data = Codec(data());
+
+		for &content_type in ContentType::all() {
+			let mut buf = Vec::with_capacity(256);
+			buf.extend_from_slice(b"prefix");
+
+			data.encode_into(content_type, &mut buf).unwrap();
+
+			assert_eq!(buf[..6], *b"prefix", "{content_type}");
+			assert_eq!(
+				buf[6..],
+				data.to_bytes(content_type).unwrap(),
+				"{content_type}"
+			);
+		}
+	}
+
+	#[apply(encode, decode, derive(Clone, Hash, PartialEq, Eq))]
+	struct CacheKey {
+		user_id: u64,
+		page: u32,
+	}
+
+	#[test]
+	fn test_apply_derives() {
+		use std::collections::HashSet;
+
+		let key = CacheKey {
+			user_id: 1,
+			page: 2,
+		};
+		let encoded = Codec(key.clone()).to_json().unwrap();
+		let Codec(decoded) = Codec::<CacheKey>::from_json(&encoded).unwrap();
+
+		let mut keys = HashSet::new();
+		keys.insert(key);
+
+		assert!(keys.contains(&decoded));
+	}
+
+	#[apply(decode, default)]
+	#[derive(Debug, Default, PartialEq)]
+	struct Defaults {
+		string: String,
+		integer: i32,
+	}
+
+	#[apply(decode)]
+	#[derive(Debug, PartialEq)]
+	struct FieldDefault {
+		string: String,
+		#[codec(default)]
+		array: Vec<i32>,
+	}
+
+	#[test]
+	fn test_apply_defaults() {
+		let Codec(decoded) = Codec::<Defaults>::from_json(br#"{"integer":5}"#).unwrap();
+		assert_eq!(decoded, Defaults {
+			string: String::new(),
+			integer: 5,
+		});
+
+		let Codec(decoded) = Codec::<FieldDefault>::from_json(br#"{"string":"a"}"#).unwrap();
+		assert_eq!(decoded, FieldDefault {
+			string: "a".into(),
+			array: Vec::new(),
+		});
+
+		assert!(Codec::<FieldDefault>::from_json(br#"{"array":[]}"#).is_err());
+	}
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Renamed {
+		#[codec(rename = "userName")]
+		user_name: String,
+	}
+
+	#[test]
+	fn test_apply_rename() {
+		let renamed = Renamed {
+			user_name: "a".into(),
+		};
+
+		assert_eq!(Codec(&renamed).to_json().unwrap(), br#"{"userName":"a"}"#);
+		assert!(Codec::<Renamed>::from_json(br#"{"user_name":"a"}"#).is_err());
+
+		for &content_type in ContentType::all() {
+			roundtrip(
+				Renamed {
+					user_name: "a".into(),
+				},
+				content_type,
+			);
+		}
+	}
+
+	#[apply(decode, encode, skip_none)]
+	#[derive(Debug, PartialEq)]
+	struct SkipNone {
+		name: String,
+		bio: Option<String>,
+		#[serde(skip_serializing_if = "Option::is_some")]
+		nickname: Option<String>,
+	}
+
+	#[apply(decode, encode, skip_none)]
+	#[derive(Debug, PartialEq)]
+	struct SkipNoneTuple(u32, Option<u32>, u32);
+
+	#[test]
+	fn test_apply_skip_none() {
+		let skipped = SkipNone {
+			name: "a".into(),
+			bio: None,
+			nickname: None,
+		};
+
+		assert_eq!(
+			Codec(&skipped).to_json().unwrap(),
+			br#"{"name":"a","nickname":null}"#
+		);
+
+		let Codec(decoded) = Codec::<SkipNone>::from_json(br#"{"name":"a"}"#).unwrap();
+		assert_eq!(decoded, skipped);
+
+		for &content_type in ContentType::all() {
+			for bio in [Some("b".into()), None] {
+				roundtrip(
+					SkipNone {
+						name: "a".into(),
+						bio,
+						nickname: None,
+					},
+					content_type,
+				);
+			}
+
+			// TOML and BSON documents must be tables, so tuple structs are skipped.
+			if !matches!(content_type, ContentType::Toml | ContentType::Bson) {
+				roundtrip(SkipNoneTuple(1, None, 3), content_type);
+			}
+		}
+	}
+
+	#[test]
+	fn test_json_non_finite_floats() {
+		#[apply(encode, decode)]
+		#[derive(Debug, PartialEq)]
+		struct Reading {
+			value: Option<f64>,
+		}
+
+		for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+			let reading = Codec(Reading { value: Some(value) });
+
+			assert_eq!(reading.to_json().unwrap(), br#"{"value":null}"#);
+			assert_eq!(
+				reading.to_response(ContentType::Json).status(),
+				axum::http::StatusCode::OK
+			);
+
+			let Codec(decoded) = Codec::<Reading>::from_json(br#"{"value":null}"#).unwrap();
+			assert_eq!(decoded, Reading { value: None });
+		}
+	}
+
+	#[test]
+	fn test_convert_bytes() {
+		#[apply(decode, encode)]
+		struct Primitive(u32);
+
+		let json = Codec(data()).to_json().unwrap();
+
+		for &content_type in ContentType::all() {
+			let converted = convert_bytes::<Data>(&json, ContentType::Json, content_type).unwrap();
+			let Codec(decoded) = Codec::<Data>::from_bytes(&converted, content_type).unwrap();
+
+			assert_eq!(decoded, data(), "{content_type}");
+		}
+
+		assert!(matches!(
+			convert_bytes::<Data>(b"{}", ContentType::Json, ContentType::MsgPack),
+			Err(encode::ConvertError::Decode(..))
+		));
+		// TOML documents must be tables.
+		assert!(matches!(
+			convert_bytes::<Primitive>(b"1", ContentType::Json, ContentType::Toml),
+			Err(encode::ConvertError::Encode(..))
+		));
+	}
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Account {
+		#[codec(json_bignum)]
+		id: u64,
+		#[codec(json_bignum)]
+		balance: i64,
+		version: u64,
+	}
+
+	#[test]
+	fn test_apply_json_bignum() {
+		let account = Account {
+			id: u64::MAX,
+			balance: i64::MIN,
+			version: 1,
+		};
+
+		assert_eq!(
+			Codec(&account).to_json().unwrap(),
+			br#"{"id":"18446744073709551615","balance":"-9223372036854775808","version":1}"#
+		);
+
+		let Codec(decoded) = Codec::<Account>::from_json(
+			br#"{"id":18446744073709551615,"balance":"-9223372036854775808","version":1}"#,
+		)
+		.unwrap();
+		assert_eq!(decoded, account);
+
+		assert!(Codec::<Account>::from_json(br#"{"id":"-1","balance":"0","version":1}"#).is_err());
+
+		for &content_type in ContentType::all() {
+			// BSON integers are signed 64-bit.
+			let id = if content_type == ContentType::Bson {
+				i64::MAX.unsigned_abs()
+			} else {
+				u64::MAX
+			};
+
+			roundtrip(
+				Account {
+					id,
+					balance: i64::MIN,
+					version: 1,
+				},
+				content_type,
+			);
+		}
+	}
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct Upload {
+		name: String,
+		#[codec(bytes)]
+		data: Vec<u8>,
+		#[codec(bytes)]
+		boxed: Box<[u8]>,
+	}
+
+	#[test]
+	fn test_apply_bytes() {
+		let upload = Upload {
+			name: "a".into(),
+			data: b"hello".to_vec(),
+			boxed: Box::new([0, 255]),
+		};
+
+		assert_eq!(
+			Codec(&upload).to_json().unwrap(),
+			br#"{"name":"a","data":"aGVsbG8=","boxed":"AP8="}"#
+		);
+
+		let Codec(decoded) =
+			Codec::<Upload>::from_json(br#"{"name":"a","data":[104,101,108,108,111],"boxed":"AP8="}"#)
+				.unwrap();
+		assert_eq!(decoded, upload);
+
+		assert!(Codec::<Upload>::from_json(br#"{"name":"a","data":"!","boxed":""}"#).is_err());
+
+		for &content_type in ContentType::all() {
+			roundtrip(
+				Upload {
+					name: "a".into(),
+					data: (0..=255).collect(),
+					boxed: Box::new([]),
+				},
+				content_type,
+			);
+		}
+	}
+
+	#[apply(decode)]
+	struct CreateUser {
+		name: String,
+		password: String,
+	}
+
+	struct ImportUser {
+		name: &'static str,
+	}
+
+	#[convert(from = CreateUser, from = ImportUser)]
+	#[apply(encode)]
+	#[derive(Debug, PartialEq)]
+	struct User {
+		#[convert(default)]
+		id: u64,
+		name: String,
+		#[convert(default)]
+		admin: bool,
+	}
+
+	#[test]
+	fn test_convert() {
+		let Codec(create) = Codec::<CreateUser>::from_json(br#"{"name":"a","password":"b"}"#).unwrap();
+		assert_eq!(create.password, "b");
+
+		assert_eq!(
+			User {
+				id: 1,
+				..User::from(create)
+			},
+			User {
+				id: 1,
+				name: "a".into(),
+				admin: false,
+			}
+		);
+
+		assert_eq!(User::from(ImportUser { name: "b" }), User {
+			id: 0,
+			name: "b".into(),
+			admin: false,
+		});
+	}
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct SchemaSkip {
+		name: String,
+		#[codec(schema_skip)]
+		shard: u16,
+	}
+
+	#[test]
+	fn test_apply_schema_skip() {
+		for &content_type in ContentType::all() {
+			roundtrip(
+				SchemaSkip {
+					name: "a".into(),
+					shard: 3,
+				},
+				content_type,
+			);
+		}
+
+		#[cfg(feature = "aide")]
+		{
+			let schema = serde_json::to_value(schemars::schema_for!(SchemaSkip)).unwrap();
+
+			assert!(schema["properties"].get("name").is_some());
+			assert!(schema["properties"].get("shard").is_none());
+		}
+	}
+
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize, bitcode::Encode, bitcode::Decode)]
+	#[cfg_attr(feature = "aide", derive(schemars::JsonSchema))]
+	struct SerdeOnly(u32);
+
+	#[apply(decode, encode)]
+	#[derive(Debug, PartialEq)]
+	struct WithSerde {
+		name: String,
+		#[bincode(with_serde)]
+		legacy: SerdeOnly,
+	}
+
+	#[test]
+	fn test_apply_bincode_with_serde() {
+		for &content_type in ContentType::all() {
+			roundtrip(
+				WithSerde {
+					name: "a".into(),
+					legacy: SerdeOnly(3),
+				},
+				content_type,
+			);
+		}
+	}
+
+	#[cfg(feature = "validator")]
+	#[test]
+	fn test_apply_validates_fields_of_other_shapes() {
+		use validator::Validate;
+
+		#[apply(decode)]
+		struct Inner {
+			#[validate(length(min = 1))]
+			name: String,
+		}
+
+		#[apply(decode)]
+		struct Wrapper(Inner, u32);
+
+		#[apply(decode)]
+		enum Choice {
+			One(Inner),
+			Two { inner: Inner, count: u32 },
+			Three,
+		}
+
+		let valid = || Inner { name: "a".into() };
+		let invalid = || Inner {
+			name: String::new(),
+		};
+
+		assert!(Wrapper(valid(), 1).validate().is_ok());
+		assert!(Wrapper(invalid(), 1)
+			.validate()
+			.unwrap_err()
+			.errors()
+			.contains_key("0"));
+
+		assert!(Choice::One(valid()).validate().is_ok());
+		assert!(Choice::One(invalid()).validate().is_err());
+		assert!(Choice::Two {
+			inner: invalid(),
+			count: 1,
+		}
+		.validate()
+		.unwrap_err()
+		.errors()
+		.contains_key("inner"));
+		assert!(Choice::Three.validate().is_ok());
+	}
+
+	#[test]
+	fn test_size_hint() {
+		use crate::SizeHint;
+
+		#[crate::apply(encode, crate = "crate", size_hint = 64 + self.items.len() * 8)]
+		struct Batch {
+			items: Vec<u64>,
+		}
+
+		let batch = Codec(Batch {
+			items: vec![1, 2, 3],
+		});
+
+		assert_eq!(batch.size_hint(ContentType::Json), Some(88));
+
+		for &content_type in ContentType::all() {
+			let bytes = batch.to_bytes_hinted(content_type).unwrap();
+
+			assert!(bytes.capacity() >= 88, "{content_type}");
+			assert_eq!(bytes, batch.to_bytes(content_type).unwrap(), "{content_type}");
+
+			let mut buf = b"prefix".to_vec();
+			batch.encode_into_hinted(content_type, &mut buf).unwrap();
+
+			assert!(buf.capacity() >= 6 + 88, "{content_type}");
+			assert_eq!(buf[6..], bytes, "{content_type}");
+		}
+	}
+
 	#[test]
 	fn test_bitcode_roundtrip() {
 		let encoded = Codec(data()).to_bitcode();
@@ -0,0 +1,125 @@
+//! Metrics recorded with the `metrics` feature.
+//!
+//! - `axum_codec_responses_total` (counter): the negotiated response
+//!   `content_type` of each request handled by a codec handler.
+//! - `axum_codec_request_body_bytes` (histogram): the size of each request body
+//!   decoded by [`Codec`](crate::Codec), labelled with its `content_type`.
+//! - `axum_codec_decodes_total` (counter): each request body decoded by
+//!   [`Codec`](crate::Codec), labelled with its `content_type` and an `outcome`
+//!   of `success` or `rejected`.
+
+use crate::{CodecRejection, ContentType};
+
+/// Records the negotiated response [`ContentType`] of a request.
+pub(crate) fn record_negotiation(content_type: ContentType) {
+	::metrics::counter!("axum_codec_responses_total", "content_type" => content_type.as_str())
+		.increment(1);
+}
+
+/// Records the size of a decoded request body.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn record_decode(content_type: ContentType, len: usize) {
+	::metrics::histogram!("axum_codec_request_body_bytes", "content_type" => content_type.as_str())
+		.record(len as f64);
+}
+
+/// Records whether a request body was decoded successfully or rejected.
+pub(crate) fn record_outcome<T>(content_type: ContentType, result: &Result<T, CodecRejection>) {
+	let outcome = if result.is_ok() { "success" } else { "rejected" };
+
+	::metrics::counter!(
+		"axum_codec_decodes_total",
+		"content_type" => content_type.as_str(),
+		"outcome" => outcome,
+	)
+	.increment(1);
+}
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		body::Body,
+		http::{header, Request, StatusCode},
+		Router,
+	};
+	use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+	use tower::ServiceExt;
+
+	use crate::{routing::post, Codec};
+
+	#[crate::apply(encode, decode, crate = "crate")]
+	struct Data {
+		hello: String,
+	}
+
+	#[test]
+	fn test_decode_metrics() {
+		let recorder = DebuggingRecorder::new();
+		let snapshotter = recorder.snapshotter();
+
+		let router: Router = Router::new().route(
+			"/",
+			post(|Codec(data): Codec<Data>| async move { Codec(data) }).into(),
+		);
+
+		let statuses = metrics::with_local_recorder(&recorder, || {
+			tokio::runtime::Builder::new_current_thread()
+				.build()
+				.unwrap()
+				.block_on(async {
+					let mut statuses = Vec::new();
+
+					for body in [r#"{"hello":"world"}"#, "{"] {
+						let response = router
+							.clone()
+							.oneshot(
+								Request::builder()
+									.method("POST")
+									.header(header::CONTENT_TYPE, "application/json")
+									.header(header::ACCEPT, "application/cbor")
+									.body(Body::from(body))
+									.unwrap(),
+							)
+							.await
+							.unwrap();
+
+						statuses.push(response.status());
+					}
+
+					statuses
+				})
+		});
+
+		assert_eq!(statuses, [StatusCode::OK, StatusCode::BAD_REQUEST]);
+
+		let mut metrics = snapshotter
+			.snapshot()
+			.into_vec()
+			.into_iter()
+			.map(|(key, _, _, value)| {
+				let labels = key
+					.key()
+					.labels()
+					.map(|label| format!("{}={}", label.key(), label.value()))
+					.collect::<Vec<_>>()
+					.join(",");
+				let value = match value {
+					DebugValue::Counter(count) => count.to_string(),
+					DebugValue::Histogram(values) => format!("{values:?}"),
+					DebugValue::Gauge(value) => value.to_string(),
+				};
+
+				format!("{}{{{labels}}} {value}", key.key().name())
+			})
+			.collect::<Vec<_>>();
+
+		metrics.sort();
+
+		assert_eq!(metrics, [
+			"axum_codec_decodes_total{content_type=application/json,outcome=rejected} 1",
+			"axum_codec_decodes_total{content_type=application/json,outcome=success} 1",
+			"axum_codec_request_body_bytes{content_type=application/json} [17.0, 1.0]",
+			"axum_codec_responses_total{content_type=application/cbor} 2",
+		]);
+	}
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use axum::{extract::rejection::BytesRejection, http::StatusCode, response::Response};
 
 use crate::{ContentType, IntoCodecResponse};
@@ -11,6 +13,28 @@ use crate::{ContentType, IntoCodecResponse};
 pub enum CodecRejection {
 	#[error(transparent)]
 	Bytes(#[from] BytesRejection),
+	#[error("failed to read the request body: {0}")]
+	Body(axum::Error),
+	#[error("request has multiple conflicting `Content-Type` headers")]
+	ConflictingContentType,
+	#[error("payload is larger than the limit of {limit} bytes")]
+	PayloadTooLarge { limit: usize },
+	#[error("request is missing the `{header}` header")]
+	MissingDiscriminator { header: &'static str },
+	#[error("unknown value `{value}` for the `{header}` header")]
+	UnknownDiscriminator { header: &'static str, value: String },
+	#[error("request does not accept any supported format")]
+	NotAcceptable,
+	#[error("{content_type} is not supported by this endpoint")]
+	UnsupportedContentType {
+		content_type: ContentType,
+		supported: &'static [ContentType],
+	},
+	#[error("{0} cannot be decoded with a `DeserializeSeed`")]
+	UnsupportedSeed(ContentType),
+	#[cfg(feature = "base64")]
+	#[error("payload is not valid base64: {0}")]
+	Base64(#[from] base64::DecodeError),
 	#[cfg(feature = "json")]
 	#[error(transparent)]
 	Json(#[from] serde_json::Error),
@@ -20,6 +44,9 @@ pub enum CodecRejection {
 	#[cfg(feature = "cbor")]
 	#[error(transparent)]
 	Cbor(#[from] ciborium::de::Error<std::io::Error>),
+	#[cfg(feature = "bson")]
+	#[error(transparent)]
+	Bson(#[from] bson::de::Error),
 	#[cfg(feature = "bincode")]
 	#[error(transparent)]
 	Bincode(#[from] bincode::error::DecodeError),
@@ -28,27 +55,118 @@ pub enum CodecRejection {
 	Bitcode(#[from] bitcode::Error),
 	#[cfg(feature = "yaml")]
 	#[error(transparent)]
-	Yaml(#[from] serde_yaml::Error),
+	Yaml(#[from] crate::yaml::Error),
 	#[cfg(feature = "toml")]
 	#[error(transparent)]
-	Toml(#[from] toml::de::Error),
-	#[cfg(any(feature = "toml", feature = "yaml"))]
+	Toml(#[from] TomlError),
+	#[cfg(feature = "ron")]
+	#[error(transparent)]
+	Ron(#[from] ron::error::SpannedError),
+	#[cfg(any(feature = "toml", feature = "yaml", feature = "ron"))]
 	#[error(transparent)]
 	Utf8Error(#[from] core::str::Utf8Error),
+	#[cfg(feature = "custom")]
+	#[error(transparent)]
+	Custom(axum::BoxError),
 	#[cfg(feature = "validator")]
 	#[error("validator error")]
 	Validator(#[from] validator::ValidationErrors),
 }
 
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for CodecRejection {
+	fn from(err: toml::de::Error) -> Self {
+		Self::Toml(err.into())
+	}
+}
+
+/// A TOML decoding error, along with the line and column it occurred at.
+///
+/// `toml` only exposes the location of an error as a byte range, so the line
+/// and column are computed from the input when the error is created. Derefs
+/// to the underlying [`toml::de::Error`].
+#[cfg(feature = "toml")]
+#[derive(Debug)]
+pub struct TomlError {
+	error: toml::de::Error,
+	location: Option<(usize, usize)>,
+}
+
+#[cfg(feature = "toml")]
+impl TomlError {
+	/// Wraps an error produced while decoding `input`.
+	pub(crate) fn new(error: toml::de::Error, input: &str) -> Self {
+		let location = error
+			.span()
+			.and_then(|span| input.get(..span.start))
+			.map(|before| {
+				let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+				(
+					before.matches('\n').count() + 1,
+					before[line_start..].chars().count() + 1,
+				)
+			});
+
+		Self { error, location }
+	}
+
+	/// Returns the line and column (both starting at 1) that the error occurred
+	/// at, if known.
+	#[must_use]
+	pub fn location(&self) -> Option<(usize, usize)> {
+		self.location
+	}
+
+	/// Returns the underlying [`toml::de::Error`].
+	#[must_use]
+	pub fn into_inner(self) -> toml::de::Error {
+		self.error
+	}
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for TomlError {
+	fn from(error: toml::de::Error) -> Self {
+		Self {
+			error,
+			location: None,
+		}
+	}
+}
+
+#[cfg(feature = "toml")]
+impl core::fmt::Display for TomlError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		self.error.fmt(f)
+	}
+}
+
+#[cfg(feature = "toml")]
+impl std::error::Error for TomlError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		self.error.source()
+	}
+}
+
+#[cfg(feature = "toml")]
+impl core::ops::Deref for TomlError {
+	type Target = toml::de::Error;
+
+	fn deref(&self) -> &Self::Target {
+		&self.error
+	}
+}
+
 #[cfg(not(feature = "pretty-errors"))]
 impl IntoCodecResponse for CodecRejection {
-	fn into_codec_response(self, _content_type: ContentType) -> Response {
+	fn into_codec_response(self, content_type: ContentType) -> Response {
 		use axum::response::IntoResponse;
 
 		let mut response = self.to_string().into_response();
 
 		*response.status_mut() = self.status_code();
-		response
+		self.attach(response, content_type)
 	}
 }
 
@@ -96,7 +214,7 @@ impl IntoCodecResponse for CodecRejection {
 		let mut response = crate::Codec(self.message()).into_codec_response(content_type);
 
 		*response.status_mut() = self.status_code();
-		response
+		self.attach(response, content_type)
 	}
 }
 
@@ -106,7 +224,9 @@ pub struct Message {
 	/// A unique error code, useful for localization.
 	pub code: &'static str,
 	/// A human-readable error message in English.
-	// TODO: use Cow<'static, str> when bitcode supports it
+	// TODO: use Cow<'static, str> when bitcode supports it (still missing as of
+	// bitcode 0.6.9, which only implements `Encode` for `str`, `&str`, `String`
+	// and smart pointers such as `Box<str>`)
 	pub content: String,
 }
 
@@ -129,17 +249,250 @@ impl aide::OperationOutput for Message {
 	}
 }
 
-impl CodecRejection {
+/// Extension trait for returning early from a handler with an error encoded in
+/// the negotiated format.
+///
+/// Errors that convert into a [`CodecRejection`] (such as
+/// `validator::ValidationErrors`) are encoded like the rejections of the
+/// [`Codec`](crate::Codec) extractor. With the `pretty-errors` feature, domain
+/// errors can also be expressed as a `(StatusCode, Message)` pair.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum::{http::StatusCode, response::Response};
+/// # use axum_codec::{rejection::{CodecResultExt, Message}, Accept, Codec};
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Transfer {
+///   from: u64,
+///   to: u64,
+/// }
+///
+/// fn check(transfer: &Transfer) -> Result<(), (StatusCode, Message)> {
+///   if transfer.from == transfer.to {
+///     return Err((StatusCode::UNPROCESSABLE_ENTITY, Message {
+///       code: "same_account",
+///       content: "Cannot transfer to the same account.".into(),
+///     }));
+///   }
+///
+///   Ok(())
+/// }
+///
+/// async fn transfer(accept: Accept, Codec(transfer): Codec<Transfer>) -> Result<StatusCode, Response> {
+///   check(&transfer).or_reject(accept)?;
+///
+///   Ok(StatusCode::NO_CONTENT)
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub trait CodecResultExt<T> {
+	/// Converts the error into a response encoded as `content_type`, leaving
+	/// the success value untouched.
+	///
+	/// # Errors
+	///
+	/// Returns the encoded error if `self` is an error.
+	#[allow(clippy::result_large_err)]
+	fn or_reject<C: Into<ContentType>>(self, content_type: C) -> Result<T, Response>;
+}
+
+impl<T, E> CodecResultExt<T> for Result<T, E>
+where
+	E: Into<CodecRejection>,
+{
+	fn or_reject<C: Into<ContentType>>(self, content_type: C) -> Result<T, Response> {
+		self.map_err(|e| e.into().into_codec_response(content_type.into()))
+	}
+}
+
+#[cfg(feature = "pretty-errors")]
+impl<T> CodecResultExt<T> for Result<T, (StatusCode, Message)> {
+	fn or_reject<C: Into<ContentType>>(self, content_type: C) -> Result<T, Response> {
+		self.map_err(|(status, message)| {
+			let mut response = crate::Codec(message).into_codec_response(content_type.into());
+
+			*response.status_mut() = status;
+			response
+		})
+	}
+}
+
+/// Decides the HTTP status code of a [`CodecRejection`].
+///
+/// The default implementation is used when rendering every rejection, and can
+/// be overridden for a whole router with
+/// [`RejectionStatusLayer`](crate::layer::RejectionStatusLayer), so that the
+/// mapping lives in one place instead of in every handler.
+///
+/// The trait is implemented for closures, which can fall back to
+/// [`CodecRejection::status_code`] for the rejections they do not handle.
+pub trait RejectionStatus: Clone + Send + Sync + 'static {
 	/// Returns the HTTP status code for the rejection.
+	fn status_code(&self, rejection: &CodecRejection) -> StatusCode {
+		match rejection {
+			CodecRejection::Bytes(..) | CodecRejection::PayloadTooLarge { .. } => {
+				StatusCode::PAYLOAD_TOO_LARGE
+			}
+			CodecRejection::UnsupportedSeed(..) | CodecRejection::UnsupportedContentType { .. } => {
+				StatusCode::UNSUPPORTED_MEDIA_TYPE
+			}
+			CodecRejection::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
+			_ => StatusCode::BAD_REQUEST,
+		}
+	}
+}
+
+/// The [`RejectionStatus`] used unless another one is configured.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultRejectionStatus;
+
+impl RejectionStatus for DefaultRejectionStatus {}
+
+impl<F> RejectionStatus for F
+where
+	F: Fn(&CodecRejection) -> StatusCode + Clone + Send + Sync + 'static,
+{
+	fn status_code(&self, rejection: &CodecRejection) -> StatusCode {
+		self(rejection)
+	}
+}
+
+/// The rejection that produced a response, kept in its extensions so that
+/// [`RejectionStatusLayer`](crate::layer::RejectionStatusLayer) can change its
+/// status code.
+#[derive(Clone, Debug)]
+pub(crate) struct Rejected {
+	pub(crate) rejection: Arc<CodecRejection>,
+	#[cfg(feature = "tracing")]
+	trace: Arc<PendingTrace>,
+}
+
+impl Rejected {
+	/// Changes the status code that the rejection is logged with.
+	#[cfg(feature = "tracing")]
+	pub(crate) fn set_status(&self, status: StatusCode) {
+		self
+			.trace
+			.status
+			.store(status.as_u16(), std::sync::atomic::Ordering::Relaxed);
+	}
+}
+
+/// Logs a rejection once its response is dropped, so that the logged status
+/// code is the one sent to the client, after any
+/// [`RejectionStatusLayer`](crate::layer::RejectionStatusLayer) has changed
+/// it.
+///
+/// The event is recorded within the span that was current when the rejection
+/// was rendered, so any request id attached to it by an upstream layer is
+/// included.
+#[cfg(feature = "tracing")]
+#[derive(Debug)]
+struct PendingTrace {
+	rejection: Arc<CodecRejection>,
+	content_type: ContentType,
+	status: std::sync::atomic::AtomicU16,
+	span: tracing::Span,
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for PendingTrace {
+	fn drop(&mut self) {
+		let status = *self.status.get_mut();
+		let _span = self.span.enter();
+
+		self.rejection.trace(self.content_type, status);
+	}
+}
+
+impl CodecRejection {
+	/// Returns the HTTP status code for the rejection, as decided by
+	/// [`DefaultRejectionStatus`].
 	#[must_use]
 	pub fn status_code(&self) -> StatusCode {
-		if matches!(self, Self::Bytes(..)) {
-			StatusCode::PAYLOAD_TOO_LARGE
-		} else {
-			StatusCode::BAD_REQUEST
+		DefaultRejectionStatus.status_code(self)
+	}
+
+	/// Stores the rejection in the extensions of the response rendered from it,
+	/// and logs it once the response is dropped (with the `tracing` feature).
+	fn attach(self, mut response: Response, content_type: ContentType) -> Response {
+		let rejection = Arc::new(self);
+
+		#[cfg(feature = "tracing")]
+		let trace = Arc::new(PendingTrace {
+			rejection: Arc::clone(&rejection),
+			content_type,
+			status: response.status().as_u16().into(),
+			span: tracing::Span::current(),
+		});
+		#[cfg(not(feature = "tracing"))]
+		let _ = content_type;
+
+		response.extensions_mut().insert(Rejected {
+			rejection,
+			#[cfg(feature = "tracing")]
+			trace,
+		});
+		response
+	}
+
+	/// Returns the name of the variant, used for logging.
+	#[cfg(feature = "tracing")]
+	fn kind(&self) -> &'static str {
+		match self {
+			Self::Bytes(..) => "bytes",
+			Self::Body(..) => "body",
+			Self::ConflictingContentType => "conflicting_content_type",
+			Self::PayloadTooLarge { .. } => "payload_too_large",
+			Self::MissingDiscriminator { .. } => "missing_discriminator",
+			Self::UnknownDiscriminator { .. } => "unknown_discriminator",
+			Self::UnsupportedSeed(..) => "unsupported_seed",
+			Self::NotAcceptable => "not_acceptable",
+			Self::UnsupportedContentType { .. } => "unsupported_content_type",
+			#[cfg(feature = "base64")]
+			Self::Base64(..) => "base64",
+			#[cfg(feature = "json")]
+			Self::Json(..) => "json",
+			#[cfg(feature = "msgpack")]
+			Self::MsgPack(..) => "msgpack",
+			#[cfg(feature = "cbor")]
+			Self::Cbor(..) => "cbor",
+			#[cfg(feature = "bson")]
+			Self::Bson(..) => "bson",
+			#[cfg(feature = "bincode")]
+			Self::Bincode(..) => "bincode",
+			#[cfg(feature = "bitcode")]
+			Self::Bitcode(..) => "bitcode",
+			#[cfg(feature = "yaml")]
+			Self::Yaml(..) => "yaml",
+			#[cfg(feature = "toml")]
+			Self::Toml(..) => "toml",
+			#[cfg(feature = "ron")]
+			Self::Ron(..) => "ron",
+			#[cfg(any(feature = "toml", feature = "yaml", feature = "ron"))]
+			Self::Utf8Error(..) => "utf8",
+			#[cfg(feature = "custom")]
+			Self::Custom(..) => "custom",
+			#[cfg(feature = "validator")]
+			Self::Validator(..) => "validator",
 		}
 	}
 
+	/// Logs the full rejection, since the client only receives a terse message.
+	#[cfg(feature = "tracing")]
+	fn trace(&self, content_type: ContentType, status: u16) {
+		tracing::debug!(
+			kind = self.kind(),
+			status,
+			content_type = %content_type,
+			error = ?self,
+			"rejected request: {self}",
+		);
+	}
+
 	/// Consumes the rejection and returns a pretty [`Message`] representing the
 	/// error.
 	///
@@ -149,18 +502,62 @@ impl CodecRejection {
 	#[must_use]
 	pub fn message(&self) -> Message {
 		let code = match self {
-			Self::Bytes(..) => {
+			Self::Bytes(..) | Self::PayloadTooLarge { .. } => {
 				return Message {
 					code: "payload_too_large",
 					content: "The request payload is too large.".into(),
 				}
 			}
+			Self::ConflictingContentType => {
+				return Message {
+					code: "conflicting_content_type",
+					content: "The request has multiple conflicting `Content-Type` headers.".into(),
+				}
+			}
+			Self::Body(..) => {
+				return Message {
+					code: "malformed_body",
+					content: "The request body could not be read.".into(),
+				}
+			}
+			Self::NotAcceptable => {
+				return Message {
+					code: "not_acceptable",
+					content: format!(
+						"None of the formats in the `Accept` header are supported. Supported formats: {}.",
+						crate::enabled_mime_types().collect::<Vec<_>>().join(", ")
+					),
+				}
+			}
+			Self::UnsupportedContentType {
+				content_type,
+				supported,
+			} => {
+				return Message {
+					code: "unsupported_media_type",
+					content: format!(
+						"The `{content_type}` format is not supported by this endpoint. Supported formats: {}.",
+						supported
+							.iter()
+							.map(ContentType::as_str)
+							.collect::<Vec<_>>()
+							.join(", ")
+					),
+				}
+			}
+			Self::MissingDiscriminator { .. } => "missing_discriminator",
+			Self::UnknownDiscriminator { .. } => "unknown_discriminator",
+			Self::UnsupportedSeed(..) => "unsupported_media_type",
+			#[cfg(feature = "base64")]
+			Self::Base64(..) => "decode",
 			#[cfg(feature = "json")]
 			Self::Json(..) => "decode",
 			#[cfg(feature = "msgpack")]
 			Self::MsgPack(..) => "decode",
 			#[cfg(feature = "cbor")]
 			Self::Cbor(..) => "decode",
+			#[cfg(feature = "bson")]
+			Self::Bson(..) => "decode",
 			#[cfg(feature = "bincode")]
 			Self::Bincode(..) => "decode",
 			#[cfg(feature = "bitcode")]
@@ -168,8 +565,17 @@ impl CodecRejection {
 			#[cfg(feature = "yaml")]
 			Self::Yaml(..) => "decode",
 			#[cfg(feature = "toml")]
-			Self::Toml(..) => "decode",
-			#[cfg(any(feature = "toml", feature = "yaml"))]
+			Self::Toml(err) => {
+				return Message {
+					code: "decode",
+					content: format_toml(err),
+				}
+			}
+			#[cfg(feature = "ron")]
+			Self::Ron(..) => "decode",
+			#[cfg(feature = "custom")]
+			Self::Custom(..) => "decode",
+			#[cfg(any(feature = "toml", feature = "yaml", feature = "ron"))]
 			Self::Utf8Error(..) => {
 				return Message {
 					code: "malformed_utf8",
@@ -192,6 +598,18 @@ impl CodecRejection {
 	}
 }
 
+/// Formats a TOML error as a single line starting with its location, such as
+/// `line 4, column 2: invalid type: string "x", expected u16`.
+#[cfg(all(feature = "pretty-errors", feature = "toml"))]
+fn format_toml(err: &TomlError) -> String {
+	let message = err.message().trim_end().replace('\n', ", ");
+
+	match err.location() {
+		Some((line, column)) => format!("line {line}, column {column}: {message}"),
+		None => message,
+	}
+}
+
 #[cfg(all(feature = "pretty-errors", feature = "validator"))]
 fn format_validator(err: &validator::ValidationErrors) -> String {
 	let mut buf = String::new();
@@ -257,3 +675,143 @@ fn append_validator_errors(field: &str, err: &validator::ValidationErrorsKind, b
 		}
 	}
 }
+
+#[cfg(all(test, feature = "pretty-errors"))]
+mod test {
+	use axum::{body::to_bytes, http::StatusCode};
+
+	use super::{CodecRejection, CodecResultExt, Message};
+	use crate::{Codec, ContentType, IntoCodecResponse};
+
+	/// [`Message`](super::Message) borrows its code, so it can only be decoded
+	/// into an owned mirror of itself.
+	#[crate::apply(decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct OwnedMessage {
+		code: String,
+		content: String,
+	}
+
+	#[tokio::test]
+	async fn test_message_negotiates_every_format() {
+		for &content_type in ContentType::all() {
+			let rejection = CodecRejection::PayloadTooLarge { limit: 1 };
+			let expected = rejection.message();
+			let response = rejection.into_codec_response(content_type);
+
+			assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+			assert_eq!(
+				response.headers()[axum::http::header::CONTENT_TYPE],
+				content_type.into_header(),
+			);
+
+			let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+			let Codec(decoded) = Codec::<OwnedMessage>::from_bytes(&bytes, content_type)
+				.unwrap_or_else(|err| panic!("{content_type}: {err}"));
+
+			assert_eq!(
+				decoded,
+				OwnedMessage {
+					code: expected.code.into(),
+					content: expected.content,
+				},
+				"{content_type}"
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_or_reject() {
+		assert_eq!(
+			Ok::<_, CodecRejection>(5)
+				.or_reject(ContentType::Json)
+				.unwrap(),
+			5
+		);
+
+		let Err(response) =
+			Err::<(), _>(CodecRejection::ConflictingContentType).or_reject(ContentType::Cbor)
+		else {
+			panic!("expected a rejection");
+		};
+
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+		assert_eq!(
+			response.headers()[axum::http::header::CONTENT_TYPE],
+			ContentType::Cbor.into_header(),
+		);
+
+		let Err(response) = Err::<(), _>((StatusCode::CONFLICT, Message {
+			code: "already_exists",
+			content: "The user already exists.".into(),
+		}))
+		.or_reject(ContentType::Json) else {
+			panic!("expected a rejection");
+		};
+
+		assert_eq!(response.status(), StatusCode::CONFLICT);
+
+		let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+		let Codec(decoded) = Codec::<OwnedMessage>::from_json(&bytes).unwrap();
+
+		assert_eq!(decoded, OwnedMessage {
+			code: "already_exists".into(),
+			content: "The user already exists.".into(),
+		});
+	}
+
+	#[test]
+	fn test_toml_message_location() {
+		#[crate::apply(decode, crate = "crate")]
+		#[derive(Debug)]
+		struct Config {
+			name: String,
+			port: u16,
+		}
+
+		let message = |input: &[u8]| {
+			Codec::<Config>::from_bytes(input, ContentType::Toml)
+				.unwrap_err()
+				.message()
+		};
+
+		let invalid_type = message(b"name = \"a\"\nport = \"x\"\n");
+
+		assert_eq!(invalid_type.code, "decode");
+		assert_eq!(
+			invalid_type.content,
+			"line 2, column 8: invalid type: string \"x\", expected u16"
+		);
+
+		assert_eq!(
+			message(b"name = \"a\"\nport = = 4\n").content,
+			"line 2, column 8: invalid string, expected `\"`, `'`"
+		);
+	}
+
+	#[test]
+	fn test_toml_error_location() {
+		use super::CodecRejection;
+
+		#[crate::apply(decode, crate = "crate")]
+		#[derive(Debug)]
+		struct Config {
+			#[allow(dead_code)]
+			name: String,
+		}
+
+		let input = "# café ☕\nname = \"ok\"\n\nname = 1\n";
+		let Err(CodecRejection::Toml(err)) =
+			Codec::<Config>::from_bytes(input.as_bytes(), ContentType::Toml)
+		else {
+			panic!("expected a TOML error");
+		};
+
+		assert_eq!(err.location(), Some((4, 1)));
+		assert_eq!(err.to_string(), err.into_inner().to_string());
+
+		let err = crate::rejection::TomlError::from(toml::from_str::<Config>("name = 1").unwrap_err());
+
+		assert_eq!(err.location(), None);
+	}
+}
@@ -1,9 +1,21 @@
-use axum::response::Response;
+use core::{
+	pin::Pin,
+	task::{ready, Context, Poll},
+	time::Duration,
+};
 
-use crate::{Codec, CodecEncode, ContentType};
+use axum::{
+	body::Body,
+	http::{header, HeaderValue},
+	response::Response,
+	BoxError,
+};
+use futures_core::Stream;
+
+use crate::{Accept, Codec, CodecEncode, ContentType};
 
 #[cfg(not(feature = "aide"))]
-pub trait IntoCodecResponse {
+pub trait IntoCodecResponse: boxed::IntoCodecResponseBoxed {
 	fn into_codec_response(self, content_type: ContentType) -> Response;
 }
 
@@ -22,6 +34,41 @@ where
 	}
 }
 
+#[cfg(not(feature = "aide"))]
+mod boxed {
+	use axum::response::Response;
+
+	use super::IntoCodecResponse;
+	use crate::ContentType;
+
+	/// Allows calling [`IntoCodecResponse::into_codec_response`] through a
+	/// `Box<dyn IntoCodecResponse>`, since it takes `self` by value.
+	///
+	/// Implemented for every [`IntoCodecResponse`].
+	pub trait IntoCodecResponseBoxed {
+		fn into_codec_response_boxed(self: Box<Self>, content_type: ContentType) -> Response;
+	}
+
+	impl<T> IntoCodecResponseBoxed for T
+	where
+		T: IntoCodecResponse,
+	{
+		fn into_codec_response_boxed(self: Box<Self>, content_type: ContentType) -> Response {
+			(*self).into_codec_response(content_type)
+		}
+	}
+
+	/// Allows returning different response types from the same handler.
+	///
+	/// This is not available with the `aide` feature, since
+	/// `aide::OperationOutput` cannot be made into a trait object.
+	impl IntoCodecResponse for Box<dyn IntoCodecResponse + Send> {
+		fn into_codec_response(self, content_type: ContentType) -> Response {
+			self.into_codec_response_boxed(content_type)
+		}
+	}
+}
+
 #[cfg(feature = "aide")]
 impl<D> IntoCodecResponse for Codec<D>
 where
@@ -33,6 +80,816 @@ where
 	}
 }
 
+/// A response that renders HTML for clients that prefer `text/html`, and
+/// otherwise encodes the value with the negotiated [`ContentType`].
+///
+/// Useful for endpoints that serve both browsers and API clients.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{response::CodecOrHtml, Accept, IntoCodecResponse};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct User {
+///   name: String,
+/// }
+///
+/// async fn get_user(accept: Accept) -> impl IntoCodecResponse {
+///   let user = User {
+///     name: "Alice".into(),
+///   };
+///
+///   CodecOrHtml::new(accept, user, |user: &User| format!("<h1>{}</h1>", user.name))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct CodecOrHtml<T, F> {
+	value: T,
+	render: F,
+	html: bool,
+}
+
+impl<T, F> CodecOrHtml<T, F>
+where
+	F: FnOnce(&T) -> String,
+{
+	/// Creates a new [`CodecOrHtml`], rendering `value` with `render` if the
+	/// request's [`Accept`] prefers HTML.
+	pub fn new(accept: Accept, value: T, render: F) -> Self {
+		Self {
+			value,
+			render,
+			html: accept.prefers_html(),
+		}
+	}
+}
+
+impl<T, F> CodecOrHtml<T, F>
+where
+	T: CodecEncode,
+	F: FnOnce(&T) -> String,
+{
+	fn render(self, content_type: ContentType) -> Response {
+		use axum::response::{Html, IntoResponse};
+
+		if self.html {
+			Html((self.render)(&self.value)).into_response()
+		} else {
+			Codec(self.value).to_response(content_type)
+		}
+	}
+}
+
+#[cfg(not(feature = "aide"))]
+impl<T, F> IntoCodecResponse for CodecOrHtml<T, F>
+where
+	T: CodecEncode,
+	F: FnOnce(&T) -> String,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T, F> IntoCodecResponse for CodecOrHtml<T, F>
+where
+	T: CodecEncode,
+	F: FnOnce(&T) -> String,
+	Self: aide::OperationOutput,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T, F> aide::OperationOutput for CodecOrHtml<T, F>
+where
+	T: schemars::JsonSchema,
+{
+	type Inner = T;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<T>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_responses(ctx, operation)
+	}
+}
+
+/// A [`Codec`] response with a `Cache-Control: max-age=...` header, created
+/// with [`Codec::with_cache`].
+///
+/// The header is only added to successful responses, so a failure to encode
+/// the value is never cached.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use std::time::Duration;
+/// # use axum_codec::{Codec, IntoCodecResponse};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Config {
+///   theme: String,
+/// }
+///
+/// async fn config() -> impl IntoCodecResponse {
+///   Codec(Config {
+///     theme: "dark".into(),
+///   })
+///   .with_cache(Duration::from_secs(60))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct CacheableCodec<T> {
+	value: T,
+	max_age: Duration,
+}
+
+impl<T> Codec<T> {
+	/// Wraps the value in a [`CacheableCodec`], which allows clients to cache
+	/// the response for `max_age` (rounded down to whole seconds).
+	pub fn with_cache(self, max_age: Duration) -> CacheableCodec<T> {
+		CacheableCodec {
+			value: self.0,
+			max_age,
+		}
+	}
+}
+
+impl<T> CacheableCodec<T>
+where
+	T: CodecEncode,
+{
+	fn render(self, content_type: ContentType) -> Response {
+		let mut response = Codec(self.value).to_response(content_type);
+
+		let value = HeaderValue::try_from(format!("max-age={}", self.max_age.as_secs()));
+
+		if let (true, Ok(value)) = (response.status().is_success(), value) {
+			response.headers_mut().insert(header::CACHE_CONTROL, value);
+		}
+
+		response
+	}
+}
+
+#[cfg(not(feature = "aide"))]
+impl<T> IntoCodecResponse for CacheableCodec<T>
+where
+	T: CodecEncode,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> IntoCodecResponse for CacheableCodec<T>
+where
+	T: CodecEncode,
+	Self: aide::OperationOutput,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::OperationOutput for CacheableCodec<T>
+where
+	T: schemars::JsonSchema,
+{
+	type Inner = T;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<T>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_responses(ctx, operation)
+	}
+}
+
+/// A response for values that only implement [`serde::Serialize`], such as
+/// maps assembled at runtime.
+///
+/// Unlike [`Codec`], the value does not need to implement the Bincode or
+/// Bitcode traits. If one of those formats is negotiated, a
+/// `406 Not Acceptable` response is returned instead.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use std::collections::BTreeMap;
+/// # use axum_codec::{response::SerdeCodec, IntoCodecResponse};
+/// #
+/// async fn stats() -> impl IntoCodecResponse {
+///   let mut stats = BTreeMap::new();
+///
+///   stats.insert("users".to_string(), serde_json::json!(42));
+///   stats.insert("regions".to_string(), serde_json::json!(["eu", "us"]));
+///
+///   SerdeCodec(stats)
+/// }
+/// #
+/// # fn main() {}
+/// ```
+#[cfg(feature = "serde")]
+pub struct SerdeCodec<T>(pub T);
+
+#[cfg(all(feature = "serde", not(feature = "aide")))]
+impl<T> IntoCodecResponse for SerdeCodec<T>
+where
+	T: serde::Serialize,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		serde_response(&Codec(self.0), content_type)
+	}
+}
+
+#[cfg(all(feature = "serde", feature = "aide"))]
+impl<T> IntoCodecResponse for SerdeCodec<T>
+where
+	T: serde::Serialize,
+	Self: aide::OperationOutput,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		serde_response(&Codec(self.0), content_type)
+	}
+}
+
+#[cfg(feature = "serde")]
+fn serde_response<T>(codec: &Codec<T>, content_type: ContentType) -> Response
+where
+	T: serde::Serialize,
+{
+	use axum::{http::header, response::IntoResponse};
+
+	match codec.to_serde_bytes(content_type) {
+		Ok(bytes) => ([(header::CONTENT_TYPE, content_type.into_header())], bytes).into_response(),
+		Err(error) => error.into_codec_response(content_type),
+	}
+}
+
+#[cfg(all(feature = "serde", feature = "aide"))]
+impl<T> aide::OperationOutput for SerdeCodec<T>
+where
+	T: schemars::JsonSchema,
+{
+	type Inner = T;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<T>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_responses(ctx, operation)
+	}
+}
+
+/// Wraps any [`IntoResponse`](axum::response::IntoResponse) so it can be
+/// returned from a codec handler. The negotiated [`ContentType`] is ignored.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{response::Passthrough, IntoCodecResponse};
+/// # use axum::response::Redirect;
+/// #
+/// async fn legacy() -> impl IntoCodecResponse {
+///   Passthrough(Redirect::permanent("/v2/legacy"))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct Passthrough<R>(pub R);
+
+#[cfg(not(feature = "aide"))]
+impl<R> IntoCodecResponse for Passthrough<R>
+where
+	R: axum::response::IntoResponse,
+{
+	fn into_codec_response(self, _content_type: ContentType) -> Response {
+		self.0.into_response()
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<R> IntoCodecResponse for Passthrough<R>
+where
+	R: axum::response::IntoResponse + aide::OperationOutput,
+{
+	fn into_codec_response(self, _content_type: ContentType) -> Response {
+		self.0.into_response()
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<R> aide::OperationOutput for Passthrough<R>
+where
+	R: aide::OperationOutput,
+{
+	type Inner = R::Inner;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		R::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		R::inferred_responses(ctx, operation)
+	}
+}
+
+/// A response that encodes a [`Stream`] of items as an array, without
+/// collecting the items first.
+///
+/// JSON and CBOR (as an indefinite-length array) are written incrementally,
+/// one item per chunk, so memory use is bounded by the size of a single item.
+/// Formats that need to know the length of an array up front, or cannot frame
+/// one at all, fall back to collecting every item and encoding them together.
+///
+/// Since the status code and headers are sent before the first item is
+/// encoded, an item that fails to encode (or to validate, with the
+/// `validate-responses` feature) ends the body with an error instead of
+/// returning `500 Internal Server Error`.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{response::CodecArrayStream, IntoCodecResponse};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Row {
+///   id: u32,
+/// }
+///
+/// async fn rows() -> impl IntoCodecResponse {
+///   CodecArrayStream(futures_util::stream::iter((0..10_000).map(|id| Row { id })))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct CodecArrayStream<S>(pub S);
+
+impl<S> CodecArrayStream<S>
+where
+	S: Stream + Send + 'static,
+	S::Item: CodecEncode + Send + 'static,
+{
+	fn render(self, content_type: ContentType) -> Response {
+		use axum::response::IntoResponse;
+
+		let body = ArrayBody {
+			stream: Box::pin(self.0),
+			content_type,
+			framing: Framing::of(content_type),
+			buffered: Vec::new(),
+			opened: false,
+			first: true,
+			done: false,
+		};
+
+		(
+			[(header::CONTENT_TYPE, content_type.into_header())],
+			Body::from_stream(body),
+		)
+			.into_response()
+	}
+}
+
+#[cfg(not(feature = "aide"))]
+impl<S> IntoCodecResponse for CodecArrayStream<S>
+where
+	S: Stream + Send + 'static,
+	S::Item: CodecEncode + Send + 'static,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<S> IntoCodecResponse for CodecArrayStream<S>
+where
+	S: Stream + Send + 'static,
+	S::Item: CodecEncode + Send + 'static,
+	Self: aide::OperationOutput,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<S> aide::OperationOutput for CodecArrayStream<S>
+where
+	S: Stream,
+	S::Item: schemars::JsonSchema,
+{
+	type Inner = Vec<S::Item>;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<Vec<S::Item>>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<Vec<S::Item>>::inferred_responses(ctx, operation)
+	}
+}
+
+/// A response that forwards a stream of already-encoded chunks, such as a
+/// proxied upstream body, tagged with the negotiated [`ContentType`].
+///
+/// Only the `Content-Type` header is set. Since the length is not known up
+/// front, no `Content-Length` is sent and HTTP/1.1 responses use chunked
+/// transfer encoding. The chunks must already be encoded in the negotiated
+/// format.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use std::convert::Infallible;
+/// # use axum_codec::{response::ChunkedCodec, IntoCodecResponse};
+/// #
+/// async fn proxied() -> impl IntoCodecResponse {
+///   let chunks = ["{\"hello\":", "\"world\"}"].map(Ok::<_, Infallible>);
+///
+///   ChunkedCodec(futures_util::stream::iter(chunks))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct ChunkedCodec<S>(pub S);
+
+impl<S> IntoCodecResponse for ChunkedCodec<S>
+where
+	S: futures_core::TryStream + Send + 'static,
+	S::Ok: Into<axum::body::Bytes>,
+	S::Error: Into<BoxError>,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		use axum::response::IntoResponse;
+
+		(
+			[(header::CONTENT_TYPE, content_type.into_header())],
+			Body::from_stream(self.0),
+		)
+			.into_response()
+	}
+}
+
+/// The chunks are opaque, so no schema is generated for them.
+#[cfg(feature = "aide")]
+impl<S> aide::OperationOutput for ChunkedCodec<S> {
+	type Inner = ();
+}
+
+/// A response that encodes a [`Stream`] of items one at a time, framing each
+/// item so that clients can decode them as they arrive.
+///
+/// JSON is sent as [NDJSON](https://github.com/ndjson/ndjson-spec)
+/// (`application/x-ndjson`), with one item per line. Every other format is
+/// sent with its own content type, and each item is preceded by its length in
+/// bytes as a 4-byte big-endian integer.
+///
+/// Unlike [`CodecArrayStream`], the items are never collected, regardless of
+/// the format. As with it, an item that fails to encode (or to validate, with
+/// the `validate-responses` feature) ends the body with an error, since the
+/// status code has already been sent.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{response::CodecChunks, IntoCodecResponse};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Tick {
+///   n: u32,
+/// }
+///
+/// async fn ticks() -> impl IntoCodecResponse {
+///   CodecChunks(futures_util::stream::iter((0..10).map(|n| Tick { n })))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+#[cfg(feature = "stream")]
+pub struct CodecChunks<S>(pub S);
+
+#[cfg(feature = "stream")]
+impl<S> CodecChunks<S>
+where
+	S: Stream + Send + 'static,
+	S::Item: CodecEncode + Send + 'static,
+{
+	fn render(self, content_type: ContentType) -> Response {
+		use axum::response::IntoResponse;
+
+		let header = match content_type {
+			#[cfg(feature = "json")]
+			ContentType::Json => HeaderValue::from_static("application/x-ndjson"),
+			#[allow(unreachable_patterns)]
+			_ => content_type.into_header(),
+		};
+
+		let body = ChunksBody {
+			stream: Box::pin(self.0),
+			content_type,
+			done: false,
+		};
+
+		([(header::CONTENT_TYPE, header)], Body::from_stream(body)).into_response()
+	}
+}
+
+#[cfg(all(feature = "stream", not(feature = "aide")))]
+impl<S> IntoCodecResponse for CodecChunks<S>
+where
+	S: Stream + Send + 'static,
+	S::Item: CodecEncode + Send + 'static,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+#[cfg(all(feature = "stream", feature = "aide"))]
+impl<S> IntoCodecResponse for CodecChunks<S>
+where
+	S: Stream + Send + 'static,
+	S::Item: CodecEncode + Send + 'static,
+	Self: aide::OperationOutput,
+{
+	fn into_codec_response(self, content_type: ContentType) -> Response {
+		self.render(content_type)
+	}
+}
+
+/// Documented as an array, since there is no schema for a framed stream.
+#[cfg(all(feature = "stream", feature = "aide"))]
+impl<S> aide::OperationOutput for CodecChunks<S>
+where
+	S: Stream,
+	S::Item: schemars::JsonSchema,
+{
+	type Inner = Vec<S::Item>;
+
+	fn operation_response(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Option<aide::openapi::Response> {
+		axum::Json::<Vec<S::Item>>::operation_response(ctx, operation)
+	}
+
+	fn inferred_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<Vec<S::Item>>::inferred_responses(ctx, operation)
+	}
+}
+
+/// The body of a [`CodecChunks`].
+#[cfg(feature = "stream")]
+struct ChunksBody<S> {
+	stream: Pin<Box<S>>,
+	content_type: ContentType,
+	done: bool,
+}
+
+// The stream is boxed and the other fields are never pinned.
+#[cfg(feature = "stream")]
+impl<S> Unpin for ChunksBody<S> {}
+
+#[cfg(feature = "stream")]
+impl<S> ChunksBody<S>
+where
+	S: Stream,
+	S::Item: CodecEncode + 'static,
+{
+	fn encode(&self, item: S::Item) -> Result<Vec<u8>, BoxError> {
+		#[cfg(feature = "validate-responses")]
+		validator::Validate::validate(&item)?;
+
+		#[cfg(feature = "json")]
+		if self.content_type == ContentType::Json {
+			let mut buf = Vec::new();
+
+			Codec(item).encode_into(self.content_type, &mut buf)?;
+			buf.push(b'\n');
+
+			return Ok(buf);
+		}
+
+		Ok(crate::framed::encode_frame(item, self.content_type)?)
+	}
+}
+
+#[cfg(feature = "stream")]
+impl<S> Stream for ChunksBody<S>
+where
+	S: Stream,
+	S::Item: CodecEncode + 'static,
+{
+	type Item = Result<Vec<u8>, BoxError>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+
+		if this.done {
+			return Poll::Ready(None);
+		}
+
+		let Some(item) = ready!(this.stream.as_mut().poll_next(cx)) else {
+			this.done = true;
+			return Poll::Ready(None);
+		};
+
+		let chunk = this.encode(item);
+		this.done = chunk.is_err();
+
+		Poll::Ready(Some(chunk))
+	}
+}
+
+/// How a format writes an array incrementally.
+#[derive(Clone, Copy)]
+enum Framing {
+	#[cfg(feature = "json")]
+	Json,
+	#[cfg(feature = "cbor")]
+	Cbor,
+}
+
+impl Framing {
+	fn of(content_type: ContentType) -> Option<Self> {
+		match content_type {
+			#[cfg(feature = "json")]
+			ContentType::Json => Some(Self::Json),
+			#[cfg(feature = "cbor")]
+			ContentType::Cbor => Some(Self::Cbor),
+			#[allow(unreachable_patterns)]
+			_ => None,
+		}
+	}
+
+	fn open(self) -> &'static [u8] {
+		match self {
+			#[cfg(feature = "json")]
+			Self::Json => b"[",
+			// Major type 4 (array) with an indefinite length.
+			#[cfg(feature = "cbor")]
+			Self::Cbor => &[0x9f],
+		}
+	}
+
+	fn separator(self) -> &'static [u8] {
+		match self {
+			#[cfg(feature = "json")]
+			Self::Json => b",",
+			#[cfg(feature = "cbor")]
+			Self::Cbor => &[],
+		}
+	}
+
+	fn close(self) -> &'static [u8] {
+		match self {
+			#[cfg(feature = "json")]
+			Self::Json => b"]",
+			// The "break" stop code that ends an indefinite-length item.
+			#[cfg(feature = "cbor")]
+			Self::Cbor => &[0xff],
+		}
+	}
+}
+
+/// The body of a [`CodecArrayStream`].
+struct ArrayBody<S: Stream> {
+	stream: Pin<Box<S>>,
+	content_type: ContentType,
+	framing: Option<Framing>,
+	/// Items collected for formats without [`Framing`].
+	buffered: Vec<S::Item>,
+	opened: bool,
+	first: bool,
+	done: bool,
+}
+
+// The stream is boxed and the other fields are never pinned.
+impl<S: Stream> Unpin for ArrayBody<S> {}
+
+impl<S> ArrayBody<S>
+where
+	S: Stream,
+	S::Item: CodecEncode + 'static,
+{
+	fn encode(&mut self, item: S::Item, framing: Framing) -> Result<Vec<u8>, BoxError> {
+		#[cfg(feature = "validate-responses")]
+		validator::Validate::validate(&item)?;
+
+		let mut buf = Vec::new();
+
+		if !core::mem::take(&mut self.first) {
+			buf.extend_from_slice(framing.separator());
+		}
+
+		Codec(item).encode_into(self.content_type, &mut buf)?;
+
+		Ok(buf)
+	}
+
+	fn encode_buffered(&mut self) -> Result<Vec<u8>, BoxError> {
+		let items = core::mem::take(&mut self.buffered);
+
+		#[cfg(feature = "validate-responses")]
+		items.iter().try_for_each(validator::Validate::validate)?;
+
+		Ok(Codec(items).to_bytes(self.content_type)?)
+	}
+}
+
+impl<S> Stream for ArrayBody<S>
+where
+	S: Stream,
+	S::Item: CodecEncode + 'static,
+{
+	type Item = Result<Vec<u8>, BoxError>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+
+		loop {
+			if this.done {
+				return Poll::Ready(None);
+			}
+
+			if !this.opened {
+				this.opened = true;
+
+				if let Some(framing) = this.framing {
+					return Poll::Ready(Some(Ok(framing.open().to_vec())));
+				}
+			}
+
+			let Some(item) = ready!(this.stream.as_mut().poll_next(cx)) else {
+				this.done = true;
+
+				return Poll::Ready(Some(match this.framing {
+					Some(framing) => Ok(framing.close().to_vec()),
+					None => this.encode_buffered(),
+				}));
+			};
+
+			let Some(framing) = this.framing else {
+				this.buffered.push(item);
+				continue;
+			};
+
+			let chunk = this.encode(item, framing);
+			this.done = chunk.is_err();
+
+			return Poll::Ready(Some(chunk));
+		}
+	}
+}
+
 mod axum_impls {
 	use std::borrow::Cow;
 
@@ -40,11 +897,15 @@ mod axum_impls {
 		body::Bytes,
 		http::StatusCode,
 		response::{IntoResponse, Response},
-		BoxError,
+		BoxError, Extension,
 	};
 
 	use super::{ContentType, IntoCodecResponse};
 
+	/// Encodes either arm with the negotiated content type.
+	///
+	/// Since [`Response`] implements [`IntoCodecResponse`], this also covers
+	/// `Result<T, Response>` for handlers whose errors are already rendered.
 	impl<T, E> IntoCodecResponse for Result<T, E>
 	where
 		T: IntoCodecResponse,
@@ -84,6 +945,17 @@ mod axum_impls {
 		StatusCode, (), &'static str, String, Bytes, Cow<'static, str>, &'static [u8], Vec<u8>,  Cow<'static, [u8]>
 	}
 
+	/// Allows `Result<T, Infallible>` to be returned from handlers.
+	///
+	/// This is not available with the `aide` feature, since `aide` does not
+	/// implement `OperationOutput` for [`Infallible`](std::convert::Infallible).
+	#[cfg(not(feature = "aide"))]
+	impl IntoCodecResponse for std::convert::Infallible {
+		fn into_codec_response(self, _ct: ContentType) -> Response {
+			match self {}
+		}
+	}
+
 	impl<R> IntoCodecResponse for (StatusCode, R)
 	where
 		R: IntoCodecResponse,
@@ -94,4 +966,318 @@ mod axum_impls {
 			res
 		}
 	}
+
+	/// Inserts the extension into the response, so it can be read by outer
+	/// middleware.
+	impl<E, R> IntoCodecResponse for (Extension<E>, R)
+	where
+		E: Clone + Send + Sync + 'static,
+		R: IntoCodecResponse,
+	{
+		fn into_codec_response(self, content_type: ContentType) -> Response {
+			let mut res = self.1.into_codec_response(content_type);
+			res.extensions_mut().insert(self.0 .0);
+			res
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		http::{header, StatusCode},
+		response::{IntoResponse, Response},
+	};
+
+	use super::IntoCodecResponse;
+	use crate::{Codec, ContentType};
+
+	#[crate::apply(encode)]
+	struct Data {
+		hello: String,
+	}
+
+	#[allow(clippy::result_large_err)]
+	fn respond(ok: bool) -> Result<Codec<Data>, Response> {
+		if ok {
+			Ok(Codec(Data {
+				hello: "world".into(),
+			}))
+		} else {
+			Err((StatusCode::IM_A_TEAPOT, "pre-rendered").into_response())
+		}
+	}
+
+	#[test]
+	fn test_result_with_raw_response() {
+		let response = respond(true).into_codec_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let response = respond(false).into_codec_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+	}
+
+	#[tokio::test]
+	async fn test_codec_or_html() {
+		use axum::{
+			body::{to_bytes, Body},
+			http::Request,
+			Router,
+		};
+		use tower::ServiceExt;
+
+		use super::CodecOrHtml;
+		use crate::{routing::get, Accept};
+
+		let router: Router = Router::new().route(
+			"/",
+			get(|accept: Accept| async move {
+				CodecOrHtml::new(
+					accept,
+					Data {
+						hello: "world".into(),
+					},
+					|data: &Data| format!("<h1>{}</h1>", data.hello),
+				)
+			})
+			.into(),
+		);
+
+		let call = |accept: &'static str| {
+			router.clone().oneshot(
+				Request::builder()
+					.header(header::ACCEPT, accept)
+					.body(Body::empty())
+					.unwrap(),
+			)
+		};
+
+		let response = call("text/html,application/xhtml+xml,*/*;q=0.8")
+			.await
+			.unwrap();
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			"text/html; charset=utf-8"
+		);
+		assert_eq!(
+			to_bytes(response.into_body(), usize::MAX).await.unwrap(),
+			"<h1>world</h1>"
+		);
+
+		let response = call("application/vnd.msgpack").await.unwrap();
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::MsgPack.into_header()
+		);
+		assert_eq!(
+			to_bytes(response.into_body(), usize::MAX).await.unwrap(),
+			Codec(Data {
+				hello: "world".into(),
+			})
+			.to_msgpack()
+			.unwrap()
+		);
+	}
+
+	#[test]
+	fn test_cacheable_codec() {
+		use core::time::Duration;
+
+		let response = Codec(Data {
+			hello: "world".into(),
+		})
+		.with_cache(Duration::from_millis(90_500))
+		.into_codec_response(ContentType::Json);
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.headers()[header::CACHE_CONTROL], "max-age=90");
+	}
+
+	#[test]
+	fn test_extension_tuple() {
+		#[derive(Clone, Debug, PartialEq)]
+		struct RequestId(u64);
+
+		let response = (
+			axum::Extension(RequestId(7)),
+			Codec(Data {
+				hello: "world".into(),
+			}),
+		)
+			.into_codec_response(ContentType::Json);
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(
+			response.extensions().get::<RequestId>(),
+			Some(&RequestId(7))
+		);
+	}
+
+	#[tokio::test]
+	async fn test_chunked_codec() {
+		use std::convert::Infallible;
+
+		use axum::body::HttpBody;
+
+		use super::ChunkedCodec;
+
+		let chunks = ["[1,", "2]"].map(Ok::<_, Infallible>);
+		let response =
+			ChunkedCodec(futures_util::stream::iter(chunks)).into_codec_response(ContentType::Json);
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(
+			response.headers()[header::CONTENT_TYPE],
+			ContentType::Json.into_header()
+		);
+		assert!(!response.headers().contains_key(header::CONTENT_LENGTH));
+		assert_eq!(response.body().size_hint().exact(), None);
+
+		let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+			.await
+			.unwrap();
+
+		assert_eq!(body, "[1,2]");
+	}
+
+	#[cfg(feature = "stream")]
+	#[tokio::test]
+	async fn test_codec_chunks() {
+		use axum::http::HeaderValue;
+
+		use super::CodecChunks;
+
+		#[crate::apply(encode, decode, crate = "crate")]
+		#[derive(Debug, PartialEq)]
+		struct Tick {
+			n: u32,
+		}
+
+		async fn encode(content_type: ContentType) -> (HeaderValue, Vec<u8>) {
+			let stream = futures_util::stream::iter((0..3).map(|n| Tick { n }));
+			let response = CodecChunks(stream).into_codec_response(content_type);
+
+			assert_eq!(response.status(), StatusCode::OK);
+
+			let header = response.headers()[header::CONTENT_TYPE].clone();
+			let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+				.await
+				.unwrap();
+
+			(header, body.to_vec())
+		}
+
+		assert_eq!(
+			encode(ContentType::Json).await,
+			(
+				HeaderValue::from_static("application/x-ndjson"),
+				b"{\"n\":0}\n{\"n\":1}\n{\"n\":2}\n".to_vec()
+			)
+		);
+
+		for &content_type in ContentType::all() {
+			if content_type == ContentType::Json {
+				continue;
+			}
+
+			let (header, mut bytes) = encode(content_type).await;
+			assert_eq!(header, content_type.into_header());
+
+			for n in 0..3 {
+				let (len, rest) = bytes.split_at(4);
+				let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+				let Codec(tick) = Codec::<Tick>::from_bytes(&rest[..len], content_type).unwrap();
+
+				assert_eq!(tick, Tick { n }, "{content_type}");
+				bytes.drain(..4 + len);
+			}
+
+			assert!(bytes.is_empty(), "{content_type}");
+		}
+	}
+
+	#[tokio::test]
+	async fn test_array_stream() {
+		use super::CodecArrayStream;
+
+		#[crate::apply(encode, decode, crate = "crate")]
+		#[derive(Debug, PartialEq)]
+		struct Row {
+			id: u32,
+		}
+
+		async fn encode(count: u32, content_type: ContentType) -> Vec<u8> {
+			let stream = futures_util::stream::iter((0..count).map(|id| Row { id }));
+			let response = CodecArrayStream(stream).into_codec_response(content_type);
+
+			assert_eq!(response.status(), StatusCode::OK);
+			assert_eq!(
+				response.headers()[header::CONTENT_TYPE],
+				content_type.into_header()
+			);
+			assert!(!response.headers().contains_key(header::CONTENT_LENGTH));
+
+			axum::body::to_bytes(response.into_body(), usize::MAX)
+				.await
+				.unwrap()
+				.to_vec()
+		}
+
+		assert_eq!(encode(0, ContentType::Json).await, b"[]");
+		assert_eq!(
+			encode(2, ContentType::Json).await,
+			b"[{\"id\":0},{\"id\":1}]"
+		);
+
+		let bytes = encode(3, ContentType::Cbor).await;
+		assert_eq!((bytes[0], bytes[bytes.len() - 1]), (0x9f, 0xff));
+
+		for content_type in [
+			ContentType::Cbor,
+			ContentType::MsgPack,
+			ContentType::Bitcode,
+		] {
+			let bytes = encode(3, content_type).await;
+			let Codec(rows) = Codec::<Vec<Row>>::from_bytes(&bytes, content_type).unwrap();
+
+			assert_eq!(rows, [Row { id: 0 }, Row { id: 1 }, Row { id: 2 }]);
+		}
+	}
+
+	#[cfg(not(feature = "aide"))]
+	#[test]
+	fn test_boxed_response() {
+		fn respond(found: bool) -> Box<dyn IntoCodecResponse + Send> {
+			if found {
+				Box::new(Codec(Data {
+					hello: "world".into(),
+				}))
+			} else {
+				Box::new(StatusCode::NOT_FOUND)
+			}
+		}
+
+		let response = respond(true).into_codec_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let response = respond(false).into_codec_response(ContentType::Json);
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[cfg(not(feature = "aide"))]
+	#[test]
+	fn test_infallible_result() {
+		let result: Result<Codec<Data>, std::convert::Infallible> = Ok(Codec(Data {
+			hello: "world".into(),
+		}));
+
+		assert_eq!(
+			result.into_codec_response(ContentType::Json).status(),
+			StatusCode::OK
+		);
+	}
 }
@@ -1,6 +1,6 @@
 use std::convert::Infallible;
 
-use axum::routing;
+use axum::routing::{self, MethodFilter};
 
 use crate::{
 	handler::{CodecHandlerFn, Input},
@@ -73,6 +73,29 @@ impl<S, E> From<MethodRouter<S, E>> for aide::axum::routing::ApiMethodRouter<S,
 	}
 }
 
+impl<S, E> MethodRouter<S, E>
+where
+	S: Clone,
+{
+	/// Merges two routers for the same path into one, such as routers for
+	/// different methods built in separate modules. See
+	/// [`axum::routing::MethodRouter::merge`] for more details.
+	///
+	/// # Panics
+	///
+	/// Panics if both routers have a route for the same method, or if both
+	/// have a fallback.
+	#[must_use]
+	#[track_caller]
+	pub fn merge<M>(mut self, other: M) -> Self
+	where
+		M: Into<Self>,
+	{
+		self.inner = self.inner.merge(other.into().inner);
+		self
+	}
+}
+
 #[cfg(not(feature = "aide"))]
 macro_rules! method_router_chain_method {
 	($name:ident, $with:ident) => {
@@ -90,6 +113,40 @@ macro_rules! method_router_chain_method {
 			self
 		}
   };
+	(@filter $name:ident, $with:ident) => {
+		#[doc = concat!("Route requests matching the given [`MethodFilter`] to the given handler. See [`axum::routing::MethodRouter::", stringify!($name) , "`] for more details.")]
+		#[must_use]
+		pub fn $name<T, H, I, D>(mut self, filter: MethodFilter, handler: H) -> Self
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + Sync + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static
+		{
+			self.inner = self.inner.$name(filter, CodecHandlerFn::new(handler));
+			self
+		}
+	};
+	(@fallback $name:ident) => {
+		#[doc = concat!("Route requests with any method to the given handler, if they are not matched by another route. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		///
+		/// This sets the fallback of the router, so it replaces a handler set
+		/// before with [`fallback`](Self::fallback) or another call to this
+		/// method.
+		#[must_use]
+		pub fn $name<T, H, I, D>(mut self, handler: H) -> Self
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + Sync + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static
+		{
+			self.inner = self.inner.fallback(CodecHandlerFn::new(handler));
+			self
+		}
+	};
 }
 
 #[cfg(feature = "aide")]
@@ -124,6 +181,88 @@ macro_rules! method_router_chain_method {
 			self
 		}
   };
+	(@filter $name:ident, $with:ident) => {
+		#[doc = concat!("Route requests matching the given [`MethodFilter`] to the given handler. See [`axum::routing::MethodRouter::", stringify!($name) , "`] for more details.")]
+		#[must_use]
+		pub fn $name<T, H, I, D>(self, filter: MethodFilter, handler: H) -> Self
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static,
+		{
+			self.$with(filter, handler, |t| t)
+		}
+
+		#[doc = concat!("Route requests matching the given [`MethodFilter`] to the given handler. See [`axum::routing::MethodRouter::", stringify!($name) , "`] for more details.")]
+		///
+		/// The transform function is applied to the operation of every method
+		/// matched by the filter.
+		#[must_use]
+		pub fn $with<T, H, I, D, F>(mut self, filter: MethodFilter, handler: H, transform: F) -> Self
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static,
+			F: Fn(aide::transform::TransformOperation) -> aide::transform::TransformOperation,
+		{
+			let contains = |method: MethodFilter| filter.or(method) == filter;
+
+			if contains(MethodFilter::DELETE) {
+				self = self.delete_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::GET) {
+				self = self.get_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::HEAD) {
+				self = self.head_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::OPTIONS) {
+				self = self.options_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::PATCH) {
+				self = self.patch_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::POST) {
+				self = self.post_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::PUT) {
+				self = self.put_with(handler.clone(), &transform);
+			}
+			if contains(MethodFilter::TRACE) {
+				self = self.trace_with(handler, &transform);
+			}
+
+			self
+		}
+	};
+	(@fallback $name:ident) => {
+		#[doc = concat!("Route requests with any method to the given handler, if they are not matched by another route. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		///
+		/// Since `OpenAPI` has no notion of a catch-all method, the handler is not
+		/// included in the generated documentation.
+		///
+		/// # Panics
+		///
+		/// Panics if the router already has a fallback, set with
+		/// [`fallback`](Self::fallback) or another call to this method. The
+		/// `aide` router has no way to replace it.
+		#[must_use]
+		pub fn $name<T, H, I, D>(mut self, handler: H) -> Self
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static,
+		{
+			self.inner = self.inner.merge(routing::$name(CodecHandlerFn::<H, I, D>::new(handler)));
+			self
+		}
+	};
 }
 
 impl<S> MethodRouter<S, Infallible>
@@ -145,6 +284,66 @@ where
 	method_router_chain_method!(put, put_with);
 
 	method_router_chain_method!(trace, trace_with);
+
+	method_router_chain_method!(@fallback any);
+
+	method_router_chain_method!(@filter on, on_with);
+
+	/// Route requests whose method is not matched by any other route to the
+	/// given handler, instead of the default empty `405 Method Not Allowed`
+	/// response. See [`axum::routing::MethodRouter::fallback`] for more details.
+	///
+	/// The response is encoded with the negotiated content type like any other
+	/// codec handler, so returning a status code alongside a
+	/// [`Message`](crate::rejection::Message) (when the `pretty-errors` feature
+	/// is enabled) keeps method errors consistent with decoding errors.
+	///
+	/// A fallback set before, with [`any`](Self::any) or another call to this
+	/// method, is replaced.
+	#[cfg(not(feature = "aide"))]
+	#[must_use]
+	pub fn fallback<T, H, I, D>(mut self, handler: H) -> Self
+	where
+		H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+		I: Input + Send + 'static,
+		D: IntoCodecResponse + Send + Sync + 'static,
+		T: 'static,
+	{
+		self.inner = self.inner.fallback(CodecHandlerFn::new(handler));
+		self
+	}
+
+	/// Route requests whose method is not matched by any other route to the
+	/// given handler, instead of the default empty `405 Method Not Allowed`
+	/// response. See [`axum::routing::MethodRouter::fallback`] for more details.
+	///
+	/// The response is encoded with the negotiated content type like any other
+	/// codec handler, so returning a status code alongside a
+	/// [`Message`](crate::rejection::Message) (when the `pretty-errors` feature
+	/// is enabled) keeps method errors consistent with decoding errors.
+	///
+	/// Like [`any`](Self::any), the fallback is not included in the generated
+	/// documentation.
+	///
+	/// # Panics
+	///
+	/// Panics if the router already has a fallback, set with
+	/// [`any`](Self::any) or another call to this method. The `aide` router has
+	/// no way to replace it.
+	#[cfg(feature = "aide")]
+	#[must_use]
+	pub fn fallback<T, H, I, D>(mut self, handler: H) -> Self
+	where
+		H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+		I: Input + Send + 'static,
+		D: IntoCodecResponse + Send + 'static,
+		T: 'static,
+	{
+		self.inner = self
+			.inner
+			.merge(routing::MethodRouter::new().fallback(CodecHandlerFn::<H, I, D>::new(handler)));
+		self
+	}
 }
 
 #[cfg(not(feature = "aide"))]
@@ -162,6 +361,32 @@ macro_rules! method_router_top_level {
 			MethodRouter::from(routing::$name(CodecHandlerFn::new(handler)))
 		}
 	};
+	(@filter $name:ident, $with:ident) => {
+		#[doc = concat!("Route requests matching the given [`MethodFilter`] to the given handler. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		pub fn $name<T, H, I, D, S>(filter: MethodFilter, handler: H) -> MethodRouter<S, Infallible>
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + Sync + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static
+		{
+			MethodRouter::from(routing::$name(filter, CodecHandlerFn::new(handler)))
+		}
+	};
+	(@fallback $name:ident) => {
+		#[doc = concat!("Route requests with any method to the given handler. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		pub fn $name<T, H, I, D, S>(handler: H) -> MethodRouter<S, Infallible>
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + Sync + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static
+		{
+			MethodRouter::from(routing::$name(CodecHandlerFn::new(handler)))
+		}
+	};
 }
 
 #[cfg(feature = "aide")]
@@ -195,6 +420,52 @@ macro_rules! method_router_top_level {
 			MethodRouter::from(aide::axum::routing::$with(CodecHandlerFn::<H, I, D>::new(handler), transform))
 		}
 	};
+	(@filter $name:ident, $with:ident) => {
+		#[doc = concat!("Route requests matching the given [`MethodFilter`] to the given handler. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		pub fn $name<T, H, I, D, S>(filter: MethodFilter, handler: H) -> MethodRouter<S, Infallible>
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static,
+		{
+			$with(filter, handler, |t| t)
+		}
+
+		#[doc = concat!("Route requests matching the given [`MethodFilter`] to the given handler. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		///
+		/// The transform function is applied to the operation of every method
+		/// matched by the filter.
+		#[must_use]
+		pub fn $with<T, H, I, D, S, F>(filter: MethodFilter, handler: H, transform: F) -> MethodRouter<S, Infallible>
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static,
+			F: Fn(aide::transform::TransformOperation) -> aide::transform::TransformOperation,
+		{
+			MethodRouter::from(aide::axum::routing::ApiMethodRouter::new()).$with(filter, handler, transform)
+		}
+	};
+	(@fallback $name:ident) => {
+		#[doc = concat!("Route requests with any method to the given handler. See [`axum::routing::", stringify!($name) , "`] for more details.")]
+		///
+		/// Since `OpenAPI` has no notion of a catch-all method, the handler is not
+		/// included in the generated documentation.
+		pub fn $name<T, H, I, D, S>(handler: H) -> MethodRouter<S, Infallible>
+		where
+			H: CodecHandler<T, I, D, S> + Clone + Send + Sync + 'static,
+			I: Input + Send + 'static,
+			D: IntoCodecResponse + Send + 'static,
+			S: Clone + Send + Sync + 'static,
+			T: 'static,
+		{
+			MethodRouter::from(routing::$name(CodecHandlerFn::<H, I, D>::new(handler)))
+		}
+	};
 }
 
 method_router_top_level!(delete, delete_with);
@@ -205,3 +476,132 @@ method_router_top_level!(patch, patch_with);
 method_router_top_level!(post, post_with);
 method_router_top_level!(put, put_with);
 method_router_top_level!(trace, trace_with);
+method_router_top_level!(@fallback any);
+method_router_top_level!(@filter on, on_with);
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		body::Body,
+		http::{header, Method, Request, StatusCode},
+		Router,
+	};
+	use tower::ServiceExt;
+
+	use super::{get, on, MethodRouter};
+
+	async fn ok() -> &'static str {
+		"ok"
+	}
+
+	async fn teapot() -> (StatusCode, &'static str) {
+		(StatusCode::IM_A_TEAPOT, "teapot")
+	}
+
+	async fn not_allowed() -> (StatusCode, &'static str) {
+		(StatusCode::METHOD_NOT_ALLOWED, "not allowed")
+	}
+
+	fn allowed(response: &axum::response::Response) -> Vec<&str> {
+		let mut methods = response.headers()[header::ALLOW]
+			.to_str()
+			.unwrap()
+			.split(',')
+			.collect::<Vec<_>>();
+
+		methods.sort_unstable();
+		methods
+	}
+
+	async fn call(router: MethodRouter, method: Method) -> axum::response::Response {
+		Router::new()
+			.route("/", router.into())
+			.oneshot(
+				Request::builder()
+					.method(method)
+					.uri("/")
+					.body(Body::empty())
+					.unwrap(),
+			)
+			.await
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_allow_header() {
+		let response = call(get(ok).post(ok), Method::PUT).await;
+
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(allowed(&response), ["GET", "HEAD", "POST"]);
+
+		let filter = axum::routing::MethodFilter::GET.or(axum::routing::MethodFilter::DELETE);
+		let response = call(on(filter, ok), Method::PUT).await;
+
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(allowed(&response), ["DELETE", "GET", "HEAD"]);
+	}
+
+	#[tokio::test]
+	async fn test_merge() {
+		let router = get(ok).merge(super::post(ok)).merge(super::delete(ok));
+
+		for method in [Method::GET, Method::POST, Method::DELETE] {
+			assert_eq!(call(router.clone(), method).await.status(), StatusCode::OK);
+		}
+
+		let response = call(router, Method::PUT).await;
+
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(allowed(&response), ["DELETE", "GET", "HEAD", "POST"]);
+	}
+
+	#[tokio::test]
+	async fn test_fallback_keeps_allow_header() {
+		let response = call(get(ok).fallback(not_allowed), Method::PUT).await;
+
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(allowed(&response), ["GET", "HEAD"]);
+	}
+
+	#[tokio::test]
+	async fn test_any_with_other_methods() {
+		let router = get(ok).any(teapot).post(ok);
+
+		for method in [Method::GET, Method::POST] {
+			assert_eq!(call(router.clone(), method).await.status(), StatusCode::OK);
+		}
+
+		assert_eq!(
+			call(router, Method::PUT).await.status(),
+			StatusCode::IM_A_TEAPOT
+		);
+
+		#[cfg(not(feature = "aide"))]
+		assert_eq!(
+			call(super::any(ok).any(teapot), Method::PUT).await.status(),
+			StatusCode::IM_A_TEAPOT
+		);
+	}
+
+	#[tokio::test]
+	async fn test_fallback_with_other_methods() {
+		let router = get(ok).fallback(teapot).post(ok);
+
+		for method in [Method::GET, Method::POST] {
+			assert_eq!(call(router.clone(), method).await.status(), StatusCode::OK);
+		}
+
+		assert_eq!(
+			call(router, Method::PUT).await.status(),
+			StatusCode::IM_A_TEAPOT
+		);
+
+		#[cfg(not(feature = "aide"))]
+		assert_eq!(
+			call(get(ok).fallback(not_allowed).fallback(teapot), Method::PUT)
+				.await
+				.status(),
+			StatusCode::IM_A_TEAPOT
+		);
+	}
+}
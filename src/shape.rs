@@ -0,0 +1,260 @@
+#[cfg(feature = "serde")]
+use core::fmt;
+
+/// The structural kind of a value at the top level, used to skip formats that
+/// cannot represent it during negotiation.
+///
+/// See [`ContentType::supports_shape`](crate::ContentType::supports_shape) and
+/// [`Accept::content_type_for`](crate::Accept::content_type_for).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+	/// A struct, map or enum variant with data.
+	Map,
+	/// A sequence, tuple or tuple struct.
+	Sequence,
+	/// Anything else, such as a number, string or unit variant.
+	Scalar,
+}
+
+#[cfg(feature = "serde")]
+impl Shape {
+	/// Determines the shape of a value from its [`serde::Serialize`]
+	/// implementation.
+	///
+	/// Only the outermost layer is inspected, so this is cheap even for large
+	/// values. Newtypes and `Some` are transparent.
+	///
+	/// ```edition2021
+	/// # use axum_codec::Shape;
+	/// #
+	/// # fn main() {
+	/// assert_eq!(Shape::of(&vec![1, 2, 3]), Shape::Sequence);
+	/// assert_eq!(Shape::of(&Some(1)), Shape::Scalar);
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn of<T: serde::Serialize + ?Sized>(value: &T) -> Self {
+		match value.serialize(Probe) {
+			Ok(shape) | Err(Found(shape)) => shape,
+		}
+	}
+}
+
+/// Error used by [`Probe`] to stop as soon as the shape is known.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+struct Found(Shape);
+
+#[cfg(feature = "serde")]
+impl fmt::Display for Found {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "value has the shape {:?}", self.0)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for Found {}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Found {
+	fn custom<T: fmt::Display>(_msg: T) -> Self {
+		Self(Shape::Scalar)
+	}
+}
+
+/// A serializer that only records the shape of the outermost value.
+#[cfg(feature = "serde")]
+struct Probe;
+
+#[cfg(feature = "serde")]
+impl serde::Serializer for Probe {
+	type Error = Found;
+	type Ok = Shape;
+	type SerializeMap = serde::ser::Impossible<Shape, Found>;
+	type SerializeSeq = serde::ser::Impossible<Shape, Found>;
+	type SerializeStruct = serde::ser::Impossible<Shape, Found>;
+	type SerializeStructVariant = serde::ser::Impossible<Shape, Found>;
+	type SerializeTuple = serde::ser::Impossible<Shape, Found>;
+	type SerializeTupleStruct = serde::ser::Impossible<Shape, Found>;
+	type SerializeTupleVariant = serde::ser::Impossible<Shape, Found>;
+
+	fn serialize_bool(self, _v: bool) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_i8(self, _v: i8) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_i16(self, _v: i16) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_i32(self, _v: i32) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_i64(self, _v: i64) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_u8(self, _v: u8) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_u16(self, _v: u16) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_u32(self, _v: u32) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_u64(self, _v: u64) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_f32(self, _v: f32) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_f64(self, _v: f64) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_char(self, _v: char) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_str(self, _v: &str) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_bytes(self, _v: &[u8]) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_none(self) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_some<T: serde::Serialize + ?Sized>(self, value: &T) -> Result<Shape, Found> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+	) -> Result<Shape, Found> {
+		Ok(Shape::Scalar)
+	}
+
+	fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Shape, Found> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<Shape, Found> {
+		Ok(Shape::Map)
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Found> {
+		Err(Found(Shape::Sequence))
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Found> {
+		Err(Found(Shape::Sequence))
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleStruct, Found> {
+		Err(Found(Shape::Sequence))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant, Found> {
+		Err(Found(Shape::Map))
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Found> {
+		Err(Found(Shape::Map))
+	}
+
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Found> {
+		Err(Found(Shape::Map))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStructVariant, Found> {
+		Err(Found(Shape::Map))
+	}
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+	use std::collections::BTreeMap;
+
+	use super::Shape;
+
+	#[crate::apply(encode, crate = "crate")]
+	struct Wrapper(Vec<u8>);
+
+	#[crate::apply(encode, crate = "crate")]
+	struct User {
+		name: String,
+	}
+
+	#[crate::apply(encode, crate = "crate")]
+	enum Event {
+		Ping,
+		Message(String),
+	}
+
+	#[test]
+	fn test_shape_of() {
+		assert_eq!(Shape::of(&User { name: "a".into() }), Shape::Map);
+		assert_eq!(Shape::of(&BTreeMap::<u8, u8>::new()), Shape::Map);
+		assert_eq!(Shape::of(&Event::Message("a".into())), Shape::Map);
+		assert_eq!(Shape::of(&Wrapper(vec![1])), Shape::Sequence);
+		assert_eq!(Shape::of(&(1, 2)), Shape::Sequence);
+		assert_eq!(Shape::of(&Event::Ping), Shape::Scalar);
+		assert_eq!(Shape::of("a"), Shape::Scalar);
+		assert_eq!(Shape::of(&None::<User>), Shape::Scalar);
+	}
+}
@@ -0,0 +1,275 @@
+//! Decoding request bodies that are too large to hold in memory.
+
+use core::{future::poll_fn, pin::Pin};
+use std::{
+	io::{Seek, SeekFrom},
+	ops::{Deref, DerefMut},
+};
+
+use axum::{
+	extract::{FromRequest, FromRequestParts, Request},
+	response::Response,
+};
+use futures_core::Stream;
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+	decode::io_rejection, Accept, Codec, CodecDecode, CodecRejection, ContentType, IntoCodecResponse,
+};
+
+/// The default number of bytes kept in memory before a body is written to a
+/// temporary file, matching the default body limit used by axum.
+pub const DEFAULT_THRESHOLD: usize = 2 * 1024 * 1024;
+
+/// Extractor for request bodies that may be too large to hold in memory, such
+/// as multi-gigabyte imports.
+///
+/// Bodies up to `THRESHOLD` bytes are buffered and decoded like [`Codec`].
+/// Larger bodies are written to a temporary file as they arrive and then
+/// decoded from the file with [`Codec::from_reader`] on a blocking thread, so
+/// memory use is bounded for formats that can be decoded from a reader (JSON,
+/// `MessagePack` and Bincode). The file is removed once decoding finishes.
+///
+/// Since the body is never collected into memory, the limit set with
+/// [`DefaultBodyLimit`](axum::extract::DefaultBodyLimit) does not apply. Use a
+/// layer such as `tower_http::limit::RequestBodyLimitLayer` to bound the size
+/// of the file instead.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::spool::SpooledCodec;
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Import {
+///   rows: Vec<Vec<String>>,
+/// }
+///
+/// // Spool bodies larger than 64 MiB to disk.
+/// async fn import(SpooledCodec(import): SpooledCodec<Import, { 64 * 1024 * 1024 }>) -> String {
+///   format!("imported {} rows", import.rows.len())
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct SpooledCodec<T, const THRESHOLD: usize = DEFAULT_THRESHOLD>(pub T);
+
+impl<T, const THRESHOLD: usize> SpooledCodec<T, THRESHOLD> {
+	/// Consumes the [`SpooledCodec`] and returns the inner value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T, const THRESHOLD: usize> Deref for SpooledCodec<T, THRESHOLD> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, const THRESHOLD: usize> DerefMut for SpooledCodec<T, THRESHOLD> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+#[axum::async_trait]
+impl<T, S, const THRESHOLD: usize> FromRequest<S> for SpooledCodec<T, THRESHOLD>
+where
+	T: CodecDecode + Send + 'static,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+		let (mut parts, body) = req.into_parts();
+		let accept = Accept::from_request_parts(&mut parts, state).await.unwrap();
+
+		ContentType::ensure_unambiguous(&parts.headers)
+			.map_err(|e| e.into_codec_response(accept.into()))?;
+
+		let content_type = ContentType::from_request_headers(&parts.headers, &parts.extensions);
+
+		spool::<T, THRESHOLD>(body.into_data_stream(), content_type)
+			.await
+			.map(Self)
+			.map_err(|e| e.into_codec_response(accept.into()))
+	}
+}
+
+/// Reads the body into memory, switching to a temporary file once it grows
+/// past `THRESHOLD` bytes, and decodes it.
+async fn spool<T, const THRESHOLD: usize>(
+	mut body: axum::body::BodyDataStream,
+	content_type: ContentType,
+) -> Result<T, CodecRejection>
+where
+	T: CodecDecode + Send + 'static,
+{
+	let mut buffer = Vec::new();
+	let mut len = 0;
+
+	let overflow = loop {
+		let Some(chunk) = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await else {
+			break None;
+		};
+		let chunk = chunk.map_err(CodecRejection::Body)?;
+		len += chunk.len();
+
+		if buffer.len() + chunk.len() > THRESHOLD {
+			break Some(chunk);
+		}
+
+		buffer.extend_from_slice(&chunk);
+	};
+
+	let Some(chunk) = overflow else {
+		#[cfg(feature = "metrics")]
+		crate::metrics::record_decode(content_type, len);
+
+		let result = Codec::from_bytes(&buffer, content_type).map(|Codec(data)| data);
+
+		#[cfg(feature = "metrics")]
+		crate::metrics::record_outcome(content_type, &result);
+
+		return result;
+	};
+
+	let mut file = tokio::fs::File::from_std(
+		tokio::task::spawn_blocking(tempfile::tempfile)
+			.await
+			.unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+			.map_err(io_rejection)?,
+	);
+
+	file.write_all(&buffer).await.map_err(io_rejection)?;
+	file.write_all(&chunk).await.map_err(io_rejection)?;
+	drop(buffer);
+
+	while let Some(chunk) = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await {
+		let chunk = chunk.map_err(CodecRejection::Body)?;
+		len += chunk.len();
+
+		file.write_all(&chunk).await.map_err(io_rejection)?;
+	}
+
+	#[cfg(feature = "metrics")]
+	crate::metrics::record_decode(content_type, len);
+	#[cfg(not(feature = "metrics"))]
+	let _ = len;
+
+	file.flush().await.map_err(io_rejection)?;
+	let mut file = file.into_std().await;
+
+	let result = tokio::task::spawn_blocking(move || {
+		file.seek(SeekFrom::Start(0)).map_err(io_rejection)?;
+
+		Codec::from_reader(file, content_type).map(|Codec(data)| data)
+	})
+	.await
+	.unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()));
+
+	#[cfg(feature = "metrics")]
+	crate::metrics::record_outcome(content_type, &result);
+
+	result
+}
+
+#[cfg(feature = "aide")]
+impl<T, const THRESHOLD: usize> aide::operation::OperationInput for SpooledCodec<T, THRESHOLD>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<T>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<T>::inferred_early_responses(ctx, operation)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use axum::{
+		body::Body,
+		extract::FromRequest,
+		http::{header, Request, StatusCode},
+	};
+
+	use super::SpooledCodec;
+	use crate::{Codec, ContentType};
+
+	#[crate::apply(encode, decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Import {
+		rows: Vec<String>,
+	}
+
+	fn import() -> Import {
+		Import {
+			rows: (0..100).map(|i| format!("row {i}")).collect(),
+		}
+	}
+
+	fn request(content_type: ContentType, body: Vec<u8>) -> Request<Body> {
+		Request::builder()
+			.header(header::CONTENT_TYPE, content_type.as_str())
+			.body(Body::from(body))
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_spooled_codec() {
+		for &content_type in ContentType::all() {
+			let bytes = Codec(import()).to_bytes(content_type).unwrap();
+
+			let Ok(SpooledCodec(decoded)) =
+				SpooledCodec::<Import, 16>::from_request(request(content_type, bytes.clone()), &()).await
+			else {
+				panic!("expected the spooled {content_type} body to decode");
+			};
+
+			assert_eq!(decoded, import(), "{content_type}");
+
+			let Ok(SpooledCodec(decoded)) =
+				SpooledCodec::<Import>::from_request(request(content_type, bytes), &()).await
+			else {
+				panic!("expected the buffered {content_type} body to decode");
+			};
+
+			assert_eq!(decoded, import(), "{content_type}");
+		}
+	}
+
+	#[tokio::test]
+	async fn test_spooled_json() {
+		let mut bytes = b"\xef\xbb\xbf{\"rows\":[".to_vec();
+		bytes.extend_from_slice(&b"\"row\",".repeat(10));
+		bytes.extend_from_slice(b"\"row\"]}");
+
+		let Ok(SpooledCodec(decoded)) =
+			SpooledCodec::<Import, 16>::from_request(request(ContentType::Json, bytes), &()).await
+		else {
+			panic!("expected the body to decode");
+		};
+
+		assert_eq!(decoded.rows.len(), 11);
+
+		let Err(response) = SpooledCodec::<Import, 16>::from_request(
+			request(ContentType::Json, b"{\"rows\":[\"unterminated\"".to_vec()),
+			&(),
+		)
+		.await
+		else {
+			panic!("expected the body to be rejected");
+		};
+
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	}
+}
@@ -0,0 +1,105 @@
+//! Encoding [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+//! with the same formats as [`Codec`].
+
+use axum::response::sse::Event;
+use base64::Engine;
+
+use crate::{encode, Codec, CodecEncode, ContentType};
+
+/// Creates a server-sent event with the value encoded in the given format as
+/// its data.
+///
+/// SSE is a text protocol, so the output of text formats (JSON, YAML, TOML
+/// and RON) is used as is, while every other format is encoded with standard
+/// base64. Multi-line output, such as YAML, is split over multiple `data:`
+/// lines, which clients join back together.
+///
+/// ```edition2021
+/// # use axum::response::sse::{Event, Sse};
+/// # use axum_codec::{sse, ContentType};
+/// # use std::convert::Infallible;
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Tick {
+///   count: u64,
+/// }
+///
+/// async fn ticks() -> Sse<impl futures_core::Stream<Item = Result<Event, Infallible>>> {
+///   let event = sse::codec_event(Tick { count: 1 }, ContentType::Json).unwrap();
+///
+///   Sse::new(futures_util::stream::iter([Ok(event.event("tick"))]))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+///
+/// # Errors
+///
+/// See [`encode::Error`].
+pub fn codec_event<T>(value: T, content_type: ContentType) -> Result<Event, encode::Error>
+where
+	T: CodecEncode,
+{
+	let bytes = Codec(value).to_bytes(content_type)?;
+
+	let data = if content_type.is_text() {
+		String::from_utf8(bytes)
+			.unwrap_or_else(|e| base64::engine::general_purpose::STANDARD.encode(e.into_bytes()))
+	} else {
+		base64::engine::general_purpose::STANDARD.encode(bytes)
+	};
+
+	Ok(Event::default().data(data))
+}
+
+#[cfg(test)]
+mod test {
+	use std::convert::Infallible;
+
+	use axum::{
+		body::to_bytes,
+		response::{
+			sse::{Event, Sse},
+			IntoResponse,
+		},
+	};
+	use base64::Engine;
+
+	use super::codec_event;
+	use crate::{Codec, ContentType};
+
+	#[crate::apply(encode, decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Tick {
+		count: u64,
+	}
+
+	async fn render(event: Event) -> String {
+		let response =
+			Sse::new(futures_util::stream::iter([Ok::<_, Infallible>(event)])).into_response();
+		let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+		String::from_utf8(bytes.to_vec()).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_codec_event() {
+		let event = codec_event(Tick { count: 1 }, ContentType::Json).unwrap();
+
+		assert_eq!(render(event).await, "data: {\"count\":1}\n\n");
+
+		let event = codec_event(Tick { count: 1 }, ContentType::Cbor).unwrap();
+		let rendered = render(event).await;
+		let data = rendered
+			.strip_prefix("data: ")
+			.and_then(|data| data.strip_suffix("\n\n"))
+			.unwrap();
+
+		let bytes = base64::engine::general_purpose::STANDARD
+			.decode(data)
+			.unwrap();
+		let Codec(decoded) = Codec::<Tick>::from_cbor(&bytes).unwrap();
+
+		assert_eq!(decoded, Tick { count: 1 });
+	}
+}
@@ -0,0 +1,558 @@
+use core::{
+	marker::PhantomData,
+	ops::Range,
+	pin::Pin,
+	task::{ready, Context, Poll},
+};
+
+use axum::{
+	body::{Body, BodyDataStream},
+	extract::{FromRequest, Request},
+	http::{header, StatusCode},
+	response::{IntoResponse, Response},
+};
+use futures_core::Stream;
+
+use crate::{Accept, Codec, CodecDecode, CodecRejection, ContentType, IntoCodecResponse};
+
+/// The default maximum size of a single item, matching the default body limit
+/// used by axum.
+const DEFAULT_ITEM_LIMIT: usize = 2 * 1024 * 1024;
+
+/// The record separator that starts every item in a JSON text sequence.
+pub(crate) const RECORD_SEPARATOR: u8 = 0x1e;
+
+/// Streaming extractor for large JSON uploads.
+///
+/// Instead of buffering the whole body like [`Codec`], the body is read
+/// incrementally and each item is decoded (and validated, if the `validator`
+/// feature is enabled) as soon as it has been received, so memory use is
+/// bounded by the size of a single item rather than the whole request.
+///
+/// Two body formats are supported, chosen by the `Content-Type` header:
+/// - [NDJSON](https://github.com/ndjson/ndjson-spec) (`application/x-ndjson`,
+///   `application/ndjson` or `application/jsonl`), with one item per line.
+///   Blank lines are skipped.
+/// - [JSON text sequences](https://www.rfc-editor.org/rfc/rfc7464)
+///   (`application/json-seq`), with each item preceded by a record separator
+///   (`0x1E`). Empty records are skipped.
+/// - A JSON array (`application/json`, or no `Content-Type` at all), with one
+///   item per element.
+///
+/// Any other content type is rejected with `415 Unsupported Media Type`.
+///
+/// The stream ends after the first error. Since the body limit enforced by
+/// axum does not apply to streamed bodies, each item is instead limited to
+/// 2 MiB by default, which can be changed with
+/// [`CodecStream::with_item_limit`].
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::{CodecRejection, CodecStream};
+/// # use futures_util::StreamExt;
+/// #
+/// #[axum_codec::apply(decode)]
+/// struct Row {
+///   id: u32,
+/// }
+///
+/// async fn import(mut rows: CodecStream<Row>) -> Result<String, CodecRejection> {
+///   let mut count = 0;
+///
+///   while let Some(row) = rows.next().await {
+///     let _row = row?;
+///     count += 1;
+///   }
+///
+///   Ok(format!("imported {count} rows"))
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub struct CodecStream<T> {
+	body: BodyDataStream,
+	buffer: Vec<u8>,
+	format: Format,
+	scanner: ArrayScanner,
+	limit: usize,
+	eof: bool,
+	done: bool,
+	_marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+	NdJson,
+	JsonSeq,
+	Array,
+}
+
+impl Format {
+	fn from_mime(mime: &mime::Mime) -> Option<Self> {
+		let subtype = mime.suffix().unwrap_or_else(|| mime.subtype());
+
+		match (mime.type_().as_str(), subtype.as_str()) {
+			("application", "x-ndjson" | "ndjson" | "jsonl" | "x-jsonlines") => Some(Self::NdJson),
+			("application", "json-seq") => Some(Self::JsonSeq),
+			("application", "json") => Some(Self::Array),
+			_ => None,
+		}
+	}
+}
+
+impl<T> CodecStream<T>
+where
+	T: CodecDecode,
+{
+	/// Creates a stream that decodes items from the given body.
+	fn new(body: Body, format: Format) -> Self {
+		Self {
+			body: body.into_data_stream(),
+			buffer: Vec::new(),
+			format,
+			scanner: ArrayScanner::default(),
+			limit: DEFAULT_ITEM_LIMIT,
+			eof: false,
+			done: false,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Sets the maximum size of a single item in bytes.
+	///
+	/// If an item grows past the limit before it is complete, the stream yields
+	/// [`CodecRejection::PayloadTooLarge`] and ends.
+	#[must_use]
+	pub fn with_item_limit(mut self, limit: usize) -> Self {
+		self.limit = limit;
+		self
+	}
+
+	/// Decodes the next complete item in the buffer, if there is one.
+	fn next_item(&mut self) -> Option<Result<T, CodecRejection>> {
+		let range = match self.format {
+			Format::NdJson => loop {
+				let Some(newline) = self.buffer[self.scanner.pos..]
+					.iter()
+					.position(|&b| b == b'\n')
+				else {
+					self.scanner.pos = self.buffer.len();
+					return None;
+				};
+				let line = 0..self.scanner.pos + newline;
+
+				if self.buffer[line.clone()].trim_ascii().is_empty() {
+					self.buffer.drain(..=line.end);
+					self.scanner.pos = 0;
+				} else {
+					break line;
+				}
+			},
+			// A record is only complete once the separator of the next one arrives.
+			Format::JsonSeq => loop {
+				let start = usize::from(self.buffer.first() == Some(&RECORD_SEPARATOR));
+				let from = self.scanner.pos.max(start);
+				let Some(separator) = self.buffer[from..]
+					.iter()
+					.position(|&b| b == RECORD_SEPARATOR)
+				else {
+					self.scanner.pos = self.buffer.len();
+					return None;
+				};
+				let record = start..from + separator;
+
+				if self.buffer[record.clone()].trim_ascii().is_empty() {
+					self.buffer.drain(..record.end);
+					self.scanner.pos = 0;
+				} else {
+					break record;
+				}
+			},
+			Format::Array => match self.scanner.scan(&self.buffer) {
+				Ok(Scan::Element(range)) => range,
+				Ok(Scan::Pending) => return None,
+				Err(err) => return Some(Err(err.into())),
+			},
+		};
+
+		let item = decode(&self.buffer[range.clone()]);
+		let consumed = match self.format {
+			Format::NdJson => range.end + 1,
+			Format::JsonSeq | Format::Array => range.end,
+		};
+
+		self.buffer.drain(..consumed);
+		self.scanner.pos -= consumed.min(self.scanner.pos);
+
+		Some(item)
+	}
+
+	/// Handles whatever is left in the buffer once the body has ended.
+	fn finish(&mut self) -> Option<Result<T, CodecRejection>> {
+		let rest = self.buffer.trim_ascii();
+
+		match self.format {
+			Format::NdJson if rest.is_empty() => None,
+			Format::NdJson => Some(decode(rest)),
+			Format::JsonSeq => {
+				let rest = rest
+					.strip_prefix(&[RECORD_SEPARATOR])
+					.unwrap_or(rest)
+					.trim_ascii();
+
+				(!rest.is_empty()).then(|| decode(rest))
+			}
+			Format::Array if !self.scanner.is_finished() => Some(Err(
+				json_error("unexpected end of input while parsing a JSON array").into(),
+			)),
+			Format::Array if !self.buffer[self.scanner.pos..].trim_ascii().is_empty() => Some(Err(
+				json_error("trailing characters after the JSON array").into(),
+			)),
+			Format::Array => None,
+		}
+	}
+}
+
+fn decode<T: CodecDecode>(bytes: &[u8]) -> Result<T, CodecRejection> {
+	Codec::<T>::from_bytes(bytes, ContentType::Json).map(|Codec(item)| item)
+}
+
+fn json_error(message: &str) -> serde_json::Error {
+	<serde_json::Error as serde::de::Error>::custom(message)
+}
+
+impl<T> Stream for CodecStream<T>
+where
+	T: CodecDecode,
+{
+	type Item = Result<T, CodecRejection>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+
+		loop {
+			if this.done {
+				return Poll::Ready(None);
+			}
+
+			if let Some(item) = this.next_item() {
+				this.done = item.is_err();
+				return Poll::Ready(Some(item));
+			}
+
+			if this.eof {
+				this.done = true;
+				return Poll::Ready(this.finish());
+			}
+
+			if this.buffer.len() > this.limit {
+				this.done = true;
+				return Poll::Ready(Some(Err(CodecRejection::PayloadTooLarge {
+					limit: this.limit,
+				})));
+			}
+
+			match ready!(Pin::new(&mut this.body).poll_next(cx)) {
+				Some(Ok(bytes)) => this.buffer.extend_from_slice(&bytes),
+				Some(Err(err)) => {
+					this.done = true;
+					return Poll::Ready(Some(Err(CodecRejection::Body(err))));
+				}
+				None => this.eof = true,
+			}
+		}
+	}
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for CodecStream<T>
+where
+	T: CodecDecode,
+	S: Send + Sync + 'static,
+{
+	type Rejection = Response;
+
+	async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+		ContentType::ensure_unambiguous(req.headers()).map_err(|e| {
+			e.into_codec_response(
+				Accept::from_request_head(req.uri(), req.headers(), req.extensions()).into(),
+			)
+		})?;
+
+		let format = match req.headers().get(header::CONTENT_TYPE) {
+			None => Format::Array,
+			Some(value) => value
+				.to_str()
+				.ok()
+				.and_then(|value| value.parse::<mime::Mime>().ok())
+				.as_ref()
+				.and_then(Format::from_mime)
+				.ok_or_else(|| StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response())?,
+		};
+
+		Ok(Self::new(req.into_body(), format))
+	}
+}
+
+#[cfg(feature = "aide")]
+impl<T> aide::operation::OperationInput for CodecStream<T>
+where
+	T: schemars::JsonSchema,
+{
+	fn operation_input(ctx: &mut aide::gen::GenContext, operation: &mut aide::openapi::Operation) {
+		axum::Json::<Vec<T>>::operation_input(ctx, operation);
+	}
+
+	fn inferred_early_responses(
+		ctx: &mut aide::gen::GenContext,
+		operation: &mut aide::openapi::Operation,
+	) -> Vec<(Option<u16>, aide::openapi::Response)> {
+		axum::Json::<Vec<T>>::inferred_early_responses(ctx, operation)
+	}
+}
+
+enum Scan {
+	/// A complete element was found at the given range of the buffer.
+	Element(Range<usize>),
+	/// More input is needed, or the array has ended.
+	Pending,
+}
+
+/// Finds the boundaries of the elements of a JSON array without parsing them.
+///
+/// Only strings and nesting are tracked, which is enough to find the commas
+/// and the closing bracket at the top level. The elements themselves are
+/// validated by `serde_json` once they are complete.
+#[derive(Default)]
+struct ArrayScanner {
+	/// Position in the buffer up to which the input has been scanned.
+	pos: usize,
+	state: State,
+	element: Option<Element>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum State {
+	/// Expecting the opening bracket.
+	#[default]
+	Start,
+	/// Expecting the first element or the closing bracket.
+	First,
+	/// Expecting an element after a comma.
+	Next,
+	/// Expecting a comma or the closing bracket.
+	AfterElement,
+	/// The closing bracket has been seen.
+	Finished,
+}
+
+#[derive(Default)]
+struct Element {
+	start: usize,
+	depth: usize,
+	in_string: bool,
+	escaped: bool,
+}
+
+impl ArrayScanner {
+	fn is_finished(&self) -> bool {
+		self.state == State::Finished
+	}
+
+	fn scan(&mut self, buffer: &[u8]) -> Result<Scan, serde_json::Error> {
+		while let Some(&byte) = buffer.get(self.pos) {
+			if let Some(element) = &mut self.element {
+				if element.in_string {
+					if element.escaped {
+						element.escaped = false;
+					} else if byte == b'\\' {
+						element.escaped = true;
+					} else if byte == b'"' {
+						element.in_string = false;
+					}
+				} else {
+					match byte {
+						b'"' => element.in_string = true,
+						b'[' | b'{' => element.depth += 1,
+						b']' | b'}' if element.depth > 0 => element.depth -= 1,
+						b']' | b',' if element.depth == 0 => {
+							let range = element.start..self.pos;
+
+							self.element = None;
+							self.state = State::AfterElement;
+							return Ok(Scan::Element(range));
+						}
+						_ => {}
+					}
+				}
+			} else if !byte.is_ascii_whitespace() {
+				self.state = match (self.state, byte) {
+					(State::Finished, _) => return Ok(Scan::Pending),
+					(State::Start, b'[') => State::First,
+					(State::Start, _) => return Err(json_error("expected a JSON array")),
+					(State::First | State::AfterElement, b']') => State::Finished,
+					(State::AfterElement, b',') => State::Next,
+					(State::AfterElement, _) => return Err(json_error("expected `,` or `]`")),
+					(State::Next, b']') => return Err(json_error("trailing comma in JSON array")),
+					(State::First | State::Next, _) => {
+						self.element = Some(Element {
+							start: self.pos,
+							..Element::default()
+						});
+
+						// the first byte of the element still needs to be tracked
+						continue;
+					}
+				};
+			}
+
+			self.pos += 1;
+		}
+
+		Ok(Scan::Pending)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use axum::{body::Body, extract::FromRequest, http::Request};
+	use futures_util::StreamExt;
+
+	use super::CodecStream;
+	use crate::CodecRejection;
+
+	#[crate::apply(decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Item {
+		id: u32,
+		name: String,
+	}
+
+	fn item(id: u32, name: &str) -> Item {
+		Item {
+			id,
+			name: name.into(),
+		}
+	}
+
+	async fn collect(
+		content_type: &str,
+		chunks: Vec<&'static str>,
+	) -> Vec<Result<Item, CodecRejection>> {
+		let body = Body::from_stream(futures_util::stream::iter(
+			chunks.into_iter().map(Ok::<_, core::convert::Infallible>),
+		));
+		let request = Request::builder()
+			.header("content-type", content_type)
+			.body(body)
+			.unwrap();
+
+		let Ok(stream) = CodecStream::<Item>::from_request(request, &()).await else {
+			panic!("failed to extract stream");
+		};
+
+		stream.collect().await
+	}
+
+	#[tokio::test]
+	async fn test_ndjson() {
+		let items = collect("application/x-ndjson", vec![
+			"{\"id\":1,\"name\":\"a\"}\n{\"id\"",
+			":2,\"name\":\"b\\n\"}\n\n",
+			"{\"id\":3,\"name\":\"c\"}",
+		])
+		.await;
+
+		let items = items.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+		assert_eq!(items, vec![item(1, "a"), item(2, "b\n"), item(3, "c")]);
+	}
+
+	#[tokio::test]
+	async fn test_json_seq() {
+		let items = collect("application/json-seq", vec![
+			"\u{1e}{\"id\":1,\"name\":\"a\"}\n\u{1e}\u{1e}\n\u{1e}{\"id\"",
+			":2,\"name\":\"b\"}\n\u{1e}",
+			"{\"id\":3,\"name\":\"c\"}\n",
+		])
+		.await;
+
+		let items = items.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+		assert_eq!(items, vec![item(1, "a"), item(2, "b"), item(3, "c")]);
+
+		assert!(collect("application/json-seq", vec![""]).await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_json_array() {
+		let items = collect("application/json", vec![
+			" [ {\"id\":1,\"name\":\"a],\\\"\"}",
+			", {\"id\":2,",
+			"\"name\":\"{[\"} ] \n",
+		])
+		.await;
+
+		let items = items.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+		assert_eq!(items, vec![item(1, "a],\""), item(2, "{[")]);
+
+		assert!(collect("application/json", vec!["[]"]).await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_errors_end_stream() {
+		for body in [
+			"[{\"id\":1,\"name\":\"a\"}",
+			"[{\"id\":1,\"name\":\"a\"},]",
+			"[{\"id\":1,\"name\":\"a\"}] x",
+			"{\"id\":1,\"name\":\"a\"}",
+		] {
+			let items = collect("application/json", vec![body]).await;
+
+			assert!(
+				matches!(items.last(), Some(Err(CodecRejection::Json(..)))),
+				"{body}"
+			);
+		}
+
+		let items = collect("application/x-ndjson", vec![
+			"{\"id\":1,\"name\":\"a\"}\n{\"id\":\"2\"}\n{\"id\":3,\"name\":\"c\"}\n",
+		])
+		.await;
+
+		assert_eq!(items.len(), 2);
+		assert!(matches!(items[1], Err(CodecRejection::Json(..))));
+	}
+
+	#[tokio::test]
+	async fn test_item_limit() {
+		let body = Body::from("[{\"id\":1,\"name\":\"a long name");
+		let request = Request::builder().body(body).unwrap();
+
+		let Ok(stream) = CodecStream::<Item>::from_request(request, &()).await else {
+			panic!("failed to extract stream");
+		};
+
+		let items = stream.with_item_limit(8).collect::<Vec<_>>().await;
+
+		assert!(matches!(items.as_slice(), [Err(
+			CodecRejection::PayloadTooLarge { limit: 8 }
+		)]));
+	}
+
+	#[tokio::test]
+	async fn test_unsupported_content_type() {
+		let request = Request::builder()
+			.header("content-type", "application/msgpack")
+			.body(Body::empty())
+			.unwrap();
+
+		let Err(response) = CodecStream::<Item>::from_request(request, &()).await else {
+			panic!("expected a rejection");
+		};
+
+		assert_eq!(response.status(), 415);
+	}
+}
@@ -0,0 +1,145 @@
+//! Helpers for testing types against every enabled format.
+
+use core::fmt;
+
+use crate::{Codec, CodecDecode, CodecEncode, ContentType};
+
+/// The outcome of [`check_roundtrip`] for each enabled format.
+#[derive(Debug, Default)]
+pub struct RoundtripReport {
+	passed: Vec<ContentType>,
+	failed: Vec<(ContentType, String)>,
+}
+
+impl RoundtripReport {
+	/// Returns the formats that the value survived.
+	#[must_use]
+	pub fn passed(&self) -> &[ContentType] {
+		&self.passed
+	}
+
+	/// Returns the formats that the value did not survive, along with a
+	/// description of what went wrong.
+	#[must_use]
+	pub fn failed(&self) -> &[(ContentType, String)] {
+		&self.failed
+	}
+
+	/// Returns `true` if the value survived every format.
+	#[must_use]
+	pub fn is_ok(&self) -> bool {
+		self.failed.is_empty()
+	}
+}
+
+impl fmt::Display for RoundtripReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for content_type in &self.passed {
+			writeln!(f, "{content_type}: ok")?;
+		}
+
+		for (content_type, reason) in &self.failed {
+			writeln!(f, "{content_type}: {reason}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Encodes and decodes the value with every format in [`ContentType::all`],
+/// reporting which formats produced an equal value.
+pub fn check_roundtrip<T>(value: T) -> RoundtripReport
+where
+	T: CodecEncode + CodecDecode + PartialEq + fmt::Debug,
+{
+	let value = Codec(value);
+	let mut report = RoundtripReport::default();
+
+	for &content_type in ContentType::all() {
+		let result = value
+			.to_bytes(content_type)
+			.map_err(|e| format!("failed to encode: {e}"))
+			.and_then(|bytes| {
+				Codec::<T>::from_bytes(&bytes, content_type).map_err(|e| format!("failed to decode: {e}"))
+			})
+			.and_then(|Codec(decoded)| {
+				if decoded == value.0 {
+					Ok(())
+				} else {
+					Err(format!("decoded {decoded:?}, expected {:?}", value.0))
+				}
+			});
+
+		match result {
+			Ok(()) => report.passed.push(content_type),
+			Err(reason) => report.failed.push((content_type, reason)),
+		}
+	}
+
+	report
+}
+
+/// Asserts that the value survives encoding and decoding with every format in
+/// [`ContentType::all`], returning the report.
+///
+/// # Panics
+///
+/// Panics if the value does not round-trip through any of the formats, listing
+/// the outcome of each one.
+///
+/// # Examples
+///
+/// ```edition2021
+/// # use axum_codec::test_util::assert_roundtrip;
+/// #
+/// #[axum_codec::apply(encode, decode)]
+/// #[derive(Debug, PartialEq)]
+/// struct User {
+///   name: String,
+///   age: u8,
+/// }
+///
+/// # fn main() {
+/// assert_roundtrip(User {
+///   name: "Alice".into(),
+///   age: 42,
+/// });
+/// # }
+/// ```
+#[track_caller]
+pub fn assert_roundtrip<T>(value: T) -> RoundtripReport
+where
+	T: CodecEncode + CodecDecode + PartialEq + fmt::Debug,
+{
+	let report = check_roundtrip(value);
+
+	assert!(report.is_ok(), "value did not round-trip:\n{report}");
+
+	report
+}
+
+#[cfg(test)]
+mod test {
+	use super::check_roundtrip;
+	use crate::ContentType;
+
+	#[crate::apply(encode, decode)]
+	#[derive(Debug, PartialEq)]
+	struct Primitive(u32);
+
+	#[test]
+	fn test_report() {
+		let report = check_roundtrip(Primitive(7));
+
+		// BSON and TOML documents must be tables.
+		let failed = report
+			.failed()
+			.iter()
+			.map(|(content_type, _)| *content_type)
+			.collect::<Vec<_>>();
+
+		assert!(!report.is_ok());
+		assert_eq!(failed, [ContentType::Bson, ContentType::Toml]);
+		assert_eq!(report.passed().len(), ContentType::all().len() - 2);
+	}
+}
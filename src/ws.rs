@@ -0,0 +1,125 @@
+//! Encoding and decoding WebSocket messages with the same formats as
+//! [`Codec`].
+
+use axum::extract::ws::Message;
+
+use crate::{encode, Codec, CodecDecode, CodecEncode, CodecRejection, ContentType};
+
+/// Encodes a value as a WebSocket message in the given format.
+///
+/// Text formats (JSON, YAML, TOML and RON) produce [`Message::Text`] frames,
+/// and every other format produces [`Message::Binary`] frames.
+///
+/// ```edition2021
+/// # use axum::extract::ws::Message;
+/// # use axum_codec::{ws, ContentType};
+/// #
+/// #[axum_codec::apply(encode)]
+/// struct Greeting {
+///   hello: String,
+/// }
+///
+/// # fn main() {
+/// let message = ws::encode_message(Greeting { hello: "world".into() }, ContentType::Json).unwrap();
+///
+/// assert_eq!(message, Message::Text("{\"hello\":\"world\"}".into()));
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// See [`encode::Error`].
+pub fn encode_message<T>(value: T, content_type: ContentType) -> Result<Message, encode::Error>
+where
+	T: CodecEncode,
+{
+	let bytes = Codec(value).to_bytes(content_type)?;
+
+	if !content_type.is_text() {
+		return Ok(Message::Binary(bytes));
+	}
+
+	Ok(String::from_utf8(bytes).map_or_else(|e| Message::Binary(e.into_bytes()), Message::Text))
+}
+
+/// Decodes a WebSocket message in the given format, validating it if the
+/// `validator` feature is enabled.
+///
+/// Both text and binary frames are accepted regardless of the format. Control
+/// frames (ping, pong and close) carry no application data, so they return
+/// `Ok(None)` and can be skipped.
+///
+/// # Errors
+///
+/// See [`Codec::from_bytes`].
+pub fn decode_message<T>(
+	message: &Message,
+	content_type: ContentType,
+) -> Result<Option<T>, CodecRejection>
+where
+	T: CodecDecode,
+{
+	let bytes = match message {
+		Message::Text(text) => text.as_bytes(),
+		Message::Binary(bytes) => bytes,
+		Message::Ping(..) | Message::Pong(..) | Message::Close(..) => return Ok(None),
+	};
+
+	Codec::from_bytes(bytes, content_type).map(|Codec(data)| Some(data))
+}
+
+#[cfg(test)]
+mod test {
+	use axum::extract::ws::Message;
+
+	use super::{decode_message, encode_message};
+	use crate::ContentType;
+
+	#[crate::apply(encode, decode, crate = "crate")]
+	#[derive(Debug, PartialEq)]
+	struct Chat {
+		room: String,
+		text: String,
+	}
+
+	fn chat() -> Chat {
+		Chat {
+			room: "general".into(),
+			text: "hi".into(),
+		}
+	}
+
+	#[test]
+	fn test_message_frames() {
+		for content_type in ContentType::all() {
+			let message = encode_message(chat(), *content_type).unwrap();
+
+			assert_eq!(
+				matches!(message, Message::Text(..)),
+				content_type.is_text(),
+				"{content_type}"
+			);
+			assert_eq!(
+				decode_message::<Chat>(&message, *content_type).unwrap(),
+				Some(chat()),
+				"{content_type}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_control_frames() {
+		for message in [
+			Message::Ping(vec![1]),
+			Message::Pong(vec![1]),
+			Message::Close(None),
+		] {
+			assert_eq!(
+				decode_message::<Chat>(&message, ContentType::Json).unwrap(),
+				None
+			);
+		}
+
+		assert!(decode_message::<Chat>(&Message::Text("{}".into()), ContentType::Json).is_err());
+	}
+}
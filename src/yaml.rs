@@ -0,0 +1,12 @@
+//! The YAML backend, which is `serde_yaml` by default or its maintained fork
+//! `serde_yml` with the `yaml-serde-yml` feature. Both have the same API, so
+//! the rest of the crate only refers to this module.
+
+#[cfg(all(test, not(feature = "yaml-serde-yml")))]
+pub(crate) use serde_yaml::Value;
+#[cfg(not(feature = "yaml-serde-yml"))]
+pub(crate) use serde_yaml::{from_str, to_string, to_writer, Deserializer, Error};
+#[cfg(all(test, feature = "yaml-serde-yml"))]
+pub(crate) use serde_yml::Value;
+#[cfg(feature = "yaml-serde-yml")]
+pub(crate) use serde_yml::{from_str, to_string, to_writer, Deserializer, Error};